@@ -0,0 +1,315 @@
+use crate::app::data_base::create_record::CreateRecord;
+use crate::app::data_base::index::Index;
+use crate::app::data_base::record::Record;
+use crate::app::data_base::reservation::Skip;
+use crate::app::data_base::table::Table;
+use crate::app::data_base::DataBase;
+use crate::app::settings::insert_methods::{InsertMethods, InsertMethodsData, StartEnd};
+use crate::app::settings::Settings;
+use crate::app::Message;
+use iced::futures::channel::mpsc;
+use iced::futures::{SinkExt, StreamExt};
+use iced::{stream, Subscription};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::Read;
+use std::thread;
+use std::time::Duration;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum CreateMethod {
+    Start,
+    #[default]
+    End,
+    Index,
+    Fragments,
+}
+
+#[derive(Deserialize)]
+struct CreateRequest {
+    #[serde(default)]
+    method: CreateMethod,
+    index: Option<String>,
+    parent: Option<String>,
+    quantity: Option<u32>,
+    date: Option<String>,
+    #[serde(default)]
+    fields: HashMap<String, String>,
+    #[serde(default)]
+    replace: bool,
+    recorder: Option<String>,
+}
+
+/// Runs the HTTP server while the subscription is alive and reports every write
+/// and a port that can not be listened on to the GUI.
+/// With `lan` the server accepts the recorders of the local network.
+/// The GUI gets a sender to pass the settings and their changes to the server.
+pub fn subscription(port: u16, lan: bool) -> Subscription<Message> {
+    Subscription::run_with_id(
+        ("api", port, lan),
+        stream::channel(100, move |mut output| async move {
+            let (sender, mut receiver) = mpsc::unbounded();
+            let (settings_sender, settings_receiver) = std::sync::mpsc::channel();
+            let _ = output.send(Message::ApiStarted(settings_sender)).await;
+            thread::spawn(move || serve(port, lan, settings_receiver, sender));
+            while let Some(message) = receiver.next().await {
+                let _ = output.send(message).await;
+            }
+        }),
+    )
+}
+
+fn serve(
+    port: u16,
+    lan: bool,
+    settings_receiver: std::sync::mpsc::Receiver<Settings>,
+    sender: mpsc::UnboundedSender<Message>,
+) {
+    let Ok(mut settings) = settings_receiver.recv() else {
+        return;
+    };
+    let host = if lan { "0.0.0.0" } else { "127.0.0.1" };
+    let server = match Server::http((host, port)) {
+        Ok(server) => server,
+        Err(error) => {
+            let _ = sender.unbounded_send(Message::ApiFailed(error.to_string()));
+            return;
+        }
+    };
+
+    // the subscription drops the receiver when the API is switched off
+    while !sender.is_closed() {
+        if let Some(changed) = settings_receiver.try_iter().last() {
+            settings = changed;
+        }
+        match server.recv_timeout(Duration::from_millis(500)) {
+            Ok(Some(request)) => {
+                if handle(request, &settings) {
+                    let _ = sender.unbounded_send(Message::DataBaseChanged);
+                }
+            }
+            Ok(None) => {}
+            Err(_) => break,
+        }
+    }
+}
+
+/// Answers a request and returns whether the database was changed.
+fn handle(mut request: Request, settings: &Settings) -> bool {
+    let method = request.method().clone();
+    let url = request.url().to_string();
+    let path = url.split('?').next().unwrap_or("").trim_matches('/').to_string();
+    let segments = path.split('/').collect::<Vec<_>>();
+
+    let (status, body, is_changed) = match (&method, segments.as_slice()) {
+        (Method::Get, ["records"]) => (200, list(settings), false),
+        (Method::Get, ["records", index @ ..]) => get(settings, &index.join("/")),
+        (Method::Get, ["skips"]) => (200, skips(settings), false),
+        (Method::Get, ["next"]) => (200, next(settings), false),
+        (Method::Post, ["records"]) => {
+            let mut body = String::new();
+            match request.as_reader().read_to_string(&mut body) {
+                Ok(_) => create(settings, &body),
+                Err(error) => (400, error_json(error.to_string()), false),
+            }
+        }
+        _ => (404, error_json("Not found"), false),
+    };
+
+    let _ = request.respond(
+        Response::from_string(body.to_string())
+            .with_status_code(status)
+            .with_header(
+                Header::from_bytes("Content-Type", "application/json")
+                    .expect("Can not create header"),
+            ),
+    );
+    is_changed
+}
+
+fn error_json(error: impl ToString) -> Value {
+    json!({ "error": error.to_string() })
+}
+
+fn record_json(settings: &Settings, record: &Record) -> Value {
+    json!({
+        "row": record.row,
+        "index": settings.index_format.format(record.index),
+        "date": record.data.first().cloned().unwrap_or_default(),
+        "fields": settings
+            .fields
+            .iter()
+            .zip(record.data.iter().skip(1))
+            .map(|(field, value)| (field.name.clone(), Value::from(value.clone())))
+            .collect::<serde_json::Map<_, _>>(),
+        "audit": {
+            "recorder": record.audit.recorder,
+            "created": record.audit.created,
+            "modified": record.audit.modified,
+        },
+        "photos": record.photos,
+    })
+}
+
+fn list(settings: &Settings) -> Value {
+    Value::from(
+        DataBase::from(&*settings.path_to_db)
+            .get_sheet()
+            .get_records(settings.fields.len() + 1, &settings.index_format)
+            .iter()
+            .map(|record| record_json(settings, record))
+            .collect::<Vec<_>>(),
+    )
+}
+
+fn get(settings: &Settings, value: &str) -> (u16, Value, bool) {
+    let Some(index) = settings.index_format.parse(value) else {
+        return (400, error_json(format!("\"{}\" is not a valid index", value)), false);
+    };
+    let data_base = DataBase::from(&*settings.path_to_db);
+    let sheet = data_base.get_sheet();
+    match sheet
+        .get_row_index_from_index(index, &settings.index_format)
+        .and_then(|row| sheet.get_record(row, settings.fields.len() + 1, &settings.index_format))
+    {
+        Some(record) => (200, record_json(settings, &record), false),
+        None => (404, error_json(format!("The record {} does not exist", value)), false),
+    }
+}
+
+fn skips(settings: &Settings) -> Value {
+    let skips = DataBase::from(&*settings.path_to_db).skips(&settings.index_format);
+    json!({
+        "free": skips
+            .iter()
+            .filter_map(|skip| match skip {
+                Skip::Free(number) => Some(settings.index_format.format((*number).into())),
+                Skip::Reserved(_) => None,
+            })
+            .collect::<Vec<_>>(),
+        "reserved": skips
+            .iter()
+            .filter_map(|skip| match skip {
+                Skip::Reserved(reservation) => Some(json!({
+                    "start": reservation.start,
+                    "end": reservation.end,
+                    "team": reservation.team,
+                })),
+                Skip::Free(_) => None,
+            })
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn next(settings: &Settings) -> Value {
+    let data_base = DataBase::from(&*settings.path_to_db);
+    let next = data_base.get_sheet().get_next_end_indices(
+        1,
+        &settings.index_format,
+        &data_base.get_reservations(),
+    );
+    json!({ "index": settings.index_format.format(Index::from(next[0])) })
+}
+
+fn create(settings: &Settings, body: &str) -> (u16, Value, bool) {
+    let request = match serde_json::from_str::<CreateRequest>(body) {
+        Ok(request) => request,
+        Err(error) => return (400, error_json(error), false),
+    };
+    let data = match settings.new_record_data(
+        request.date,
+        request
+            .fields
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str())),
+    ) {
+        Ok(data) => data,
+        Err(error) => return (400, error_json(error), false),
+    };
+
+    let mut insert_methods_data = InsertMethodsData {
+        sorted_insert: settings.insert_methods_data.sorted_insert,
+        ..InsertMethodsData::default()
+    };
+    match request.method {
+        CreateMethod::Start => insert_methods_data.start_end = StartEnd::Start,
+        CreateMethod::End => insert_methods_data.start_end = StartEnd::End,
+        CreateMethod::Index => {
+            let Some(index) = request
+                .index
+                .as_deref()
+                .and_then(|index| settings.index_format.parse(index))
+            else {
+                return (400, error_json("A valid \"index\" is required"), false);
+            };
+            insert_methods_data.insert_methods = InsertMethods::Input;
+            insert_methods_data.input = index;
+        }
+        CreateMethod::Fragments => {
+            let Some(parent) = request
+                .parent
+                .as_deref()
+                .and_then(|parent| settings.index_format.parse_parent(parent))
+            else {
+                return (400, error_json("A valid \"parent\" is required"), false);
+            };
+            insert_methods_data.insert_methods = InsertMethods::Fragments;
+            insert_methods_data.parent = parent;
+        }
+    }
+
+    // indices are allocated here, one request at a time, so clients never get the same number
+    let _lock = DataBase::lock();
+    let quantity = request.quantity.unwrap_or(1);
+    let data_base = DataBase::from(&*settings.path_to_db);
+    let language = &settings.current_language;
+    if let Err(error) = data_base
+        .check_context(&data, &settings.fields, &settings.contexts, language)
+        .and_then(|_| {
+            data_base.check_references(&data, &settings.fields, &settings.index_format, language)
+        })
+    {
+        return (400, error_json(error), false);
+    }
+    if let Err(error) = data_base.check_create(
+        quantity,
+        &insert_methods_data,
+        request.replace,
+        &settings.index_format,
+        &settings.current_language,
+    ) {
+        return (409, error_json(error), false);
+    }
+
+    let created = DataBase::create_record(
+        &settings.path_to_db,
+        quantity,
+        data,
+        &settings.fields,
+        &insert_methods_data,
+        &settings.index_format,
+        // a client recording over the network signs its records with its own name
+        settings
+            .audit_recorder()
+            .map(|recorder| {
+                request
+                    .recorder
+                    .as_deref()
+                    .filter(|name| !name.is_empty())
+                    .unwrap_or(recorder)
+            }),
+    );
+    (
+        201,
+        json!({
+            "created": created
+                .into_iter()
+                .map(|index| settings.index_format.format(index))
+                .collect::<Vec<_>>()
+        }),
+        true,
+    )
+}
@@ -0,0 +1,87 @@
+use crate::app::data_base::photos::is_image;
+use crate::app::Message;
+use iced::futures::channel::mpsc;
+use iced::futures::{SinkExt, StreamExt};
+use iced::{stream, Subscription};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// Size and modification time of a file, they stop changing once the camera has written it.
+type Stamp = (u64, Option<SystemTime>);
+
+/// Images of the folder with their stamps.
+fn images(folder: &Path) -> Vec<(PathBuf, Stamp)> {
+    let Ok(entries) = fs::read_dir(folder) else {
+        return vec![];
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && is_image(path))
+        .filter_map(|path| {
+            let metadata = fs::metadata(&path).ok()?;
+            Some((path, (metadata.len(), metadata.modified().ok())))
+        })
+        .collect()
+}
+
+/// Images of the folder not reported yet that are unchanged since the last poll, oldest first.
+fn new_images(
+    folder: &Path,
+    seen: &mut HashSet<PathBuf>,
+    pending: &mut HashMap<PathBuf, Stamp>,
+) -> Vec<PathBuf> {
+    let mut ready = vec![];
+    let mut still_pending = HashMap::new();
+    for (path, stamp) in images(folder) {
+        if seen.contains(&path) {
+            continue;
+        }
+        if pending.get(&path) == Some(&stamp) {
+            ready.push((stamp.1, path));
+        } else {
+            still_pending.insert(path, stamp);
+        }
+    }
+    *pending = still_pending;
+    ready.sort();
+    ready
+        .into_iter()
+        .map(|(_, path)| {
+            seen.insert(path.clone());
+            path
+        })
+        .collect()
+}
+
+/// Watches the camera folder and reports the images that appear in it.
+pub fn subscription(folder: PathBuf) -> Subscription<Message> {
+    Subscription::run_with_id(
+        ("camera", folder.clone()),
+        stream::channel(100, move |mut output| async move {
+            let (sender, mut receiver) = mpsc::unbounded();
+            thread::spawn(move || {
+                // the images already in the folder were taken before the ingest was switched on
+                let mut seen = images(&folder)
+                    .into_iter()
+                    .map(|(path, _)| path)
+                    .collect::<HashSet<_>>();
+                let mut pending = HashMap::new();
+                // the subscription drops the receiver when the ingest is switched off
+                while !sender.is_closed() {
+                    let images = new_images(&folder, &mut seen, &mut pending);
+                    if !images.is_empty() && sender.unbounded_send(images).is_err() {
+                        break;
+                    }
+                    thread::sleep(Duration::from_secs(2));
+                }
+            });
+            while let Some(images) = receiver.next().await {
+                let _ = output.send(Message::CameraFiles(images)).await;
+            }
+        }),
+    )
+}
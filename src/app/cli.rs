@@ -0,0 +1,202 @@
+use crate::app::data_base::export::Export;
+use crate::app::data_base::DataBase;
+use crate::app::settings::insert_methods::{InsertMethods, InsertMethodsData, StartEnd};
+use crate::app::settings::Settings;
+
+const USAGE: &str = "Usage:
+    Archaeological_Assistant add [--start] [--quantity N] [--date DATE] [--field NAME=VALUE]...
+    Archaeological_Assistant insert --index INDEX [--replace] [--sorted] [--date DATE] [--field NAME=VALUE]...
+    Archaeological_Assistant fragments --parent INDEX [--quantity N]
+    Archaeological_Assistant skips
+    Archaeological_Assistant export [--output FILE]
+
+Without a command the graphical interface is started.";
+
+const FLAGS: [&str; 3] = ["start", "replace", "sorted"];
+
+struct Arguments {
+    flags: Vec<String>,
+    options: Vec<(String, String)>,
+}
+
+impl Arguments {
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let mut result = Self {
+            flags: vec![],
+            options: vec![],
+        };
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let Some(name) = arg.strip_prefix("--") else {
+                return Err(format!("unexpected argument \"{}\"", arg));
+            };
+            if FLAGS.contains(&name) {
+                result.flags.push(name.to_string());
+            } else {
+                let value = args.next().ok_or(format!("--{} needs a value", name))?;
+                result.options.push((name.to_string(), value.clone()));
+            }
+        }
+        Ok(result)
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|flag| flag == name)
+    }
+
+    fn options(&self, name: &str) -> Vec<&str> {
+        self.options
+            .iter()
+            .filter(|(option, _)| option == name)
+            .map(|(_, value)| value.as_str())
+            .collect()
+    }
+
+    fn option(&self, name: &str) -> Option<&str> {
+        self.options(name).last().copied()
+    }
+
+    fn number(&self, name: &str, default: u32) -> Result<u32, String> {
+        match self.option(name) {
+            Some(value) => value
+                .parse()
+                .map_err(|_| format!("--{} expects a number, got \"{}\"", name, value)),
+            None => Ok(default),
+        }
+    }
+}
+
+pub fn run(args: Vec<String>) -> Result<(), String> {
+    let Some((command, args)) = args.split_first() else {
+        return Err(USAGE.to_string());
+    };
+    let arguments = Arguments::parse(args)?;
+    let settings = Settings::load();
+
+    match command.as_str() {
+        "add" => create(
+            &settings,
+            &arguments,
+            InsertMethodsData {
+                insert_methods: InsertMethods::StartEnd,
+                start_end: if arguments.flag("start") {
+                    StartEnd::Start
+                } else {
+                    StartEnd::End
+                },
+                ..InsertMethodsData::default()
+            },
+        ),
+        "insert" => {
+            let value = arguments.option("index").ok_or("--index is required")?;
+            let index = settings
+                .index_format
+                .parse(value)
+                .ok_or(format!("\"{}\" is not a valid index", value))?;
+            create(
+                &settings,
+                &arguments,
+                InsertMethodsData {
+                    insert_methods: InsertMethods::Input,
+                    input: index,
+                    sorted_insert: arguments.flag("sorted")
+                        || settings.insert_methods_data.sorted_insert,
+                    ..InsertMethodsData::default()
+                },
+            )
+        }
+        "fragments" => {
+            let value = arguments.option("parent").ok_or("--parent is required")?;
+            let parent = settings
+                .index_format
+                .parse_parent(value)
+                .ok_or(format!("\"{}\" is not a valid parent index", value))?;
+            create(
+                &settings,
+                &arguments,
+                InsertMethodsData {
+                    insert_methods: InsertMethods::Fragments,
+                    parent,
+                    ..InsertMethodsData::default()
+                },
+            )
+        }
+        "skips" => {
+            for skip in DataBase::from(&*settings.path_to_db).skips(&settings.index_format) {
+                println!("{}", skip.text(&settings.current_language));
+            }
+            Ok(())
+        }
+        "export" => {
+            let csv = DataBase::from(&*settings.path_to_db)
+                .get_sheet()
+                .to_csv(&settings.fields, &settings.index_format);
+            match arguments.option("output") {
+                Some(path) => std::fs::write(path, csv).map_err(|error| error.to_string()),
+                None => {
+                    print!("{}", csv);
+                    Ok(())
+                }
+            }
+        }
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        _ => Err(format!("Unknown command \"{}\"\n\n{}", command, USAGE)),
+    }
+}
+
+fn record_data(settings: &Settings, arguments: &Arguments) -> Result<Vec<String>, String> {
+    let fields = arguments
+        .options("field")
+        .into_iter()
+        .map(|field| {
+            field
+                .split_once('=')
+                .ok_or(format!("--field expects NAME=VALUE, got \"{}\"", field))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    settings.new_record_data(arguments.option("date").map(str::to_string), fields)
+}
+
+fn create(
+    settings: &Settings,
+    arguments: &Arguments,
+    insert_methods_data: InsertMethodsData,
+) -> Result<(), String> {
+    let quantity = arguments.number("quantity", 1)?;
+    let data = record_data(settings, arguments)?;
+    let data_base = DataBase::from(&*settings.path_to_db);
+    let language = &settings.current_language;
+    data_base.check_context(&data, &settings.fields, &settings.contexts, language)?;
+    data_base.check_references(&data, &settings.fields, &settings.index_format, language)?;
+    data_base
+        .check_create(
+            quantity,
+            &insert_methods_data,
+            arguments.flag("replace"),
+            &settings.index_format,
+            &settings.current_language,
+        )
+        .map_err(|error| {
+            if insert_methods_data.insert_methods == InsertMethods::Input {
+                format!("{}, use --replace to overwrite it", error)
+            } else {
+                error
+            }
+        })?;
+
+    for index in DataBase::create_record(
+        &settings.path_to_db,
+        quantity,
+        data,
+        &settings.fields,
+        &insert_methods_data,
+        &settings.index_format,
+        settings.audit_recorder(),
+    ) {
+        println!("{}", settings.index_format.format(index));
+    }
+    Ok(())
+}
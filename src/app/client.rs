@@ -0,0 +1,233 @@
+use crate::app::data_base::audit::Audit;
+use crate::app::data_base::index::Index;
+use crate::app::data_base::record::Record;
+use crate::app::data_base::reservation::{Reservation, Skip};
+use crate::app::settings::insert_methods::{InsertMethods, StartEnd};
+use crate::app::settings::Settings;
+use crate::app::Message;
+use iced::futures::channel::{mpsc, oneshot};
+use iced::futures::{SinkExt, StreamExt};
+use iced::{stream, Subscription};
+use serde_json::{json, Value};
+use std::thread;
+use std::time::Duration;
+
+/// Runs requests on their own thread, so waiting for the server does not block the GUI.
+pub async fn run<T: Send + 'static>(
+    request: impl FnOnce() -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    let (sender, receiver) = oneshot::channel();
+    thread::spawn(move || {
+        let _ = sender.send(request());
+    });
+    receiver
+        .await
+        .unwrap_or_else(|_| Err("The request was cancelled".to_string()))
+}
+
+/// Requests fail instead of hanging when the server is gone.
+fn agent() -> ureq::Agent {
+    ureq::AgentBuilder::new()
+        .timeout_connect(Duration::from_secs(3))
+        .timeout_read(Duration::from_secs(10))
+        .build()
+}
+
+/// Sends a request to the server and returns the answer, turning the `error` of a failed request into a message.
+fn call(request: ureq::Request, body: Option<Value>) -> Result<Value, String> {
+    send(request, body).map_err(|(_, error)| error)
+}
+
+/// Like `call`, with the status code of a request the server refused.
+fn send(request: ureq::Request, body: Option<Value>) -> Result<Value, (Option<u16>, String)> {
+    let response = match body {
+        Some(body) => request
+            .set("Content-Type", "application/json")
+            .send_string(&body.to_string()),
+        None => request.call(),
+    };
+    let text = match response {
+        Ok(response) => response
+            .into_string()
+            .map_err(|error| (None, error.to_string()))?,
+        Err(ureq::Error::Status(status, response)) => {
+            let text = response
+                .into_string()
+                .map_err(|error| (Some(status), error.to_string()))?;
+            return Err((
+                Some(status),
+                serde_json::from_str::<Value>(&text)
+                    .ok()
+                    .and_then(|value| value["error"].as_str().map(str::to_string))
+                    .unwrap_or(text),
+            ));
+        }
+        Err(error) => return Err((None, format!("The server is not available: {}", error))),
+    };
+    serde_json::from_str(&text).map_err(|error| (None, error.to_string()))
+}
+
+fn url(settings: &Settings, path: &str) -> String {
+    let address = settings.api.server_address.trim().trim_end_matches('/');
+    if address.starts_with("http://") || address.starts_with("https://") {
+        format!("{}/{}", address, path)
+    } else {
+        format!("http://{}/{}", address, path)
+    }
+}
+
+/// Asks the server to create the records, the server allocates the indices.
+pub fn create(
+    settings: &Settings,
+    quantity: u32,
+    data: &[String],
+    replace: bool,
+) -> Result<Vec<String>, String> {
+    let insert_methods_data = &settings.insert_methods_data;
+    let mut body = json!({
+        "quantity": quantity,
+        "date": data.first().cloned().unwrap_or_default(),
+        "fields": settings
+            .fields
+            .iter()
+            .zip(data.iter().skip(1))
+            .map(|(field, value)| (field.name.clone(), Value::from(value.clone())))
+            .collect::<serde_json::Map<_, _>>(),
+        "replace": replace,
+        "recorder": settings.recorder,
+    });
+    match insert_methods_data.insert_methods {
+        InsertMethods::StartEnd => {
+            body["method"] = Value::from(match insert_methods_data.start_end {
+                StartEnd::Start => "start",
+                StartEnd::End => "end",
+            })
+        }
+        InsertMethods::Input => {
+            body["method"] = Value::from("index");
+            body["index"] = Value::from(settings.index_format.format(insert_methods_data.input));
+        }
+        InsertMethods::AutoInsert => {
+            body["method"] = Value::from("index");
+            body["index"] = Value::from(
+                settings
+                    .index_format
+                    .format(insert_methods_data.auto_insert.into()),
+            );
+        }
+        InsertMethods::Fragments => {
+            body["method"] = Value::from("fragments");
+            body["parent"] = Value::from(
+                settings
+                    .index_format
+                    .format(insert_methods_data.parent.into()),
+            );
+        }
+    }
+
+    let answer = call(agent().post(&url(settings, "records")), Some(body))?;
+    Ok(answer["created"]
+        .as_array()
+        .map(|created| {
+            created
+                .iter()
+                .filter_map(|index| index.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+fn record_from_json(settings: &Settings, value: &Value) -> Option<Record> {
+    Some(Record {
+        row: value["row"].as_u64()? as u32,
+        index: settings.index_format.parse(value["index"].as_str()?)?,
+        data: [vec![value["date"].as_str().unwrap_or_default().to_string()], {
+            settings
+                .fields
+                .iter()
+                .map(|field| {
+                    value["fields"][&field.name]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string()
+                })
+                .collect()
+        }]
+        .concat(),
+        audit: Audit {
+            recorder: value["audit"]["recorder"].as_str().unwrap_or_default().to_string(),
+            created: value["audit"]["created"].as_str().unwrap_or_default().to_string(),
+            modified: value["audit"]["modified"].as_str().unwrap_or_default().to_string(),
+        },
+        photos: value["photos"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|photo| photo.as_str().map(str::to_string))
+            .collect(),
+    })
+}
+
+pub fn records(settings: &Settings) -> Result<Vec<Record>, String> {
+    let answer = call(agent().get(&url(settings, "records")), None)?;
+    Ok(answer
+        .as_array()
+        .map(|records| {
+            records
+                .iter()
+                .filter_map(|record| record_from_json(settings, record))
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// The record with an index, `None` if the server does not have it.
+pub fn record(settings: &Settings, index: Index) -> Result<Option<Record>, String> {
+    let path = format!("records/{}", settings.index_format.format(index));
+    match send(agent().get(&url(settings, &path)), None) {
+        Ok(record) => Ok(record_from_json(settings, &record)),
+        Err((Some(404), _)) => Ok(None),
+        Err((_, error)) => Err(error),
+    }
+}
+
+pub fn skips(settings: &Settings) -> Result<Vec<Skip>, String> {
+    let answer = call(agent().get(&url(settings, "skips")), None)?;
+    let free = answer["free"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|index| settings.index_format.parse(index.as_str()?))
+        .map(|index| Skip::Free(index.number));
+    let reserved = answer["reserved"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|reservation| {
+            Some(Skip::Reserved(Reservation {
+                start: reservation["start"].as_u64()? as u32,
+                end: reservation["end"].as_u64()? as u32,
+                team: reservation["team"].as_str().unwrap_or_default().to_string(),
+            }))
+        });
+    Ok(free.chain(reserved).collect())
+}
+
+/// Asks the GUI to reload the data of the server every few seconds.
+pub fn subscription(address: String) -> Subscription<Message> {
+    Subscription::run_with_id(
+        ("client", address),
+        stream::channel(100, move |mut output| async move {
+            let (sender, mut receiver) = mpsc::unbounded();
+            thread::spawn(move || {
+                // the subscription drops the receiver when the client mode is switched off
+                while sender.unbounded_send(()).is_ok() {
+                    thread::sleep(Duration::from_secs(5));
+                }
+            });
+            while receiver.next().await.is_some() {
+                let _ = output.send(Message::DataBaseChanged).await;
+            }
+        }),
+    )
+}
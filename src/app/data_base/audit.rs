@@ -0,0 +1,115 @@
+use umya_spreadsheet::Worksheet;
+
+/// First of the hidden audit columns, far enough right to stay clear of the fields.
+pub const AUDIT_COLUMN: u32 = 100;
+
+/// Most fields a record can have, so that its columns end before the audit ones.
+pub const MAX_FIELDS: usize = AUDIT_COLUMN as usize - 3;
+
+/// Who recorded a record and when it was created and last changed.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Audit {
+    pub recorder: String,
+    pub created: String,
+    pub modified: String,
+}
+
+pub fn now() -> String {
+    chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+impl Audit {
+    pub fn is_empty(&self) -> bool {
+        self.recorder.is_empty() && self.created.is_empty() && self.modified.is_empty()
+    }
+}
+
+pub trait AuditTrail {
+    fn get_audit(&self, row_num: u32) -> Audit;
+
+    fn set_audit(&mut self, row_num: u32, audit: &Audit);
+
+    /// Updates the audit columns of a record that was just created or replaced.
+    fn stamp(&mut self, row_num: u32, recorder: &str, is_created: bool);
+}
+
+impl AuditTrail for Worksheet {
+    fn get_audit(&self, row_num: u32) -> Audit {
+        Audit {
+            recorder: self.get_value((AUDIT_COLUMN, row_num)),
+            created: self.get_value((AUDIT_COLUMN + 1, row_num)),
+            modified: self.get_value((AUDIT_COLUMN + 2, row_num)),
+        }
+    }
+
+    fn set_audit(&mut self, row_num: u32, audit: &Audit) {
+        if audit.is_empty() {
+            return;
+        }
+        for (offset, value) in [&audit.recorder, &audit.created, &audit.modified]
+            .into_iter()
+            .enumerate()
+        {
+            let col_num = AUDIT_COLUMN + offset as u32;
+            self.get_cell_mut((col_num, row_num)).set_value_string(value);
+            self.get_column_dimension_by_number_mut(&col_num).set_hidden(true);
+        }
+    }
+
+    fn stamp(&mut self, row_num: u32, recorder: &str, is_created: bool) {
+        let mut audit = self.get_audit(row_num);
+        let time = now();
+        if is_created || audit.created.is_empty() {
+            audit.recorder = recorder.to_string();
+            audit.created = time.clone();
+        }
+        audit.modified = time;
+        self.set_audit(row_num, &audit);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Audit, AuditTrail, MAX_FIELDS};
+    use crate::app::data_base::table::{test::FromMatrix, Table};
+    use crate::app::settings::index_format::IndexFormat;
+    use umya_spreadsheet::new_file;
+
+    #[test]
+    fn stamp() {
+        let mut book = new_file();
+        let test_sheet = book.matrix_to_sheet(vec![vec!["1", "01.07", "pot"]]);
+        assert_eq!(Audit::default(), test_sheet.get_audit(1));
+
+        test_sheet.stamp(1, "Anna", true);
+        let created = test_sheet.get_audit(1);
+        assert_eq!("Anna", created.recorder);
+        assert!(!created.created.is_empty());
+        assert_eq!(created.created, created.modified);
+
+        test_sheet.stamp(1, "Boris", false);
+        assert_eq!("Anna", test_sheet.get_audit(1).recorder);
+        assert_eq!(created.created, test_sheet.get_audit(1).created);
+
+        // the audit columns are not part of the record data
+        assert_eq!(vec!["01.07", "pot"], test_sheet.get_row_data(1));
+    }
+
+    #[test]
+    fn set_record() {
+        let mut book = new_file();
+        let test_sheet = book.matrix_to_sheet(vec![vec!["1", "01.07", "pot"]]);
+        let index_format = IndexFormat::default();
+        let index = index_format.parse("1").unwrap();
+        let mut data = vec!["02.07".to_string()];
+        data.resize(MAX_FIELDS + 1, "bone".to_string());
+
+        test_sheet.set_record(1, index, data.clone(), &index_format, Some("Anna"));
+        assert_eq!("Anna", test_sheet.get_audit(1).recorder);
+        // the most fields a record can have still end before the audit columns
+        assert_eq!(data, test_sheet.get_row_data(1));
+
+        test_sheet.set_record(2, index, data, &index_format, None);
+        assert_eq!(Audit::default(), test_sheet.get_audit(2));
+    }
+}
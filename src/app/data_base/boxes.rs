@@ -0,0 +1,256 @@
+use crate::app::data_base::audit::now;
+use crate::app::data_base::index::Index;
+use crate::app::data_base::meta_data::{get_keyed_rows, set_keyed_rows};
+use crate::app::data_base::table::Table;
+use crate::app::data_base::DataBase;
+use crate::app::settings::index_format::IndexFormat;
+use crate::app::settings::Field;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+/// Sheet holding the boxes (`box | id | location | description`)
+/// and the moves of the finds between them (`move | index | from | to | time | recorder`).
+/// The indices are written formatted, so the sheet can be read as it is.
+pub const BOXES_SHEET: &str = "boxes";
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StorageBox {
+    pub id: String,
+    /// The shelf or room the box is kept in.
+    pub location: String,
+    pub description: String,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Move {
+    pub index: Index,
+    /// Empty when the find was not in a box.
+    pub from: String,
+    pub to: String,
+    pub time: String,
+    pub recorder: String,
+}
+
+impl StorageBox {
+    pub fn from_row(row: &[String]) -> Option<Self> {
+        Some(Self {
+            id: row.first().filter(|id| !id.is_empty())?.clone(),
+            location: row.get(1).cloned().unwrap_or_default(),
+            description: row.get(2).cloned().unwrap_or_default(),
+        })
+    }
+
+    pub fn to_row(&self) -> Vec<String> {
+        vec![self.id.clone(), self.location.clone(), self.description.clone()]
+    }
+
+    /// The lines printed on the box label.
+    pub fn label_lines(&self) -> Vec<String> {
+        [&self.id, &self.location, &self.description]
+            .into_iter()
+            .filter(|line| !line.is_empty())
+            .cloned()
+            .collect()
+    }
+}
+
+impl Display for StorageBox {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.id)?;
+        if !self.location.is_empty() {
+            write!(f, " ({})", self.location)?;
+        }
+        if !self.description.is_empty() {
+            write!(f, " {}", self.description)?;
+        }
+        Ok(())
+    }
+}
+
+impl Move {
+    pub fn from_row(row: &[String], index_format: &IndexFormat) -> Option<Self> {
+        Some(Self {
+            index: index_format.parse(row.first()?)?,
+            from: row.get(1).cloned().unwrap_or_default(),
+            to: row.get(2).cloned().unwrap_or_default(),
+            time: row.get(3).cloned().unwrap_or_default(),
+            recorder: row.get(4).cloned().unwrap_or_default(),
+        })
+    }
+
+    pub fn to_row(&self, index_format: &IndexFormat) -> Vec<String> {
+        vec![
+            index_format.format(self.index),
+            self.from.clone(),
+            self.to.clone(),
+            self.time.clone(),
+            self.recorder.clone(),
+        ]
+    }
+}
+
+impl DataBase {
+    pub fn get_boxes(&self) -> Vec<StorageBox> {
+        get_keyed_rows(&self.book, BOXES_SHEET, "box")
+            .iter()
+            .filter_map(|row| StorageBox::from_row(row))
+            .collect()
+    }
+
+    pub fn set_boxes(&mut self, boxes: &[StorageBox]) {
+        set_keyed_rows(
+            &mut self.book,
+            BOXES_SHEET,
+            "box",
+            boxes.iter().map(StorageBox::to_row).collect(),
+            false,
+        )
+    }
+
+    pub fn get_moves(&self, index_format: &IndexFormat) -> Vec<Move> {
+        get_keyed_rows(&self.book, BOXES_SHEET, "move")
+            .iter()
+            .filter_map(|row| Move::from_row(row, index_format))
+            .collect()
+    }
+
+    /// The box each find in a box is in now, the last move decides.
+    fn current_boxes(&self, index_format: &IndexFormat) -> HashMap<Index, String> {
+        let mut boxes = self
+            .get_moves(index_format)
+            .into_iter()
+            .map(|other| (other.index, other.to))
+            .collect::<HashMap<_, _>>();
+        boxes.retain(|_, to| !to.is_empty());
+        boxes
+    }
+
+    /// The box a find is in now.
+    pub fn box_of(&self, index: Index, index_format: &IndexFormat) -> Option<String> {
+        self.current_boxes(index_format).remove(&index)
+    }
+
+    /// Puts the finds into a box, an empty `to` takes them out of their boxes.
+    /// Finds already in the box, or listed twice, are not moved again.
+    pub fn move_to_box(
+        &mut self,
+        indices: &[Index],
+        to: &str,
+        index_format: &IndexFormat,
+        recorder: &str,
+    ) -> Vec<Move> {
+        let mut moves = self.get_moves(index_format);
+        let mut current = self.current_boxes(index_format);
+        let time = now();
+        let new_moves = indices
+            .iter()
+            .filter_map(|&index| {
+                let from = current.insert(index, to.to_string()).unwrap_or_default();
+                (from != to).then(|| Move {
+                    index,
+                    from,
+                    to: to.to_string(),
+                    time: time.clone(),
+                    recorder: recorder.to_string(),
+                })
+            })
+            .collect::<Vec<_>>();
+        moves.extend(new_moves.iter().cloned());
+        set_keyed_rows(
+            &mut self.book,
+            BOXES_SHEET,
+            "move",
+            moves.iter().map(|other| other.to_row(index_format)).collect(),
+            false,
+        );
+        new_moves
+    }
+
+    /// The finds in a box now.
+    pub fn box_contents(&self, id: &str, index_format: &IndexFormat) -> Vec<Index> {
+        let mut contents = self
+            .current_boxes(index_format)
+            .into_iter()
+            .filter(|(_, to)| to == id)
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+        contents.sort();
+        contents
+    }
+
+    /// The contents of a box as rows for the CSV export, the header first.
+    pub fn box_contents_rows(
+        &self,
+        id: &str,
+        fields: &[Field],
+        index_format: &IndexFormat,
+    ) -> Vec<Vec<String>> {
+        let sheet = self.get_sheet();
+        let header = ["box".to_string(), "index".to_string(), "date".to_string()]
+            .into_iter()
+            .chain(fields.iter().map(|field| field.name.clone()))
+            .collect::<Vec<_>>();
+        let rows = self
+            .box_contents(id, index_format)
+            .into_iter()
+            .map(|index| {
+                let data = sheet
+                    .get_row_index_from_index(index, index_format)
+                    .and_then(|row| sheet.get_record(row, fields.len() + 1, index_format))
+                    .map(|record| record.data)
+                    .unwrap_or_default();
+                [vec![id.to_string(), index_format.format(index)], data].concat()
+            });
+        [header].into_iter().chain(rows).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::data_base::boxes::StorageBox;
+    use crate::app::data_base::index::Index;
+    use crate::app::data_base::DataBase;
+    use crate::app::settings::index_format::IndexFormat;
+    use umya_spreadsheet::new_file;
+
+    #[test]
+    fn move_to_box() {
+        let format = IndexFormat::default();
+        let mut data_base = DataBase { book: new_file() };
+        let boxes = vec![
+            StorageBox {
+                id: "B1".to_string(),
+                location: "shelf 2".to_string(),
+                description: "".to_string(),
+            },
+            StorageBox {
+                id: "B2".to_string(),
+                location: "".to_string(),
+                description: "bones".to_string(),
+            },
+        ];
+        data_base.set_boxes(&boxes);
+        assert_eq!(boxes, data_base.get_boxes());
+
+        let indices = [Index::from(1), Index::new(2, Some(1)), Index::from(3)];
+        assert_eq!(3, data_base.move_to_box(&indices, "B1", &format, "").len());
+        let moves = data_base.move_to_box(&[Index::from(3), Index::from(1)], "B2", &format, "Anna");
+        assert_eq!(1, data_base.move_to_box(&[Index::from(3)], "B1", &format, "").len());
+        // a find listed twice is moved once
+        assert_eq!(
+            1,
+            data_base.move_to_box(&[Index::from(4), Index::from(4)], "B2", &format, "").len()
+        );
+        assert_eq!("B2", data_base.move_to_box(&[Index::from(4)], "", &format, "")[0].from);
+
+        assert_eq!("B1", moves[0].from);
+        assert_eq!(Some("B1".to_string()), data_base.box_of(Index::from(3), &format));
+        assert_eq!(
+            vec![Index::new(2, Some(1)), Index::from(3)],
+            data_base.box_contents("B1", &format)
+        );
+        assert_eq!(vec![Index::from(1)], data_base.box_contents("B2", &format));
+        assert_eq!(8, data_base.get_moves(&format).len());
+        assert_eq!(boxes, data_base.get_boxes());
+    }
+}
@@ -0,0 +1,363 @@
+use crate::app::data_base::record::Record;
+use crate::app::data_base::report::fill;
+use crate::app::settings::catalogue_settings::CatalogueSettings;
+use crate::app::settings::index_format::IndexFormat;
+use crate::app::settings::Field;
+use std::fmt::{Display, Formatter};
+use std::io::{Cursor, Write};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CatalogueFormat {
+    Markdown,
+    LaTeX,
+    Docx,
+    Odt,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Condition {
+    Equals,
+    NotEquals,
+    /// Case-insensitive substring.
+    Contains,
+}
+
+/// One condition of the catalogue filter, e.g. `Square=A1`, `Status!=lost` or `Name~pot`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Filter {
+    /// Position in the record data, 0 is the date.
+    pub data_id: usize,
+    pub condition: Condition,
+    pub value: String,
+}
+
+impl CatalogueFormat {
+    pub const ALL: [CatalogueFormat; 4] = [
+        CatalogueFormat::Docx,
+        CatalogueFormat::Odt,
+        CatalogueFormat::Markdown,
+        CatalogueFormat::LaTeX,
+    ];
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            CatalogueFormat::Markdown => "md",
+            CatalogueFormat::LaTeX => "tex",
+            CatalogueFormat::Docx => "docx",
+            CatalogueFormat::Odt => "odt",
+        }
+    }
+}
+
+impl Display for CatalogueFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CatalogueFormat::Markdown => write!(f, "Markdown"),
+            CatalogueFormat::LaTeX => write!(f, "LaTeX"),
+            CatalogueFormat::Docx => write!(f, "DOCX"),
+            CatalogueFormat::Odt => write!(f, "ODT"),
+        }
+    }
+}
+
+/// The position of "Date" or a field in the record data.
+fn data_id(name: &str, fields: &[Field]) -> Option<usize> {
+    if name.eq_ignore_ascii_case("date") {
+        Some(0)
+    } else {
+        fields
+            .iter()
+            .position(|field| field.name.eq_ignore_ascii_case(name))
+            .map(|id| id + 1)
+    }
+}
+
+impl Filter {
+    /// Parses conditions separated by `;`, an empty text keeps every record.
+    pub fn parse_all(text: &str, fields: &[Field]) -> Result<Vec<Filter>, String> {
+        text.split(';')
+            .map(str::trim)
+            .filter(|condition| !condition.is_empty())
+            .map(|condition| {
+                let (name, condition_type, value) = [
+                    ("!=", Condition::NotEquals),
+                    ("=", Condition::Equals),
+                    ("~", Condition::Contains),
+                ]
+                .into_iter()
+                .find_map(|(operator, condition_type)| {
+                    let (name, value) = condition.split_once(operator)?;
+                    Some((name, condition_type, value))
+                })
+                .ok_or(format!("\"{}\" is not a condition", condition))?;
+                Ok(Filter {
+                    data_id: data_id(name.trim(), fields)
+                        .ok_or(format!("Unknown field \"{}\"", name.trim()))?,
+                    condition: condition_type,
+                    value: value.trim().to_string(),
+                })
+            })
+            .collect()
+    }
+
+    pub fn matches(&self, record: &Record) -> bool {
+        let value = record
+            .data
+            .get(self.data_id)
+            .map(|value| value.trim())
+            .unwrap_or_default();
+        match self.condition {
+            Condition::Equals => value == self.value,
+            Condition::NotEquals => value != self.value,
+            Condition::Contains => value.to_lowercase().contains(&self.value.to_lowercase()),
+        }
+    }
+}
+
+/// The catalogue entries of the records passing the filter, in the chosen order.
+pub fn catalogue_entries(
+    records: Vec<Record>,
+    fields: &[Field],
+    settings: &CatalogueSettings,
+    index_format: &IndexFormat,
+) -> Result<Vec<String>, String> {
+    let filters = Filter::parse_all(&settings.filter, fields)?;
+    let mut records = records
+        .into_iter()
+        .filter(|record| filters.iter().all(|filter| filter.matches(record)))
+        .collect::<Vec<_>>();
+    if !settings.sort_by.eq_ignore_ascii_case("index") {
+        let sort_id = data_id(&settings.sort_by, fields)
+            .ok_or(format!("Unknown field \"{}\"", settings.sort_by))?;
+        // stable, so records with the same value stay in the order of the indices
+        records.sort_by(|record1, record2| record1.data.get(sort_id).cmp(&record2.data.get(sort_id)));
+    }
+
+    Ok(records
+        .iter()
+        .map(|record| {
+            let values = [
+                ("index", index_format.format(record.index)),
+                ("date", record.data.first().cloned().unwrap_or_default()),
+            ]
+            .into_iter()
+            .chain(
+                fields
+                    .iter()
+                    .zip(record.data.iter().skip(1))
+                    .map(|(field, value)| (field.name.as_str(), value.clone())),
+            )
+            .collect::<Vec<_>>();
+            fill(&settings.entry, &values).trim().to_string()
+        })
+        .collect())
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn escape_latex(text: &str) -> String {
+    text.chars()
+        .map(|char| match char {
+            '\\' => "\\textbackslash{}".to_string(),
+            '~' => "\\textasciitilde{}".to_string(),
+            '^' => "\\textasciicircum{}".to_string(),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => format!("\\{}", char),
+            char => char.to_string(),
+        })
+        .collect()
+}
+
+fn zip_files(files: &[(&str, String)]) -> Result<Vec<u8>, String> {
+    let mut writer = ZipWriter::new(Cursor::new(vec![]));
+    for (name, content) in files {
+        // the ODT `mimetype` has to be the first file and uncompressed
+        let compression = if *name == "mimetype" {
+            CompressionMethod::Stored
+        } else {
+            CompressionMethod::Deflated
+        };
+        writer
+            .start_file(*name, SimpleFileOptions::default().compression_method(compression))
+            .map_err(|error| error.to_string())?;
+        writer
+            .write_all(content.as_bytes())
+            .map_err(|error| error.to_string())?;
+    }
+    Ok(writer
+        .finish()
+        .map_err(|error| error.to_string())?
+        .into_inner())
+}
+
+fn docx(title: &str, entries: &[String]) -> Result<Vec<u8>, String> {
+    let paragraph = |text: &str, bold: bool| {
+        format!(
+            "<w:p><w:r>{}<w:t xml:space=\"preserve\">{}</w:t></w:r></w:p>",
+            if bold { "<w:rPr><w:b/></w:rPr>" } else { "" },
+            escape_xml(text)
+        )
+    };
+    let body = [paragraph(title, true)]
+        .into_iter()
+        .chain(entries.iter().map(|entry| paragraph(entry, false)))
+        .collect::<String>();
+    zip_files(&[
+        (
+            "[Content_Types].xml",
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
+<Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\">\
+<Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/>\
+<Default Extension=\"xml\" ContentType=\"application/xml\"/>\
+<Override PartName=\"/word/document.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml\"/>\
+</Types>"
+                .to_string(),
+        ),
+        (
+            "_rels/.rels",
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
+<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+<Relationship Id=\"rId1\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument\" Target=\"word/document.xml\"/>\
+</Relationships>"
+                .to_string(),
+        ),
+        (
+            "word/document.xml",
+            format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
+<w:document xmlns:w=\"http://schemas.openxmlformats.org/wordprocessingml/2006/main\"><w:body>{}</w:body></w:document>",
+                body
+            ),
+        ),
+    ])
+}
+
+fn odt(title: &str, entries: &[String]) -> Result<Vec<u8>, String> {
+    let body = [format!("<text:h text:outline-level=\"1\">{}</text:h>", escape_xml(title))]
+        .into_iter()
+        .chain(
+            entries
+                .iter()
+                .map(|entry| format!("<text:p>{}</text:p>", escape_xml(entry))),
+        )
+        .collect::<String>();
+    zip_files(&[
+        ("mimetype", "application/vnd.oasis.opendocument.text".to_string()),
+        (
+            "META-INF/manifest.xml",
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+<manifest:manifest xmlns:manifest=\"urn:oasis:names:tc:opendocument:xmlns:manifest:1.0\" manifest:version=\"1.2\">\
+<manifest:file-entry manifest:full-path=\"/\" manifest:media-type=\"application/vnd.oasis.opendocument.text\"/>\
+<manifest:file-entry manifest:full-path=\"content.xml\" manifest:media-type=\"text/xml\"/>\
+</manifest:manifest>"
+                .to_string(),
+        ),
+        (
+            "content.xml",
+            format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+<office:document-content xmlns:office=\"urn:oasis:names:tc:opendocument:xmlns:office:1.0\" \
+xmlns:text=\"urn:oasis:names:tc:opendocument:xmlns:text:1.0\" office:version=\"1.2\">\
+<office:body><office:text>{}</office:text></office:body></office:document-content>",
+                body
+            ),
+        ),
+    ])
+}
+
+/// The catalogue as a document of the given format, one paragraph per entry.
+pub fn render_catalogue(
+    title: &str,
+    entries: &[String],
+    format: CatalogueFormat,
+) -> Result<Vec<u8>, String> {
+    match format {
+        CatalogueFormat::Markdown => Ok(format!(
+            "# {}\n\n{}",
+            title,
+            entries
+                .iter()
+                .map(|entry| format!("{}\n\n", entry))
+                .collect::<String>()
+        )
+        .into_bytes()),
+        CatalogueFormat::LaTeX => Ok(format!(
+            "\\documentclass{{article}}\n\\usepackage[utf8]{{inputenc}}\n\\begin{{document}}\n\\section*{{{}}}\n\n{}\\end{{document}}\n",
+            escape_latex(title),
+            entries
+                .iter()
+                .map(|entry| format!("{}\n\n", escape_latex(entry)))
+                .collect::<String>()
+        )
+        .into_bytes()),
+        CatalogueFormat::Docx => docx(title, entries),
+        CatalogueFormat::Odt => odt(title, entries),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{catalogue_entries, render_catalogue, CatalogueFormat, Filter};
+    use crate::app::data_base::table::{test::FromMatrix, Table};
+    use crate::app::settings::catalogue_settings::CatalogueSettings;
+    use crate::app::settings::index_format::IndexFormat;
+    use crate::app::settings::{Field, FieldType};
+    use std::io::{Cursor, Read};
+    use umya_spreadsheet::new_file;
+    use zip::ZipArchive;
+
+    #[test]
+    fn catalogue() {
+        let format = IndexFormat::default();
+        let fields = vec![
+            Field::new(FieldType::Text, "Name".to_string(), true),
+            Field::new(FieldType::Text, "Square".to_string(), true),
+        ];
+        let mut book = new_file();
+        let records = book
+            .matrix_to_sheet(vec![
+                vec!["1", "2024-07-01", "pot", "B2"],
+                vec!["2", "2024-07-01", "bead", "A1"],
+                vec!["3", "2024-07-02", "pot lid", "A1"],
+                vec!["4", "2024-07-02", "coin", "C3"],
+            ])
+            .get_records(3, &format);
+
+        assert!(Filter::parse_all("Colour=red", &fields).is_err());
+        assert!(Filter::parse_all("Name", &fields).is_err());
+
+        let settings = CatalogueSettings {
+            entry: "{index}. {Name}. Square {Square}, {date}".to_string(),
+            filter: "name~POT; Square!=C3".to_string(),
+            sort_by: "Square".to_string(),
+            title: "".to_string(),
+        };
+        assert_eq!(
+            Ok(vec![
+                "3. pot lid. Square A1, 2024-07-02".to_string(),
+                "1. pot. Square B2, 2024-07-01".to_string()
+            ]),
+            catalogue_entries(records.clone(), &fields, &settings, &format)
+        );
+
+        let entries = vec!["1. 50% of a pot_lid".to_string()];
+        assert!(String::from_utf8(render_catalogue("Finds", &entries, CatalogueFormat::LaTeX).unwrap())
+            .unwrap()
+            .contains("1. 50\\% of a pot\\_lid\n"));
+        let docx = render_catalogue("Finds", &entries, CatalogueFormat::Docx).unwrap();
+        let mut document = String::new();
+        ZipArchive::new(Cursor::new(docx))
+            .unwrap()
+            .by_name("word/document.xml")
+            .unwrap()
+            .read_to_string(&mut document)
+            .unwrap();
+        assert!(document.contains("<w:t xml:space=\"preserve\">1. 50% of a pot_lid</w:t>"));
+    }
+}
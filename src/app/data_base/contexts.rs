@@ -0,0 +1,375 @@
+use crate::app::data_base::meta_data::{get_keyed_rows, set_keyed_rows};
+use crate::app::data_base::report::fill;
+use crate::app::data_base::DataBase;
+use crate::app::i18n::tr;
+use crate::app::settings::context_settings::ContextSettings;
+use crate::app::settings::Field;
+use std::fmt::{Display, Formatter};
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Sheet holding the contexts (`context | id | fields...`)
+/// and the stratigraphic relations between them (`relation | from | kind | to`).
+pub const CONTEXTS_SHEET: &str = "contexts";
+
+/// A stratigraphic unit: a layer, a cut, a fill, a tomb...
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Context {
+    pub id: String,
+    /// The values of `ContextSettings::fields`.
+    pub data: Vec<String>,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum RelationKind {
+    Above,
+    Below,
+    Cuts,
+    Fills,
+}
+
+/// `from` is above, below, cuts or fills `to`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Relation {
+    pub from: String,
+    pub kind: RelationKind,
+    pub to: String,
+}
+
+impl Context {
+    pub fn from_row(row: &[String]) -> Option<Self> {
+        Some(Self {
+            id: row.first().filter(|id| !id.is_empty())?.clone(),
+            data: row[1..].to_vec(),
+        })
+    }
+
+    pub fn to_row(&self) -> Vec<String> {
+        [vec![self.id.clone()], self.data.clone()].concat()
+    }
+}
+
+impl Display for Context {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.id)?;
+        let data = self
+            .data
+            .iter()
+            .filter(|value| !value.is_empty())
+            .cloned()
+            .collect::<Vec<_>>();
+        if !data.is_empty() {
+            write!(f, " ({})", data.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl RelationKind {
+    pub const ALL: [RelationKind; 4] = [
+        RelationKind::Above,
+        RelationKind::Below,
+        RelationKind::Cuts,
+        RelationKind::Fills,
+    ];
+
+    pub fn parse(text: &str) -> Option<Self> {
+        RelationKind::ALL
+            .into_iter()
+            .find(|kind| kind.to_string() == text)
+    }
+}
+
+impl Display for RelationKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RelationKind::Above => write!(f, "above"),
+            RelationKind::Below => write!(f, "below"),
+            RelationKind::Cuts => write!(f, "cuts"),
+            RelationKind::Fills => write!(f, "fills"),
+        }
+    }
+}
+
+impl Relation {
+    pub fn from_row(row: &[String]) -> Option<Self> {
+        Some(Self {
+            from: row.first()?.clone(),
+            kind: RelationKind::parse(row.get(1)?)?,
+            to: row.get(2)?.clone(),
+        })
+    }
+
+    pub fn to_row(&self) -> Vec<String> {
+        vec![self.from.clone(), self.kind.to_string(), self.to.clone()]
+    }
+
+    /// The later and the earlier context: what lies above, cuts or fills is later.
+    pub fn later_earlier(&self) -> (&str, &str) {
+        match self.kind {
+            RelationKind::Below => (&self.to, &self.from),
+            _ => (&self.from, &self.to),
+        }
+    }
+}
+
+impl Display for Relation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} {}", self.from, self.kind, self.to)
+    }
+}
+
+/// Whether `to` can be reached from `from` going from later to earlier contexts.
+fn is_earlier(edges: &[(&str, &str)], from: &str, to: &str) -> bool {
+    let mut stack = vec![from];
+    let mut visited = vec![];
+    while let Some(context) = stack.pop() {
+        if context == to {
+            return true;
+        }
+        if !visited.contains(&context) {
+            visited.push(context);
+            stack.extend(
+                edges
+                    .iter()
+                    .filter(|(later, _)| *later == context)
+                    .map(|(_, earlier)| *earlier),
+            );
+        }
+    }
+    false
+}
+
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Draws a graph with Graphviz `dot`, which has to be installed.
+pub fn render_dot(dot: &str, format: &str, path: &Path) -> Result<(), String> {
+    let mut child = Command::new("dot")
+        .arg(format!("-T{}", format))
+        .arg("-o")
+        .arg(path)
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|error| format!("Can not run Graphviz dot: {}", error))?;
+    child
+        .stdin
+        .take()
+        .expect("Can not write to dot")
+        .write_all(dot.as_bytes())
+        .map_err(|error| error.to_string())?;
+    let status = child.wait().map_err(|error| error.to_string())?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("Graphviz dot failed: {}", status))
+    }
+}
+
+impl DataBase {
+    pub fn get_contexts(&self) -> Vec<Context> {
+        get_keyed_rows(&self.book, CONTEXTS_SHEET, "context")
+            .iter()
+            .filter_map(|row| Context::from_row(row))
+            .collect()
+    }
+
+    pub fn set_contexts(&mut self, contexts: &[Context]) {
+        set_keyed_rows(
+            &mut self.book,
+            CONTEXTS_SHEET,
+            "context",
+            contexts.iter().map(Context::to_row).collect(),
+            false,
+        )
+    }
+
+    pub fn get_relations(&self) -> Vec<Relation> {
+        get_keyed_rows(&self.book, CONTEXTS_SHEET, "relation")
+            .iter()
+            .filter_map(|row| Relation::from_row(row))
+            .collect()
+    }
+
+    pub fn set_relations(&mut self, relations: &[Relation]) {
+        set_keyed_rows(
+            &mut self.book,
+            CONTEXTS_SHEET,
+            "relation",
+            relations.iter().map(Relation::to_row).collect(),
+            false,
+        )
+    }
+
+    /// Adds a relation between two known contexts, refusing one that contradicts the others.
+    pub fn add_relation(&mut self, relation: Relation, language: &str) -> Result<(), String> {
+        let contexts = self.get_contexts();
+        for id in [&relation.from, &relation.to] {
+            if !contexts.iter().any(|context| context.id == *id) {
+                return Err(fill(
+                    tr(language, "The context {context} does not exist"),
+                    &[("context", id.clone())],
+                ));
+            }
+        }
+        if relation.from == relation.to {
+            return Err(fill(
+                tr(language, "The context {context} can not be related to itself"),
+                &[("context", relation.from.clone())],
+            ));
+        }
+        let mut relations = self.get_relations();
+        if relations.contains(&relation) {
+            return Ok(());
+        }
+        let edges = relations.iter().map(Relation::later_earlier).collect::<Vec<_>>();
+        let (later, earlier) = relation.later_earlier();
+        if is_earlier(&edges, earlier, later) {
+            return Err(fill(
+                tr(
+                    language,
+                    "{relation} contradicts the matrix: {later} is already earlier than {earlier}",
+                ),
+                &[
+                    ("relation", relation.to_string()),
+                    ("later", later.to_string()),
+                    ("earlier", earlier.to_string()),
+                ],
+            ));
+        }
+        relations.push(relation);
+        self.set_relations(&relations);
+        Ok(())
+    }
+
+    /// Checks that a new find names a known context, an empty context is allowed.
+    pub fn check_context(
+        &self,
+        data: &[String],
+        fields: &[Field],
+        settings: &ContextSettings,
+        language: &str,
+    ) -> Result<(), String> {
+        let Some(context) = fields
+            .iter()
+            .position(|field| field.name == settings.find_field)
+            .and_then(|id| data.get(id + 1))
+            .map(|context| context.trim())
+            .filter(|context| !context.is_empty())
+        else {
+            return Ok(());
+        };
+        if self.get_contexts().iter().any(|other| other.id == context) {
+            Ok(())
+        } else {
+            Err(fill(
+                tr(language, "The context {context} is not in the register"),
+                &[("context", context.to_string())],
+            ))
+        }
+    }
+
+    /// The Harris matrix as a Graphviz graph, later contexts above the earlier ones.
+    /// Relations implied by others are left out.
+    pub fn harris_dot(&self) -> String {
+        let relations = self.get_relations();
+        let edges = relations.iter().map(Relation::later_earlier).collect::<Vec<_>>();
+        let mut direct = edges
+            .iter()
+            .enumerate()
+            .filter(|&(i, &(later, earlier))| {
+                let others = edges
+                    .iter()
+                    .enumerate()
+                    .filter(|&(j, edge)| j != i && *edge != (later, earlier))
+                    .map(|(_, edge)| *edge)
+                    .collect::<Vec<_>>();
+                !others
+                    .iter()
+                    .filter(|(other_later, _)| *other_later == later)
+                    .any(|&(_, middle)| is_earlier(&others, middle, earlier))
+            })
+            .map(|(_, edge)| *edge)
+            .collect::<Vec<_>>();
+        direct.sort();
+        direct.dedup();
+
+        let nodes = self
+            .get_contexts()
+            .iter()
+            .map(|context| {
+                format!(
+                    "  {} [label={}];\n",
+                    quote(&context.id),
+                    quote(&context.to_string())
+                )
+            })
+            .collect::<String>();
+        let edges = direct
+            .iter()
+            .map(|(later, earlier)| format!("  {} -> {};\n", quote(later), quote(earlier)))
+            .collect::<String>();
+        format!(
+            "digraph harris {{\n  rankdir=TB;\n  node [shape=box];\n{}{}}}\n",
+            nodes, edges
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Context, Relation, RelationKind};
+    use crate::app::data_base::DataBase;
+    use crate::app::settings::context_settings::ContextSettings;
+    use crate::app::settings::{Field, FieldType};
+    use umya_spreadsheet::new_file;
+
+    fn relation(from: &str, kind: RelationKind, to: &str) -> Relation {
+        Relation {
+            from: from.to_string(),
+            kind,
+            to: to.to_string(),
+        }
+    }
+
+    #[test]
+    fn harris_matrix() {
+        let mut data_base = DataBase { book: new_file() };
+        let contexts = ["1", "2", "3", "4"]
+            .into_iter()
+            .map(|id| Context {
+                id: id.to_string(),
+                data: vec!["layer".to_string()],
+            })
+            .collect::<Vec<_>>();
+        data_base.set_contexts(&contexts);
+        assert_eq!(contexts, data_base.get_contexts());
+
+        assert!(data_base.add_relation(relation("1", RelationKind::Above, "2"), "en").is_ok());
+        assert!(data_base.add_relation(relation("3", RelationKind::Cuts, "2"), "en").is_ok());
+        assert!(data_base.add_relation(relation("4", RelationKind::Fills, "3"), "en").is_ok());
+        assert!(data_base.add_relation(relation("2", RelationKind::Below, "4"), "en").is_ok());
+        assert!(data_base.add_relation(relation("2", RelationKind::Above, "4"), "en").is_err());
+        assert!(data_base.add_relation(relation("1", RelationKind::Above, "5"), "en").is_err());
+        assert_eq!(4, data_base.get_relations().len());
+
+        let dot = data_base.harris_dot();
+        assert!(dot.contains("  \"1\" [label=\"1 (layer)\"];\n"));
+        assert!(dot.contains("  \"4\" -> \"3\";\n"));
+        assert!(dot.contains("  \"3\" -> \"2\";\n"));
+        // implied by 4 -> 3 -> 2
+        assert!(!dot.contains("  \"4\" -> \"2\";\n"));
+
+        let fields = vec![
+            Field::new(FieldType::Text, "Name".to_string(), true),
+            Field::new(FieldType::Text, "Context".to_string(), false),
+        ];
+        let settings = ContextSettings::default();
+        let data = |context: &str| vec!["01.07".to_string(), "pot".to_string(), context.to_string()];
+        assert!(data_base.check_context(&data("3"), &fields, &settings, "en").is_ok());
+        assert!(data_base.check_context(&data(""), &fields, &settings, "en").is_ok());
+        assert!(data_base.check_context(&data("7"), &fields, &settings, "en").is_err());
+    }
+}
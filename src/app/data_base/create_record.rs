@@ -1,22 +1,72 @@
 use std::cmp::min;
+use crate::app::data_base::index::Index;
+use crate::app::data_base::reservation::Reservation;
 use crate::app::data_base::table::Table;
+use crate::app::settings::index_format::IndexFormat;
 use umya_spreadsheet::Worksheet;
 
 pub trait CreateRecord {
-    fn create_start_record(&mut self, quantity: u32, data: Vec<String>);
+    fn create_start_record(
+        &mut self,
+        quantity: u32,
+        data: Vec<String>,
+        index_format: &IndexFormat,
+        recorder: Option<&str>,
+    ) -> Vec<Index>;
 
-    fn create_end_record(&mut self, quantity: u32, data: Vec<String>);
+    fn get_next_end_indices(
+        &self,
+        quantity: u32,
+        index_format: &IndexFormat,
+        reservations: &[Reservation],
+    ) -> Vec<u32>;
 
-    fn create_record_from_index(&mut self, index: u32, data: Vec<String>);
+    fn create_end_record(
+        &mut self,
+        quantity: u32,
+        data: Vec<String>,
+        index_format: &IndexFormat,
+        reservations: &[Reservation],
+        recorder: Option<&str>,
+    ) -> Vec<Index>;
+
+    fn create_record_from_index(
+        &mut self,
+        index: impl Into<Index>,
+        data: Vec<String>,
+        index_format: &IndexFormat,
+        recorder: Option<&str>,
+    ) -> Vec<Index>;
+
+    fn create_sorted_record_from_index(
+        &mut self,
+        index: impl Into<Index>,
+        data: Vec<String>,
+        index_format: &IndexFormat,
+        recorder: Option<&str>,
+    ) -> Vec<Index>;
+
+    fn create_sub_records(
+        &mut self,
+        parent: u32,
+        quantity: u32,
+        index_format: &IndexFormat,
+        recorder: Option<&str>,
+    ) -> Vec<Index>;
 }
 
 impl CreateRecord for Worksheet {
-    fn create_start_record(&mut self, quantity: u32, data: Vec<String>) {
-        let start_index = self.get_start_index() as u32;
-        let mut line_index = self.get_start_line_index();
+    fn create_start_record(
+        &mut self,
+        quantity: u32,
+        data: Vec<String>,
+        index_format: &IndexFormat,
+        recorder: Option<&str>,
+    ) -> Vec<Index> {
+        let start_index = self.get_start_index(index_format);
+        let mut line_index = self.get_start_line_index(index_format);
+        let mut result = vec![];
         for index in ((start_index - quantity)..start_index).rev() {
-            let mut new_data = data.clone();
-            new_data.insert(0, index.to_string());
             let insert = {
                 if line_index == 1 {
                     true
@@ -29,53 +79,183 @@ impl CreateRecord for Worksheet {
             } else {
                 line_index -= 1;
             }
-            self.set_row(line_index, new_data);
+            self.set_record(line_index, index.into(), data.clone(), index_format, recorder);
+            result.insert(0, index.into());
         }
+        result
     }
 
-    fn create_end_record(&mut self, quantity: u32, data: Vec<String>) {
-        let start_end_line_index = self.get_end_line_index();
-        let start_end_index = self.get_end_index() as u32;
-        for (end_line_index, end_index) in ((start_end_line_index + 1)
-            ..=(start_end_line_index + quantity))
-            .zip((start_end_index + 1)..=(start_end_index + quantity))
-        {
-            let mut new_data = data.clone();
-            new_data.insert(0, end_index.to_string());
-            self.set_row(end_line_index, new_data);
+    fn get_next_end_indices(
+        &self,
+        quantity: u32,
+        index_format: &IndexFormat,
+        reservations: &[Reservation],
+    ) -> Vec<u32> {
+        let is_reserved = |index: u32| {
+            reservations
+                .iter()
+                .find(|reservation| reservation.contains(index))
+                .cloned()
+        };
+
+        let mut end_index = self.get_end_index(index_format);
+        if is_reserved(end_index).is_some() {
+            // the last row was recorded by another team, continue our own numbering
+            end_index = self
+                .get_indices(index_format)
+                .into_iter()
+                .map(|(_, index)| index.number)
+                .filter(|&number| is_reserved(number).is_none())
+                .max()
+                .unwrap_or(0);
         }
+
+        (0..quantity)
+            .map(|_| {
+                end_index += 1;
+                while let Some(reservation) = is_reserved(end_index) {
+                    end_index = reservation.end + 1;
+                }
+                end_index
+            })
+            .collect()
     }
 
-    fn create_record_from_index(&mut self, index: u32, data: Vec<String>) {
-        let mut new_data = data;
-        new_data.insert(0, index.to_string());
+    fn create_end_record(
+        &mut self,
+        quantity: u32,
+        data: Vec<String>,
+        index_format: &IndexFormat,
+        reservations: &[Reservation],
+        recorder: Option<&str>,
+    ) -> Vec<Index> {
+        let end_indices = self.get_next_end_indices(quantity, index_format, reservations);
+        for (end_line_index, end_index) in (self.get_end_line_index() + 1..).zip(&end_indices) {
+            self.set_record(
+                end_line_index,
+                (*end_index).into(),
+                data.clone(),
+                index_format,
+                recorder,
+            );
+        }
+        end_indices.into_iter().map(Index::from).collect()
+    }
+
+    fn create_record_from_index(
+        &mut self,
+        index: impl Into<Index>,
+        data: Vec<String>,
+        index_format: &IndexFormat,
+        recorder: Option<&str>,
+    ) -> Vec<Index> {
+        let index = index.into();
+        let indices = self.get_indices(index_format);
+        if let Some(&(line_index, _)) = indices.iter().find(|(_, row_index)| *row_index == index) {
+            self.set_record(line_index, index, data, index_format, recorder);
+            return vec![index];
+        }
 
         let mut offset = 0;
         let mut is_found = true;
-        let begin_line_index = (1..=index)
+        let begin_line_index = (1..=index.number)
             .rev()
-            .find_map(|new_index| {
-                offset = index - new_index;
-                self.get_row_index_from_index(new_index) 
+            .find_map(|number| {
+                offset = index.number - number;
+                indices
+                    .iter()
+                    .filter(|(_, row_index)| row_index.number == number && *row_index < index)
+                    .map(|(line_index, _)| *line_index)
+                    .max()
             })
             .unwrap_or_else(|| {
                 is_found = false;
-                self.get_start_line_index()
+                self.get_start_line_index(index_format)
             });
-        
-        
 
-        if offset == 0 && is_found{
-            self.set_row(begin_line_index, new_data);
-        } else { 
+        if offset == 0 && is_found {
+            // a sub-number goes right after the parent or the previous sub-number
+            self.insert_record(begin_line_index + 1, index, data, index_format, recorder);
+        } else {
             let count_empty_row = self.get_count_empty_rows(begin_line_index, is_found);
-            if count_empty_row == 0{
-                self.insert_row((begin_line_index as i32 + if is_found{ 1 } else { 0 }) as u32, new_data);
+            if count_empty_row == 0 {
+                self.insert_record(
+                    (begin_line_index as i32 + if is_found { 1 } else { 0 }) as u32,
+                    index,
+                    data,
+                    index_format,
+                    recorder,
+                );
             } else {
                 let offset = min(offset, count_empty_row) as i32;
-                self.set_row((begin_line_index as i32 + if is_found{ offset } else { -offset }) as u32, new_data);
+                self.set_record(
+                    (begin_line_index as i32 + if is_found { offset } else { -offset }) as u32,
+                    index,
+                    data,
+                    index_format,
+                    recorder,
+                );
             }
         }
+        vec![index]
+    }
+
+    fn create_sorted_record_from_index(
+        &mut self,
+        index: impl Into<Index>,
+        data: Vec<String>,
+        index_format: &IndexFormat,
+        recorder: Option<&str>,
+    ) -> Vec<Index> {
+        let index = index.into();
+        self.compact(index_format);
+
+        let indices = self.get_indices(index_format);
+        if let Some(&(line_index, _)) = indices.iter().find(|(_, row_index)| *row_index == index) {
+            self.set_record(line_index, index, data, index_format, recorder);
+        } else if let Some(&(line_index, _)) =
+            indices.iter().find(|(_, row_index)| *row_index > index)
+        {
+            self.insert_record(line_index, index, data, index_format, recorder);
+        } else {
+            self.set_record(self.get_end_line_index() + 1, index, data, index_format, recorder);
+        }
+        vec![index]
+    }
+
+    fn create_sub_records(
+        &mut self,
+        parent: u32,
+        quantity: u32,
+        index_format: &IndexFormat,
+        recorder: Option<&str>,
+    ) -> Vec<Index> {
+        let indices = self
+            .get_indices(index_format)
+            .into_iter()
+            .filter(|(_, index)| index.number == parent)
+            .collect::<Vec<_>>();
+        let Some(&(parent_line_index, _)) = indices.iter().find(|(_, index)| index.sub.is_none())
+        else {
+            return vec![];
+        };
+
+        let data = self.get_row_data(parent_line_index);
+        let mut line_index = indices.iter().map(|(line_index, _)| *line_index).max().unwrap();
+        let last_sub = indices.iter().filter_map(|(_, index)| index.sub).max().unwrap_or(0);
+        (last_sub + 1..=last_sub + quantity)
+            .map(|sub| {
+                line_index += 1;
+                self.insert_record(
+                    line_index,
+                    Index::new(parent, Some(sub)),
+                    data.clone(),
+                    index_format,
+                    recorder,
+                );
+                Index::new(parent, Some(sub))
+            })
+            .collect()
     }
 }
 
@@ -83,8 +263,11 @@ impl CreateRecord for Worksheet {
 mod tests {
     use crate::app::data_base::{
         create_record::CreateRecord,
+        index::Index,
+        reservation::Reservation,
         table::{test::FromMatrix, Table},
     };
+    use crate::app::settings::index_format::IndexFormat;
     use umya_spreadsheet::new_file;
 
     #[test]
@@ -92,7 +275,13 @@ mod tests {
         {
             let mut book = new_file();
             let test_sheet = book.matrix_to_sheet(vec![]);
-            test_sheet.create_end_record(3, vec!["test".to_string(), "test2".to_string()]);
+            test_sheet.create_end_record(
+                3,
+                vec!["test".to_string(), "test2".to_string()],
+                &IndexFormat::default(),
+                &[],
+                None,
+            );
             assert_eq!(
                 vec![
                     vec!["1", "test", "test2"],
@@ -109,7 +298,13 @@ mod tests {
                 vec!["3", "b", "c"],
                 vec!["5", "b", "c"],
             ]);
-            test_sheet.create_end_record(2, vec!["test".to_string(), "test2".to_string()]);
+            test_sheet.create_end_record(
+                2,
+                vec!["test".to_string(), "test2".to_string()],
+                &IndexFormat::default(),
+                &[],
+                None,
+            );
             assert_eq!(
                 vec![
                     vec!["1", "b", "c"],
@@ -129,7 +324,13 @@ mod tests {
                 vec!["3", "b", "c"],
                 vec!["", "", ""],
             ]);
-            test_sheet.create_end_record(5, vec!["test".to_string(), "test2".to_string()]);
+            test_sheet.create_end_record(
+                5,
+                vec!["test".to_string(), "test2".to_string()],
+                &IndexFormat::default(),
+                &[],
+                None,
+            );
             assert_eq!(
                 vec![
                     vec!["", "", ""],
@@ -146,6 +347,60 @@ mod tests {
         }
     }
 
+    #[test]
+    fn create_end_record_with_reservations() {
+        let reservations = [Reservation {
+            start: 4,
+            end: 5,
+            team: "sieving".to_string(),
+        }];
+        {
+            let mut book = new_file();
+            let test_sheet = book.matrix_to_sheet(vec![
+                vec!["2", "b", "c"],
+                vec!["3", "b", "c"],
+            ]);
+            test_sheet.create_end_record(
+                2,
+                vec!["test".to_string(), "test2".to_string()],
+                &IndexFormat::default(),
+                &reservations,
+                None,
+            );
+            assert_eq!(
+                vec![
+                    vec!["2", "b", "c"],
+                    vec!["3", "b", "c"],
+                    vec!["6", "test", "test2"],
+                    vec!["7", "test", "test2"],
+                ],
+                test_sheet.get_string_matrix()
+            );
+        }
+        {
+            let mut book = new_file();
+            let test_sheet = book.matrix_to_sheet(vec![
+                vec!["1", "b", "c"],
+                vec!["4", "b", "c"],
+            ]);
+            test_sheet.create_end_record(
+                1,
+                vec!["test".to_string(), "test2".to_string()],
+                &IndexFormat::default(),
+                &reservations,
+                None,
+            );
+            assert_eq!(
+                vec![
+                    vec!["1", "b", "c"],
+                    vec!["4", "b", "c"],
+                    vec!["2", "test", "test2"],
+                ],
+                test_sheet.get_string_matrix()
+            );
+        }
+    }
+
     #[test]
     fn test_create_start_record() {
         {
@@ -155,7 +410,12 @@ mod tests {
                 vec!["4", "b", "c"],
                 vec!["5", "b", "c"],
             ]);
-            test_sheet.create_start_record(2, vec!["test".to_string(), "test2".to_string()]);
+            test_sheet.create_start_record(
+                2,
+                vec!["test".to_string(), "test2".to_string()],
+                &IndexFormat::default(),
+                None,
+            );
             assert_eq!(
                 vec![
                     vec!["1", "test", "test2"],
@@ -175,7 +435,12 @@ mod tests {
                 vec!["4", "b", "c"],
                 vec!["5", "b", "c"],
             ]);
-            test_sheet.create_start_record(2, vec!["test".to_string(), "test2".to_string()]);
+            test_sheet.create_start_record(
+                2,
+                vec!["test".to_string(), "test2".to_string()],
+                &IndexFormat::default(),
+                None,
+            );
             assert_eq!(
                 vec![
                     vec!["index", "name", "info"],
@@ -194,7 +459,12 @@ mod tests {
         {
             let mut book = new_file();
             let test_sheet = book.matrix_to_sheet(vec![]);
-            test_sheet.create_record_from_index(4, vec!["test".to_string(), "test2".to_string()]);
+            test_sheet.create_record_from_index(
+                4,
+                vec!["test".to_string(), "test2".to_string()],
+                &IndexFormat::default(),
+                None,
+            );
             assert_eq!(
                 vec![
                     vec!["4", "test", "test2"],
@@ -209,7 +479,12 @@ mod tests {
                 vec!["4", "b", "c"],
                 vec!["5", "b", "c"],
             ]);
-            test_sheet.create_record_from_index(4, vec!["test".to_string(), "test2".to_string()]);
+            test_sheet.create_record_from_index(
+                4,
+                vec!["test".to_string(), "test2".to_string()],
+                &IndexFormat::default(),
+                None,
+            );
             assert_eq!(
                 vec![
                     vec!["3", "b", "c"],
@@ -226,7 +501,12 @@ mod tests {
                 vec!["4", "b", "c"],
                 vec!["5", "b", "c"],
             ]);
-            test_sheet.create_record_from_index(3, vec!["test".to_string(), "test2".to_string()]);
+            test_sheet.create_record_from_index(
+                3,
+                vec!["test".to_string(), "test2".to_string()],
+                &IndexFormat::default(),
+                None,
+            );
             assert_eq!(
                 vec![
                     vec!["2", "b", "c"],
@@ -245,7 +525,12 @@ mod tests {
                 vec!["4", "b", "c"],
                 vec!["5", "b", "c"],
             ]);
-            test_sheet.create_record_from_index(3, vec!["test".to_string(), "test2".to_string()]);
+            test_sheet.create_record_from_index(
+                3,
+                vec!["test".to_string(), "test2".to_string()],
+                &IndexFormat::default(),
+                None,
+            );
             assert_eq!(
                 vec![
                     vec!["2", "b", "c"],
@@ -267,7 +552,12 @@ mod tests {
                 vec!["5", "b", "c"],
                 vec!["6", "b", "c"],
             ]);
-            test_sheet.create_record_from_index(4, vec!["test".to_string(), "test2".to_string()]);
+            test_sheet.create_record_from_index(
+                4,
+                vec!["test".to_string(), "test2".to_string()],
+                &IndexFormat::default(),
+                None,
+            );
             assert_eq!(
                 vec![
                     vec!["2", "b", "c"],
@@ -292,7 +582,12 @@ mod tests {
                 vec!["11", "b", "c"],
                 vec!["12", "b", "c"],
             ]);
-            test_sheet.create_record_from_index(10, vec!["test".to_string(), "test2".to_string()]);
+            test_sheet.create_record_from_index(
+                10,
+                vec!["test".to_string(), "test2".to_string()],
+                &IndexFormat::default(),
+                None,
+            );
             assert_eq!(
                 vec![
                     vec!["2", "b", "c"],
@@ -311,7 +606,12 @@ mod tests {
             let test_sheet = book.matrix_to_sheet(vec![
                 vec!["2", "b", "c"],
             ]);
-            test_sheet.create_record_from_index(4, vec!["test".to_string(), "test2".to_string()]);
+            test_sheet.create_record_from_index(
+                4,
+                vec!["test".to_string(), "test2".to_string()],
+                &IndexFormat::default(),
+                None,
+            );
             assert_eq!(
                 vec![
                     vec!["2", "b", "c"],
@@ -325,7 +625,12 @@ mod tests {
             let test_sheet = book.matrix_to_sheet(vec![
                 vec!["2", "b", "c"],
             ]);
-            test_sheet.create_record_from_index(1, vec!["test".to_string(), "test2".to_string()]);
+            test_sheet.create_record_from_index(
+                1,
+                vec!["test".to_string(), "test2".to_string()],
+                &IndexFormat::default(),
+                None,
+            );
             assert_eq!(
                 vec![
                     vec!["1", "test", "test2"],
@@ -342,7 +647,12 @@ mod tests {
                 vec!["", "", ""],
                 vec!["5", "b", "c"],
             ]);
-            test_sheet.create_record_from_index(3, vec!["test".to_string(), "test2".to_string()]);
+            test_sheet.create_record_from_index(
+                3,
+                vec!["test".to_string(), "test2".to_string()],
+                &IndexFormat::default(),
+                None,
+            );
             assert_eq!(
                 vec![
                     vec!["", "", ""],
@@ -361,7 +671,12 @@ mod tests {
                 vec!["", "", ""],
                 vec!["10", "b", "c"],
             ]);
-            test_sheet.create_record_from_index(4, vec!["test".to_string(), "test2".to_string()]);
+            test_sheet.create_record_from_index(
+                4,
+                vec!["test".to_string(), "test2".to_string()],
+                &IndexFormat::default(),
+                None,
+            );
             assert_eq!(
                 vec![
                     vec!["4", "test", "test2"],
@@ -373,4 +688,168 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn create_record_with_index_format() {
+        let index_format = IndexFormat {
+            prefix_template: "{site}{year}-T{trench}-".to_string(),
+            site: "KR".to_string(),
+            year: "24".to_string(),
+            trench: "3".to_string(),
+            input_padding: "4".to_string(),
+            padding: 4,
+            sub_separator: ".".to_string(),
+        };
+        {
+            let mut book = new_file();
+            let test_sheet = book.matrix_to_sheet(vec![
+                vec!["index", "name", "info"],
+                vec!["KR24-T3-0156", "b", "c"],
+            ]);
+            test_sheet.create_end_record(
+                2,
+                vec!["test".to_string(), "test2".to_string()],
+                &index_format,
+                &[],
+                None,
+            );
+            assert_eq!(
+                vec![
+                    vec!["index", "name", "info"],
+                    vec!["KR24-T3-0156", "b", "c"],
+                    vec!["KR24-T3-0157", "test", "test2"],
+                    vec!["KR24-T3-0158", "test", "test2"],
+                ],
+                test_sheet.get_string_matrix()
+            );
+        }
+        {
+            let mut book = new_file();
+            let test_sheet = book.matrix_to_sheet(vec![
+                vec!["KR24-T3-0157", "b", "c"],
+                vec!["KR24-T3-0157.1", "b", "c"],
+                vec!["KR24-T3-0158", "b", "c"],
+            ]);
+            test_sheet.create_record_from_index(
+                Index::new(157, Some(2)),
+                vec!["test".to_string(), "test2".to_string()],
+                &index_format,
+                None,
+            );
+            assert_eq!(
+                vec![
+                    vec!["KR24-T3-0157", "b", "c"],
+                    vec!["KR24-T3-0157.1", "b", "c"],
+                    vec!["KR24-T3-0157.2", "test", "test2"],
+                    vec!["KR24-T3-0158", "b", "c"],
+                ],
+                test_sheet.get_string_matrix()
+            );
+        }
+    }
+
+    #[test]
+    fn create_sub_records() {
+        {
+            let mut book = new_file();
+            let test_sheet = book.matrix_to_sheet(vec![
+                vec!["156", "a", "b"],
+                vec!["157", "c", "d"],
+                vec!["158", "e", "f"],
+            ]);
+            test_sheet.create_sub_records(157, 2, &IndexFormat::default(), None);
+            assert_eq!(
+                vec![
+                    vec!["156", "a", "b"],
+                    vec!["157", "c", "d"],
+                    vec!["157.1", "c", "d"],
+                    vec!["157.2", "c", "d"],
+                    vec!["158", "e", "f"],
+                ],
+                test_sheet.get_string_matrix()
+            );
+        }
+        {
+            let index_format = IndexFormat {
+                sub_separator: "/".to_string(),
+                ..IndexFormat::default()
+            };
+            let mut book = new_file();
+            let test_sheet = book.matrix_to_sheet(vec![
+                vec!["157", "c", "d"],
+                vec!["157/1", "g", "h"],
+                vec!["158", "e", "f"],
+            ]);
+            test_sheet.create_sub_records(157, 1, &index_format, None);
+            test_sheet.create_sub_records(159, 1, &index_format, None);
+            assert_eq!(
+                vec![
+                    vec!["157", "c", "d"],
+                    vec!["157/1", "g", "h"],
+                    vec!["157/2", "c", "d"],
+                    vec!["158", "e", "f"],
+                ],
+                test_sheet.get_string_matrix()
+            );
+        }
+    }
+
+    #[test]
+    fn create_sorted_record_from_index() {
+        {
+            let mut book = new_file();
+            let test_sheet = book.matrix_to_sheet(vec![
+                vec!["2", "b", "c"],
+                vec!["", "", ""],
+                vec!["", "", ""],
+                vec!["", "", ""],
+                vec!["", "", ""],
+                vec!["5", "b", "c"],
+                vec!["6", "b", "c"],
+            ]);
+            test_sheet.create_sorted_record_from_index(
+                4,
+                vec!["test".to_string(), "test2".to_string()],
+                &IndexFormat::default(),
+                None,
+            );
+            assert_eq!(
+                vec![
+                    vec!["2", "b", "c"],
+                    vec!["4", "test", "test2"],
+                    vec!["5", "b", "c"],
+                    vec!["6", "b", "c"],
+                ],
+                test_sheet.get_string_matrix()
+            );
+        }
+        {
+            let mut book = new_file();
+            let test_sheet = book.matrix_to_sheet(vec![
+                vec!["index", "name", "info"],
+                vec!["2", "b", "c"],
+            ]);
+            test_sheet.create_sorted_record_from_index(
+                1,
+                vec!["test".to_string(), "test2".to_string()],
+                &IndexFormat::default(),
+                None,
+            );
+            test_sheet.create_sorted_record_from_index(
+                7,
+                vec!["test".to_string(), "test2".to_string()],
+                &IndexFormat::default(),
+                None,
+            );
+            assert_eq!(
+                vec![
+                    vec!["index", "name", "info"],
+                    vec!["1", "test", "test2"],
+                    vec!["2", "b", "c"],
+                    vec!["7", "test", "test2"],
+                ],
+                test_sheet.get_string_matrix()
+            );
+        }
+    }
 }
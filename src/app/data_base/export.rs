@@ -0,0 +1,70 @@
+use crate::app::data_base::table::Table;
+use crate::app::settings::index_format::IndexFormat;
+use crate::app::settings::Field;
+use umya_spreadsheet::Worksheet;
+
+pub fn csv_line(cells: &[String]) -> String {
+    cells
+        .iter()
+        .map(|cell| {
+            if cell.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", cell.replace('"', "\"\""))
+            } else {
+                cell.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+pub fn to_csv(rows: &[Vec<String>]) -> String {
+    rows.iter()
+        .map(|row| csv_line(row) + "\n")
+        .collect()
+}
+
+pub trait Export {
+    fn to_csv(&self, fields: &[Field], index_format: &IndexFormat) -> String;
+}
+
+impl Export for Worksheet {
+    fn to_csv(&self, fields: &[Field], index_format: &IndexFormat) -> String {
+        let header = ["index".to_string(), "date".to_string()]
+            .into_iter()
+            .chain(fields.iter().map(|field| field.name.clone()))
+            .collect::<Vec<_>>();
+        let rows = self
+            .get_records(fields.len() + 1, index_format)
+            .into_iter()
+            .map(|record| [vec![index_format.format(record.index)], record.data].concat());
+
+        to_csv(&[header].into_iter().chain(rows).collect::<Vec<_>>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Export;
+    use crate::app::data_base::table::test::FromMatrix;
+    use crate::app::settings::index_format::IndexFormat;
+    use crate::app::settings::{Field, FieldType};
+    use umya_spreadsheet::new_file;
+
+    #[test]
+    fn to_csv() {
+        let fields = vec![
+            Field::new(FieldType::Text, "Name".to_string(), true),
+            Field::new(FieldType::Text, "info".to_string(), false),
+        ];
+        let mut book = new_file();
+        let sheet = book.matrix_to_sheet(vec![
+            vec!["index", "date", "Name", "info"],
+            vec!["2", "d", "bead", "a, b"],
+            vec!["1", "d", "pot \"A\"", ""],
+        ]);
+        assert_eq!(
+            "index,date,Name,info\n1,d,\"pot \"\"A\"\"\",\n2,d,bead,\"a, b\"\n",
+            sheet.to_csv(&fields, &IndexFormat::default())
+        );
+    }
+}
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub struct Index {
+    pub number: u32,
+    pub sub: Option<u32>,
+}
+
+impl Index {
+    pub fn new(number: u32, sub: Option<u32>) -> Self {
+        Self { number, sub }
+    }
+
+    pub fn parent(&self) -> Self {
+        Self::new(self.number, None)
+    }
+}
+
+impl From<u32> for Index {
+    fn from(number: u32) -> Self {
+        Self::new(number, None)
+    }
+}
+
+impl Display for Index {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.sub {
+            Some(sub) => write!(f, "{}.{}", self.number, sub),
+            None => write!(f, "{}", self.number),
+        }
+    }
+}
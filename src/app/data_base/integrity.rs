@@ -0,0 +1,243 @@
+use crate::app::data_base::index::Index;
+use crate::app::data_base::report::fill;
+use crate::app::data_base::table::Table;
+use crate::app::i18n::tr;
+use crate::app::settings::index_format::IndexFormat;
+use crate::app::settings::Field;
+use umya_spreadsheet::Worksheet;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Problem {
+    Duplicate {
+        row: u32,
+        value: String,
+        first_row: u32,
+    },
+    Gap {
+        number: u32,
+        /// Row of the record right before the gap.
+        row: Option<u32>,
+    },
+    MalformedIndex {
+        row: u32,
+        value: String,
+    },
+    MissingFields {
+        row: u32,
+        value: String,
+        fields: Vec<String>,
+    },
+    OutOfOrder {
+        row: u32,
+        value: String,
+    },
+    BlankRow(u32),
+    /// Malformed coordinates or a point outside of its square.
+    Coordinates {
+        row: u32,
+        value: String,
+        problem: String,
+    },
+}
+
+impl Problem {
+    pub fn row(&self) -> Option<u32> {
+        match self {
+            Problem::Duplicate { row, .. }
+            | Problem::MalformedIndex { row, .. }
+            | Problem::MissingFields { row, .. }
+            | Problem::OutOfOrder { row, .. }
+            | Problem::BlankRow(row)
+            | Problem::Coordinates { row, .. } => Some(*row),
+            Problem::Gap { row, .. } => *row,
+        }
+    }
+}
+
+impl Problem {
+    /// The problem in the language of the interface.
+    pub fn text(&self, language: &str) -> String {
+        let tr = |text| tr(language, text);
+        match self {
+            Problem::Duplicate {
+                row,
+                value,
+                first_row,
+            } => fill(
+                tr("Row {row}: index {index} duplicates row {first_row}"),
+                &[
+                    ("row", row.to_string()),
+                    ("index", value.clone()),
+                    ("first_row", first_row.to_string()),
+                ],
+            ),
+            Problem::Gap { number, .. } => {
+                fill(tr("Index {index} is missing"), &[("index", number.to_string())])
+            }
+            Problem::MalformedIndex { row, value } => {
+                if value.is_empty() {
+                    fill(tr("Row {row}: the index is empty"), &[("row", row.to_string())])
+                } else {
+                    fill(
+                        tr("Row {row}: malformed index \"{index}\""),
+                        &[("row", row.to_string()), ("index", value.clone())],
+                    )
+                }
+            }
+            Problem::MissingFields { row, value, fields } => fill(
+                tr("Row {row}: {index} has empty required fields: {fields}"),
+                &[
+                    ("row", row.to_string()),
+                    ("index", value.clone()),
+                    ("fields", fields.join(", ")),
+                ],
+            ),
+            Problem::OutOfOrder { row, value } => fill(
+                tr("Row {row}: {index} is out of order"),
+                &[("row", row.to_string()), ("index", value.clone())],
+            ),
+            Problem::BlankRow(row) => fill(tr("Row {row} is blank"), &[("row", row.to_string())]),
+            Problem::Coordinates {
+                row,
+                value,
+                problem,
+            } => fill(
+                tr("Row {row}: {index}: {problem}"),
+                &[
+                    ("row", row.to_string()),
+                    ("index", value.clone()),
+                    ("problem", problem.clone()),
+                ],
+            ),
+        }
+    }
+}
+
+pub trait Integrity {
+    fn check_integrity(&self, fields: &[Field], index_format: &IndexFormat) -> Vec<Problem>;
+}
+
+impl Integrity for Worksheet {
+    fn check_integrity(&self, fields: &[Field], index_format: &IndexFormat) -> Vec<Problem> {
+        let mut result = vec![];
+        let end_line_index = self.get_end_line_index();
+        if end_line_index == 0 {
+            return result;
+        }
+
+        let mut previous: Option<Index> = None;
+        let mut seen: Vec<(Index, u32)> = vec![];
+        for row_num in self.get_start_line_index(index_format)..=end_line_index {
+            let value = self.get_value((1, row_num));
+            let Some(index) = index_format.parse(&value) else {
+                if value.is_empty() && self.get_row_data(row_num).iter().all(|cell| cell.is_empty()) {
+                    result.push(Problem::BlankRow(row_num));
+                } else {
+                    result.push(Problem::MalformedIndex { row: row_num, value });
+                }
+                continue;
+            };
+
+            if let Some(&(_, first_row)) = seen.iter().find(|(seen_index, _)| *seen_index == index) {
+                result.push(Problem::Duplicate {
+                    row: row_num,
+                    value: value.clone(),
+                    first_row,
+                });
+            }
+            if previous.is_some_and(|previous| index < previous) {
+                result.push(Problem::OutOfOrder {
+                    row: row_num,
+                    value: value.clone(),
+                });
+            }
+            let missing_fields = fields
+                .iter()
+                .zip(3..)
+                .filter(|(field, col_num)| {
+                    field.required && self.get_value((*col_num, row_num)).is_empty()
+                })
+                .map(|(field, _)| field.name.clone())
+                .collect::<Vec<_>>();
+            if !missing_fields.is_empty() {
+                result.push(Problem::MissingFields {
+                    row: row_num,
+                    value,
+                    fields: missing_fields,
+                });
+            }
+
+            previous = Some(index);
+            seen.push((index, row_num));
+        }
+
+        for number in self.skips(index_format) {
+            result.push(Problem::Gap {
+                number,
+                row: seen
+                    .iter()
+                    .filter(|(index, _)| index.number < number)
+                    .max_by_key(|(index, _)| *index)
+                    .map(|(_, row_num)| *row_num),
+            });
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Integrity, Problem};
+    use crate::app::data_base::table::test::FromMatrix;
+    use crate::app::settings::index_format::IndexFormat;
+    use crate::app::settings::{Field, FieldType};
+    use umya_spreadsheet::new_file;
+
+    #[test]
+    fn check_integrity() {
+        let fields = vec![
+            Field::new(FieldType::Text, "Name".to_string(), true),
+            Field::new(FieldType::Text, "info".to_string(), false),
+        ];
+        let mut book = new_file();
+        let sheet = book.matrix_to_sheet(vec![
+            vec!["index", "date", "Name", "info"],
+            vec!["1", "d", "a", ""],
+            vec!["2", "d", "", "b"],
+            vec!["", "", "", ""],
+            vec!["x7", "d", "a", "b"],
+            vec!["2", "d", "a", "b"],
+            vec!["5", "d", "a", "b"],
+            vec!["4", "d", "a", "b"],
+        ]);
+        assert_eq!(
+            vec![
+                Problem::MissingFields {
+                    row: 3,
+                    value: "2".to_string(),
+                    fields: vec!["Name".to_string()],
+                },
+                Problem::BlankRow(4),
+                Problem::MalformedIndex {
+                    row: 5,
+                    value: "x7".to_string(),
+                },
+                Problem::Duplicate {
+                    row: 6,
+                    value: "2".to_string(),
+                    first_row: 3,
+                },
+                Problem::OutOfOrder {
+                    row: 8,
+                    value: "4".to_string(),
+                },
+                Problem::Gap {
+                    number: 3,
+                    row: Some(6),
+                },
+            ],
+            sheet.check_integrity(&fields, &IndexFormat::default())
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use crate::app::data_base::table::Table;
+use umya_spreadsheet::Spreadsheet;
+
+/// Hidden sheet holding rows of the form `key | value | value ...`.
+pub const META_DATA_SHEET: &str = "metadata";
+
+pub trait MetaData {
+    fn get_meta_data(&self, key: &str) -> Vec<Vec<String>>;
+
+    fn set_meta_data(&mut self, key: &str, rows: Vec<Vec<String>>);
+}
+
+impl MetaData for Spreadsheet {
+    fn get_meta_data(&self, key: &str) -> Vec<Vec<String>> {
+        get_keyed_rows(self, META_DATA_SHEET, key)
+    }
+
+    fn set_meta_data(&mut self, key: &str, rows: Vec<Vec<String>>) {
+        set_keyed_rows(self, META_DATA_SHEET, key, rows, true)
+    }
+}
+
+/// Rows of a sheet whose first cell is `key`, without the key.
+pub fn get_keyed_rows(book: &Spreadsheet, sheet_name: &str, key: &str) -> Vec<Vec<String>> {
+    match book.get_sheet_by_name(sheet_name) {
+        Some(sheet) => (1..=sheet.get_highest_row())
+            .filter(|&row_num| sheet.get_value((1, row_num)) == key)
+            .map(|row_num| sheet.get_row_data(row_num))
+            .collect(),
+        None => vec![],
+    }
+}
+
+/// Replaces the rows of a sheet with the given key, creating the sheet if needed.
+pub fn set_keyed_rows(
+    book: &mut Spreadsheet,
+    sheet_name: &str,
+    key: &str,
+    rows: Vec<Vec<String>>,
+    hidden: bool,
+) {
+    if book.get_sheet_by_name(sheet_name).is_none() {
+        let sheet = book
+            .new_sheet(sheet_name)
+            .expect("Can not create sheet");
+        if hidden {
+            sheet.set_sheet_state("hidden".to_string());
+        }
+    }
+    let sheet = book
+        .get_sheet_by_name_mut(sheet_name)
+        .expect("Can not get sheet");
+
+    let other_rows = (1..=sheet.get_highest_row())
+        .filter(|&row_num| {
+            let row_key = sheet.get_value((1, row_num));
+            !row_key.is_empty() && row_key != key
+        })
+        .map(|row_num| [vec![sheet.get_value((1, row_num))], sheet.get_row_data(row_num)].concat())
+        .collect::<Vec<_>>();
+    let highest_row = sheet.get_highest_row();
+    if highest_row > 0 {
+        sheet.remove_row(&1, &highest_row);
+    }
+
+    for (row, row_num) in other_rows
+        .into_iter()
+        .chain(rows.into_iter().map(|row| [vec![key.to_string()], row].concat()))
+        .zip(1..)
+    {
+        // kept as text, so values like `007` or `15.10` are not turned into numbers
+        for (value, col_num) in row.into_iter().zip(1..) {
+            sheet.get_cell_mut((col_num, row_num)).set_value_string(value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MetaData;
+    use umya_spreadsheet::new_file;
+
+    #[test]
+    fn set_meta_data() {
+        let mut book = new_file();
+        assert_eq!(Vec::<Vec<String>>::new(), book.get_meta_data("reserved"));
+
+        book.set_meta_data(
+            "reserved",
+            vec![vec!["1000".to_string(), "1499".to_string(), "sieving".to_string()]],
+        );
+        book.set_meta_data("other", vec![vec!["a".to_string()]]);
+        book.set_meta_data(
+            "reserved",
+            vec![
+                vec!["1000".to_string(), "1499".to_string(), "sieving".to_string()],
+                vec!["2000".to_string(), "2099".to_string(), "".to_string()],
+            ],
+        );
+
+        assert_eq!(vec![vec!["a"]], book.get_meta_data("other"));
+        assert_eq!(
+            vec![vec!["1000", "1499", "sieving"], vec!["2000", "2099", ""]],
+            book.get_meta_data("reserved")
+        );
+    }
+}
@@ -1,19 +1,54 @@
-mod create_record;
+pub mod audit;
+pub mod boxes;
+pub mod catalogue;
+pub mod contexts;
+pub mod create_record;
+pub mod export;
+pub mod index;
+pub mod integrity;
+mod meta_data;
+pub mod photos;
+pub mod record;
+pub mod references;
+pub mod report;
+pub mod reservation;
 pub(super) mod sort_cells;
+pub mod spatial;
+pub mod statistics;
+pub mod status;
+pub mod sync;
 pub mod table;
+pub mod total_station;
+use audit::AuditTrail;
 use create_record::CreateRecord;
+use index::Index;
+use meta_data::MetaData;
+use report::fill;
+use reservation::{Reservation, Skip};
+use sync::OperationKind;
 
+use crate::app::i18n::tr;
+use crate::app::settings::index_format::IndexFormat;
 use crate::app::settings::insert_methods::{InsertMethods, InsertMethodsData, StartEnd};
-use crate::app::settings::print_settings::PrintSettings;
+use crate::app::settings::Field;
 use std::path::Path;
+use std::sync::{Mutex, MutexGuard, PoisonError};
 use table::Table;
 use umya_spreadsheet::*;
 
+static FILE_LOCK: Mutex<()> = Mutex::new(());
+static TRANSACTION_LOCK: Mutex<()> = Mutex::new(());
+
 pub struct DataBase {
     book: Spreadsheet,
 }
 
 impl DataBase {
+    /// Serializes read-modify-write operations on the database between the GUI and the API.
+    pub fn lock() -> MutexGuard<'static, ()> {
+        TRANSACTION_LOCK.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
     pub fn get_sheet(&self) -> &Worksheet {
         Worksheet::new(&self.book)
     }
@@ -24,34 +59,279 @@ impl DataBase {
 
     pub fn create_record(
         path: &Path,
-        print_settings: &PrintSettings,
         quantity: u32,
         data: Vec<String>,
+        fields: &[Field],
         insert_methods_data: &InsertMethodsData,
-    ) {
+        index_format: &IndexFormat,
+        recorder: Option<&str>,
+    ) -> Vec<Index> {
         let mut data_base = DataBase::from(path);
+        let reservations = data_base.get_reservations();
+        let replaced = match insert_methods_data.insert_methods {
+            InsertMethods::Input => Some(insert_methods_data.input),
+            InsertMethods::AutoInsert => Some(insert_methods_data.auto_insert.into()),
+            _ => None,
+        }
+        .filter(|&index| {
+            data_base
+                .get_sheet()
+                .get_row_index_from_index(index, index_format)
+                .is_some()
+        });
+        let is_replace = replaced.is_some();
+        // the links of the overwritten record, to unlink the finds it no longer refers to
+        let old_references = references::reference_ids(fields)
+            .into_iter()
+            .map(|id| {
+                let old = replaced
+                    .map(|index| data_base.get_references(index, id, index_format))
+                    .unwrap_or_default();
+                (id, old)
+            })
+            .collect::<Vec<_>>();
         let sheet = data_base.get_sheet_mut();
-        match insert_methods_data.insert_methods.clone() {
+        let result = match insert_methods_data.insert_methods.clone() {
             InsertMethods::StartEnd => match insert_methods_data.start_end {
-                StartEnd::Start => sheet.create_start_record(quantity, data),
-                StartEnd::End => sheet.create_end_record(quantity, data),
+                StartEnd::Start => {
+                    sheet.create_start_record(quantity, data, index_format, recorder)
+                }
+                StartEnd::End => {
+                    sheet.create_end_record(quantity, data, index_format, &reservations, recorder)
+                }
             },
-            InsertMethods::Input => sheet.create_record_from_index(insert_methods_data.input, data),
-            InsertMethods::AutoInsert => {
-                sheet.create_record_from_index(insert_methods_data.auto_insert, data)
+            InsertMethods::Input | InsertMethods::AutoInsert => {
+                let index = if insert_methods_data.insert_methods == InsertMethods::Input {
+                    insert_methods_data.input
+                } else {
+                    insert_methods_data.auto_insert.into()
+                };
+                if insert_methods_data.sorted_insert {
+                    sheet.create_sorted_record_from_index(index, data, index_format, recorder)
+                } else {
+                    sheet.create_record_from_index(index, data, index_format, recorder)
+                }
             }
+            InsertMethods::Fragments => {
+                sheet.create_sub_records(
+                    insert_methods_data.parent,
+                    quantity,
+                    index_format,
+                    recorder,
+                )
+            }
+        };
+
+        for &index in &result {
+            for (id, old) in &old_references {
+                data_base.link_references(index, *id, old, index_format);
+            }
+        }
+        data_base.log_operations(
+            if is_replace {
+                OperationKind::Replace
+            } else {
+                OperationKind::Create
+            },
+            &result,
+            recorder.unwrap_or_default(),
+        );
+        data_base.save(path);
+        result
+    }
+
+    /// Checks that a record can be created without overwriting, running out of indices
+    /// or taking a reserved one. A typed index in a reserved range has to be confirmed
+    /// like an overwrite, so the team owning the range can still record there.
+    pub fn check_create(
+        &self,
+        quantity: u32,
+        insert_methods_data: &InsertMethodsData,
+        replace: bool,
+        index_format: &IndexFormat,
+        language: &str,
+    ) -> Result<(), String> {
+        let sheet = self.get_sheet();
+        let exists = |index: Index| sheet.get_row_index_from_index(index, index_format).is_some();
+        let reservations = self.get_reservations();
+        let check_reserved = |number: u32| match reservations
+            .iter()
+            .find(|reservation| reservation.contains(number))
+        {
+            Some(reservation) => Err(fill(
+                tr(language, "The index {index} is {reservation}"),
+                &[
+                    ("index", number.to_string()),
+                    ("reservation", reservation.text(language)),
+                ],
+            )),
+            None => Ok(()),
         };
-        
+        match insert_methods_data.insert_methods {
+            InsertMethods::StartEnd => {
+                if insert_methods_data.start_end == StartEnd::Start {
+                    let start_index = sheet.get_start_index(index_format);
+                    if start_index <= quantity {
+                        return Err(tr(
+                            language,
+                            "There are not enough free indices before the first record",
+                        )
+                        .to_string());
+                    }
+                    (start_index - quantity..start_index).try_for_each(check_reserved)?;
+                }
+            }
+            InsertMethods::Input | InsertMethods::AutoInsert => {
+                let index = if insert_methods_data.insert_methods == InsertMethods::Input {
+                    insert_methods_data.input
+                } else {
+                    insert_methods_data.auto_insert.into()
+                };
+                if !replace && exists(index) {
+                    return Err(fill(
+                        tr(language, "The record {index} already exists"),
+                        &[("index", index_format.format(index))],
+                    ));
+                }
+                if !replace || insert_methods_data.insert_methods == InsertMethods::AutoInsert {
+                    check_reserved(index.number)?;
+                }
+            }
+            InsertMethods::Fragments => {
+                if !exists(insert_methods_data.parent.into()) {
+                    return Err(fill(
+                        tr(language, "The parent record {index} does not exist"),
+                        &[("index", index_format.format(insert_methods_data.parent.into()))],
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn sort_by_index(path: &Path, index_format: &IndexFormat) {
+        let mut data_base = DataBase::from(path);
+        data_base.get_sheet_mut().sort_by_index(index_format);
         data_base.save(path)
     }
 
+    /// Sets one field of several records, e.g. the location of the finds put into a box.
+    /// `data_id` counts from the date, so the first field is 1. Returns the updated indices.
+    pub fn update_field(
+        path: &Path,
+        indices: &[Index],
+        data_id: usize,
+        value: &str,
+        fields: &[Field],
+        index_format: &IndexFormat,
+        recorder: Option<&str>,
+    ) -> Vec<Index> {
+        let mut data_base = DataBase::from(path);
+        let is_reference = references::reference_ids(fields).contains(&data_id);
+        let mut updated = vec![];
+        for &index in indices {
+            let old = if is_reference {
+                data_base.get_references(index, data_id, index_format)
+            } else {
+                vec![]
+            };
+            let sheet = data_base.get_sheet_mut();
+            let Some(row) = sheet.get_row_index_from_index(index, index_format) else {
+                continue;
+            };
+            sheet
+                .get_cell_mut((data_id as u32 + 2, row))
+                .set_value(value);
+            if let Some(recorder) = recorder {
+                sheet.stamp(row, recorder, false);
+            }
+            if is_reference {
+                data_base.link_references(index, data_id, &old, index_format);
+            }
+            updated.push(index);
+        }
+        data_base.log_operations(OperationKind::Replace, &updated, recorder.unwrap_or_default());
+        data_base.save(path);
+        updated
+    }
+
+    pub fn get_reservations(&self) -> Vec<Reservation> {
+        self.book
+            .get_meta_data("reserved")
+            .iter()
+            .filter_map(|row| Reservation::from_row(row))
+            .collect()
+    }
+
+    pub fn set_reservations(&mut self, reservations: &[Reservation]) {
+        self.book.set_meta_data(
+            "reserved",
+            reservations.iter().map(Reservation::to_row).collect(),
+        )
+    }
+
+    /// Checks that a new reservation neither overlaps another one nor covers recorded indices.
+    pub fn check_reservation(
+        &self,
+        reservation: &Reservation,
+        index_format: &IndexFormat,
+        language: &str,
+    ) -> Result<(), String> {
+        if reservation.start > reservation.end {
+            return Err(
+                tr(language, "The first index of the range is after the last one").to_string(),
+            );
+        }
+        if let Some(other) = self
+            .get_reservations()
+            .iter()
+            .find(|other| other.overlaps(reservation))
+        {
+            return Err(fill(
+                tr(language, "The range overlaps {reservation}"),
+                &[("reservation", other.text(language))],
+            ));
+        }
+        match self
+            .get_sheet()
+            .get_indices(index_format)
+            .into_iter()
+            .find(|(_, index)| reservation.contains(index.number))
+        {
+            Some((_, index)) => Err(fill(
+                tr(language, "The record {index} is already in the range"),
+                &[("index", index_format.format(index))],
+            )),
+            None => Ok(()),
+        }
+    }
+
+    pub fn skips(&self, index_format: &IndexFormat) -> Vec<Skip> {
+        self.get_sheet()
+            .skips_with_reservations(index_format, &self.get_reservations())
+    }
+
+    /// The fields of the last record without its audit and photos, to prefill a new one.
+    pub fn last_fields(&self, size: usize) -> Vec<String> {
+        let sheet = self.get_sheet();
+        let mut result = sheet
+            .get_row_data(sheet.get_end_line_index())
+            .into_iter()
+            .skip(1)
+            .collect::<Vec<_>>();
+        result.resize(size, "".to_string());
+        result
+    }
+
     pub fn save(&self, path: &Path) {
+        let _lock = FILE_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
         writer::xlsx::write(&self.book, path).unwrap()
     }
 }
 
 impl From<&Path> for DataBase {
     fn from(path: &Path) -> Self {
+        let _lock = FILE_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
         Self {
             book: {
                 if path.exists() {
@@ -64,3 +344,101 @@ impl From<&Path> for DataBase {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::audit::AuditTrail;
+    use super::create_record::CreateRecord;
+    use super::photos::Photos;
+    use super::reservation::Reservation;
+    use super::table::Table;
+    use super::DataBase;
+    use crate::app::settings::index_format::IndexFormat;
+    use crate::app::settings::insert_methods::{InsertMethods, InsertMethodsData, StartEnd};
+    use umya_spreadsheet::new_file;
+
+    #[test]
+    fn check_create_with_reservations() {
+        let index_format = IndexFormat::default();
+        let mut data_base = DataBase { book: new_file() };
+        let sheet = data_base.get_sheet_mut();
+        sheet.set_row(1, vec!["10".to_string(), "01.07".to_string()]);
+        data_base.set_reservations(&[Reservation {
+            start: 5,
+            end: 7,
+            team: "sieving".to_string(),
+        }]);
+
+        let mut insert_methods_data = InsertMethodsData {
+            start_end: StartEnd::Start,
+            ..Default::default()
+        };
+        let check = |data: &InsertMethodsData, quantity: u32, replace: bool| {
+            data_base.check_create(quantity, data, replace, &index_format, "en")
+        };
+        assert!(check(&insert_methods_data, 2, false).is_ok());
+        assert!(check(&insert_methods_data, 3, false).is_err());
+
+        insert_methods_data.insert_methods = InsertMethods::AutoInsert;
+        insert_methods_data.auto_insert = 6;
+        assert!(check(&insert_methods_data, 1, false).is_err());
+        assert!(check(&insert_methods_data, 1, true).is_err());
+        insert_methods_data.auto_insert = 4;
+        assert!(check(&insert_methods_data, 1, false).is_ok());
+
+        // the sieving team confirms recording into its own range
+        insert_methods_data.insert_methods = InsertMethods::Input;
+        insert_methods_data.input = 6.into();
+        assert!(check(&insert_methods_data, 1, false).is_err());
+        assert!(check(&insert_methods_data, 1, true).is_ok());
+    }
+
+    #[test]
+    fn check_reservation() {
+        let index_format = IndexFormat::default();
+        let mut data_base = DataBase { book: new_file() };
+        data_base
+            .get_sheet_mut()
+            .set_row(1, vec!["10".to_string(), "01.07".to_string()]);
+        data_base.set_reservations(&[Reservation {
+            start: 5,
+            end: 7,
+            team: "sieving".to_string(),
+        }]);
+        let check = |start: u32, end: u32| {
+            let reservation = Reservation {
+                start,
+                end,
+                team: "".to_string(),
+            };
+            data_base.check_reservation(&reservation, &index_format, "en")
+        };
+
+        assert!(check(1, 4).is_ok());
+        assert!(check(11, 20).is_ok());
+        assert!(check(4, 1).is_err());
+        assert!(check(6, 8).is_err());
+        assert_eq!(Err("The record 10 is already in the range".to_string()), check(8, 12));
+    }
+
+    #[test]
+    fn last_fields() {
+        let index_format = IndexFormat::default();
+        let mut data_base = DataBase { book: new_file() };
+        let sheet = data_base.get_sheet_mut();
+        sheet.set_row(1, vec!["1".to_string(), "01.07".to_string(), "pot".to_string()]);
+        sheet.stamp(1, "Anna", true);
+        sheet.set_photos(1, &["1_1.jpg".to_string()]);
+
+        let fields = data_base.last_fields(2);
+        assert_eq!(vec!["pot", ""], fields);
+
+        // the new record gets neither the audit nor the photos of the last one
+        let data = [vec!["02.07".to_string()], fields].concat();
+        let sheet = data_base.get_sheet_mut();
+        sheet.create_end_record(1, data.clone(), &index_format, &[], Some("Boris"));
+        assert_eq!(data, sheet.get_row_data(2));
+        assert!(sheet.get_photos(2).is_empty());
+        assert_eq!("Boris", sheet.get_audit(2).recorder);
+    }
+}
@@ -0,0 +1,178 @@
+use crate::app::data_base::audit::{now, AUDIT_COLUMN};
+use crate::app::data_base::index::Index;
+use crate::app::data_base::meta_data::MetaData;
+use crate::app::data_base::table::Table;
+use crate::app::data_base::DataBase;
+use crate::app::settings::index_format::IndexFormat;
+use std::fs;
+use std::path::{Path, PathBuf};
+use umya_spreadsheet::Worksheet;
+
+/// The column listing the photo files of a record, right after the audit columns.
+pub const PHOTOS_COLUMN: u32 = AUDIT_COLUMN + 3;
+const SEPARATOR: &str = "; ";
+
+pub const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "tif", "tiff", "heic", "webp"];
+
+pub trait Photos {
+    fn get_photos(&self, row_num: u32) -> Vec<String>;
+
+    fn set_photos(&mut self, row_num: u32, photos: &[String]);
+}
+
+impl Photos for Worksheet {
+    fn get_photos(&self, row_num: u32) -> Vec<String> {
+        self.get_value((PHOTOS_COLUMN, row_num))
+            .split(SEPARATOR)
+            .filter(|photo| !photo.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn set_photos(&mut self, row_num: u32, photos: &[String]) {
+        self.get_cell_mut((PHOTOS_COLUMN, row_num))
+            .set_value_string(photos.join(SEPARATOR));
+    }
+}
+
+/// The name of the `number`-th photo of a record, e.g. `AB-2024_15_2.jpg`.
+pub fn photo_name(index: &str, number: usize, extension: &str) -> String {
+    let index = index
+        .chars()
+        .map(|char| {
+            if char.is_alphanumeric() || char == '-' || char == '.' {
+                char
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    format!("{}_{}.{}", index, number, extension.to_lowercase())
+}
+
+pub fn is_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| IMAGE_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+}
+
+impl DataBase {
+    fn copy_photos(
+        &mut self,
+        media: &Path,
+        index: Index,
+        files: &[PathBuf],
+        index_format: &IndexFormat,
+    ) -> Result<Vec<String>, String> {
+        let sheet = self.get_sheet_mut();
+        let row = sheet
+            .get_row_index_from_index(index, index_format)
+            .ok_or(format!("The record {} does not exist", index_format.format(index)))?;
+        fs::create_dir_all(media).map_err(|error| error.to_string())?;
+
+        let mut photos = sheet.get_photos(row);
+        let mut attached = vec![];
+        for file in files {
+            let extension = file
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .unwrap_or("jpg");
+            let name = (photos.len() + 1..)
+                .map(|number| photo_name(&index_format.format(index), number, extension))
+                .find(|name| !photos.contains(name) && !media.join(name).exists())
+                .expect("Can not name the photo");
+            fs::copy(file, media.join(&name)).map_err(|error| {
+                format!("Can not copy {}: {}", file.to_string_lossy(), error)
+            })?;
+            photos.push(name.clone());
+            attached.push(name);
+        }
+        sheet.set_photos(row, &photos);
+        Ok(attached)
+    }
+
+    /// Copies the images into the media folder, named after the index, and lists them in the record.
+    pub fn attach_photos(
+        path: &Path,
+        media: &Path,
+        index: Index,
+        files: &[PathBuf],
+        index_format: &IndexFormat,
+    ) -> Result<Vec<String>, String> {
+        let mut data_base = DataBase::from(path);
+        let attached = data_base.copy_photos(media, index, files, index_format)?;
+        data_base.save(path);
+        Ok(attached)
+    }
+
+    /// The files already taken from the camera folder, with the photos they became.
+    pub fn get_ingested(&self) -> Vec<(String, String)> {
+        self.book
+            .get_meta_data("ingested")
+            .into_iter()
+            .filter_map(|row| Some((row.first()?.clone(), row.get(1)?.clone())))
+            .collect()
+    }
+
+    /// Attaches new camera files to a record and logs which file became which photo.
+    /// Files taken before are skipped, so the camera folder can be left as it is.
+    pub fn ingest_photos(
+        path: &Path,
+        media: &Path,
+        index: Index,
+        files: &[PathBuf],
+        index_format: &IndexFormat,
+    ) -> Result<Vec<(String, String)>, String> {
+        let mut data_base = DataBase::from(path);
+        let ingested = data_base.get_ingested();
+        let files = files
+            .iter()
+            .filter(|file| {
+                !ingested
+                    .iter()
+                    .any(|(original, _)| *original == file.to_string_lossy())
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        let photos = data_base.copy_photos(media, index, &files, index_format)?;
+
+        let mapping = files
+            .iter()
+            .map(|file| file.to_string_lossy().to_string())
+            .zip(photos)
+            .collect::<Vec<_>>();
+        let time = now();
+        let mut rows = data_base.book.get_meta_data("ingested");
+        rows.extend(mapping.iter().map(|(original, photo)| {
+            vec![
+                original.clone(),
+                photo.clone(),
+                index_format.format(index),
+                time.clone(),
+            ]
+        }));
+        data_base.book.set_meta_data("ingested", rows);
+        data_base.save(path);
+        Ok(mapping)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{photo_name, Photos};
+    use crate::app::data_base::table::{test::FromMatrix, Table};
+    use umya_spreadsheet::new_file;
+
+    #[test]
+    fn photos() {
+        assert_eq!("15_1.jpg", photo_name("15", 1, "JPG"));
+        assert_eq!("AB_2024_15.1_3.png", photo_name("AB/2024 15.1", 3, "png"));
+
+        let mut book = new_file();
+        let test_sheet = book.matrix_to_sheet(vec![vec!["15", "01.07", "pot"]]);
+        assert!(test_sheet.get_photos(1).is_empty());
+        test_sheet.set_photos(1, &["15_1.jpg".to_string(), "15_2.jpg".to_string()]);
+        assert_eq!(vec!["15_1.jpg", "15_2.jpg"], test_sheet.get_photos(1));
+        assert_eq!(vec!["01.07", "pot"], test_sheet.get_row_data(1));
+    }
+}
@@ -0,0 +1,19 @@
+use crate::app::data_base::audit::Audit;
+use crate::app::data_base::index::Index;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Record {
+    pub row: u32,
+    pub index: Index,
+    /// Cells after the index column: the date followed by the fields.
+    pub data: Vec<String>,
+    pub audit: Audit,
+    /// File names in the media folder.
+    pub photos: Vec<String>,
+}
+
+impl Record {
+    pub fn is_sub_record(&self) -> bool {
+        self.index.sub.is_some()
+    }
+}
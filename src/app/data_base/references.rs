@@ -0,0 +1,207 @@
+use crate::app::data_base::index::Index;
+use crate::app::data_base::report::fill;
+use crate::app::data_base::table::Table;
+use crate::app::data_base::DataBase;
+use crate::app::i18n::tr;
+use crate::app::settings::index_format::IndexFormat;
+use crate::app::settings::{Field, FieldType};
+
+/// The links of a reference field, e.g. `233; 240.1`. `None` if one of them is not an index.
+pub fn parse_references(text: &str, index_format: &IndexFormat) -> Option<Vec<Index>> {
+    text.split([';', ','])
+        .map(str::trim)
+        .filter(|reference| !reference.is_empty())
+        .map(|reference| index_format.parse(reference))
+        .collect()
+}
+
+pub fn format_references(indices: &[Index], index_format: &IndexFormat) -> String {
+    indices
+        .iter()
+        .map(|index| index_format.format(*index))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// The data ids of the reference fields, counted from the date.
+pub fn reference_ids(fields: &[Field]) -> Vec<usize> {
+    fields
+        .iter()
+        .enumerate()
+        .filter(|(_, field)| field.field_type == FieldType::Reference)
+        .map(|(id, _)| id + 1)
+        .collect()
+}
+
+impl DataBase {
+    /// Checks that every find a new record links to exists.
+    pub fn check_references(
+        &self,
+        data: &[String],
+        fields: &[Field],
+        index_format: &IndexFormat,
+        language: &str,
+    ) -> Result<(), String> {
+        let sheet = self.get_sheet();
+        for id in reference_ids(fields) {
+            let text = data.get(id).map(String::as_str).unwrap_or_default();
+            let references = parse_references(text, index_format).ok_or_else(|| {
+                fill(
+                    tr(language, "{field} expects indices separated by \";\", got \"{text}\""),
+                    &[
+                        ("field", fields[id - 1].display_name(language).to_string()),
+                        ("text", text.to_string()),
+                    ],
+                )
+            })?;
+            if let Some(missing) = references
+                .iter()
+                .find(|&&index| sheet.get_row_index_from_index(index, index_format).is_none())
+            {
+                return Err(fill(
+                    tr(language, "The linked record {index} does not exist"),
+                    &[("index", index_format.format(*missing))],
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// The links stored in a reference field of a record, unreadable links are skipped.
+    pub(super) fn get_references(
+        &self,
+        index: Index,
+        data_id: usize,
+        index_format: &IndexFormat,
+    ) -> Vec<Index> {
+        let sheet = self.get_sheet();
+        sheet
+            .get_row_index_from_index(index, index_format)
+            .map(|row| sheet.get_value((data_id as u32 + 2, row)))
+            .map(|text| {
+                text.split([';', ','])
+                    .filter_map(|reference| index_format.parse(reference))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn set_references(
+        &mut self,
+        index: Index,
+        data_id: usize,
+        references: &[Index],
+        index_format: &IndexFormat,
+    ) {
+        let sheet = self.get_sheet_mut();
+        if let Some(row) = sheet.get_row_index_from_index(index, index_format) {
+            sheet
+                .get_cell_mut((data_id as u32 + 2, row))
+                .set_value_string(format_references(references, index_format));
+        }
+    }
+
+    /// The links of every reference field of a record, by data id.
+    pub(super) fn get_all_references(
+        &self,
+        index: Index,
+        fields: &[Field],
+        index_format: &IndexFormat,
+    ) -> Vec<(usize, Vec<Index>)> {
+        reference_ids(fields)
+            .into_iter()
+            .map(|id| (id, self.get_references(index, id, index_format)))
+            .collect()
+    }
+
+    /// `link_references` for every reference field of a rewritten record,
+    /// `old` is what `get_all_references` returned before.
+    pub(super) fn link_all_references(
+        &mut self,
+        index: Index,
+        old: &[(usize, Vec<Index>)],
+        index_format: &IndexFormat,
+    ) {
+        for (id, old) in old {
+            self.link_references(index, *id, old, index_format);
+        }
+    }
+
+    /// Keeps the links of `index` mutual: the records it now links to get a link back,
+    /// the ones it linked to before (`old`) and no longer does lose theirs.
+    pub(super) fn link_references(
+        &mut self,
+        index: Index,
+        data_id: usize,
+        old: &[Index],
+        index_format: &IndexFormat,
+    ) {
+        let new = self.get_references(index, data_id, index_format);
+        for &other in old.iter().filter(|other| !new.contains(other)) {
+            let mut references = self.get_references(other, data_id, index_format);
+            references.retain(|&reference| reference != index);
+            self.set_references(other, data_id, &references, index_format);
+        }
+        for &other in new.iter().filter(|&&other| other != index && !old.contains(&other)) {
+            let mut references = self.get_references(other, data_id, index_format);
+            if !references.contains(&index) {
+                references.push(index);
+                self.set_references(other, data_id, &references, index_format);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_references;
+    use crate::app::data_base::index::Index;
+    use crate::app::data_base::table::Table;
+    use crate::app::data_base::DataBase;
+    use crate::app::settings::index_format::IndexFormat;
+    use crate::app::settings::{Field, FieldType};
+    use umya_spreadsheet::new_file;
+
+    fn row(index: &str, joins: &str) -> Vec<String> {
+        vec![index.to_string(), "01.07".to_string(), "sherd".to_string(), joins.to_string()]
+    }
+
+    #[test]
+    fn references() {
+        let index_format = IndexFormat::default();
+        assert_eq!(
+            Some(vec![Index::new(233, None), Index::new(240, Some(1))]),
+            parse_references("233; 240.1", &index_format)
+        );
+        assert_eq!(Some(vec![]), parse_references(" ", &index_format));
+        assert_eq!(None, parse_references("233; pot", &index_format));
+
+        let mut data_base = DataBase { book: new_file() };
+        let sheet = data_base.get_sheet_mut();
+        sheet.set_row(1, row("1", ""));
+        sheet.set_row(2, row("2", ""));
+        sheet.set_row(3, row("3", "1; 2"));
+
+        let fields = vec![
+            Field::new(FieldType::Text, "Name".to_string(), true),
+            Field::new(FieldType::Reference, "Joins".to_string(), false),
+        ];
+        let data = |joins: &str| vec!["01.07".to_string(), "sherd".to_string(), joins.to_string()];
+        let check = |joins| data_base.check_references(&data(joins), &fields, &index_format, "en");
+        assert!(check("1; 2").is_ok());
+        assert!(check("").is_ok());
+        assert_eq!(Err("The linked record 7 does not exist".to_string()), check("1; 7"));
+        assert!(check("one").is_err());
+
+        let (one, two, three) = (Index::new(1, None), Index::new(2, None), Index::new(3, None));
+        data_base.link_references(three, 2, &[], &index_format);
+        assert_eq!(vec![three], data_base.get_references(one, 2, &index_format));
+        assert_eq!(vec![three], data_base.get_references(two, 2, &index_format));
+
+        // 3 no longer joins 1
+        data_base.get_sheet_mut().set_row(3, row("3", "2"));
+        data_base.link_references(three, 2, &[one, two], &index_format);
+        assert!(data_base.get_references(one, 2, &index_format).is_empty());
+        assert_eq!(vec![three], data_base.get_references(two, 2, &index_format));
+    }
+}
@@ -0,0 +1,327 @@
+use crate::app::data_base::record::Record;
+use crate::app::data_base::reservation::Skip;
+use crate::app::data_base::sync::{Operation, OperationKind};
+use crate::app::data_base::table::Table;
+use crate::app::data_base::DataBase;
+use crate::app::settings::index_format::IndexFormat;
+use crate::app::settings::report_settings::ReportSettings;
+use crate::app::settings::Field;
+use printpdf::{BuiltinFont, Mm, PdfDocument};
+use std::fs::File;
+use std::path::Path;
+
+pub const DEFAULT_TEMPLATE: &str = "# Excavation report {date}
+
+Finds registered: {total}
+
+## Finds
+
+{finds}
+## Gaps
+
+{gaps}
+
+## Replacements
+
+{replacements}
+";
+
+/// The finds registered on one day.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DailyReport {
+    pub date: String,
+    /// The finds by square, tomb or the other group fields, in the order of the indices.
+    pub groups: Vec<(String, Vec<Record>)>,
+    /// Free numbers between the first and the last find of the day.
+    pub gaps: Vec<u32>,
+    pub replacements: Vec<Operation>,
+}
+
+/// Replaces every `{name}` in the template with its value.
+pub fn fill(template: &str, values: &[(&str, String)]) -> String {
+    values
+        .iter()
+        .fold(template.to_string(), |result, (name, value)| {
+            result.replace(&format!("{{{}}}", name), value)
+        })
+}
+
+/// Numbers as ranges, e.g. `15, 17-19`.
+pub fn ranges_text(numbers: &[u32]) -> String {
+    let mut ranges: Vec<(u32, u32)> = vec![];
+    for &number in numbers {
+        match ranges.last_mut() {
+            Some((_, last)) if *last + 1 == number => *last = number,
+            _ => ranges.push((number, number)),
+        }
+    }
+    ranges
+        .iter()
+        .map(|&(first, last)| {
+            if first == last {
+                first.to_string()
+            } else {
+                format!("{}-{}", first, last)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl DailyReport {
+    pub fn total(&self) -> usize {
+        self.groups.iter().map(|(_, records)| records.len()).sum()
+    }
+
+    pub fn to_markdown(
+        &self,
+        template: &str,
+        fields: &[Field],
+        settings: &ReportSettings,
+        index_format: &IndexFormat,
+    ) -> String {
+        let finds = self
+            .groups
+            .iter()
+            .map(|(group, records)| {
+                let lines = records
+                    .iter()
+                    .map(|record| {
+                        let description = fields
+                            .iter()
+                            .zip(record.data.iter().skip(1))
+                            .filter(|(field, value)| {
+                                !value.is_empty() && !settings.group_fields.contains(&field.name)
+                            })
+                            .map(|(_, value)| value.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        format!("- {} {}\n", index_format.format(record.index), description)
+                    })
+                    .collect::<String>();
+                format!("### {} ({})\n\n{}\n", group, records.len(), lines)
+            })
+            .collect::<String>();
+        let gaps = if self.gaps.is_empty() {
+            "none".to_string()
+        } else {
+            ranges_text(&self.gaps)
+        };
+        let replacements = if self.replacements.is_empty() {
+            "none".to_string()
+        } else {
+            self.replacements
+                .iter()
+                .map(|operation| {
+                    format!(
+                        "- {} {} {}",
+                        index_format.format(operation.index),
+                        operation.time,
+                        operation.recorder
+                    )
+                    .trim_end()
+                    .to_string()
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        fill(
+            template,
+            &[
+                ("date", self.date.clone()),
+                ("total", self.total().to_string()),
+                ("finds", finds),
+                ("gaps", gaps),
+                ("replacements", replacements),
+            ],
+        )
+    }
+}
+
+impl DataBase {
+    /// The finds whose date (column 2) is `date`, with the gaps and replacements of that day.
+    pub fn daily_report(
+        &self,
+        date: &str,
+        fields: &[Field],
+        settings: &ReportSettings,
+        index_format: &IndexFormat,
+    ) -> DailyReport {
+        let group_ids = settings
+            .group_fields
+            .iter()
+            .filter_map(|name| Some((name, fields.iter().position(|field| field.name == *name)? + 1)))
+            .collect::<Vec<_>>();
+        let records = self
+            .get_sheet()
+            .get_records(fields.len() + 1, index_format)
+            .into_iter()
+            .filter(|record| record.data.first().is_some_and(|other| other.trim() == date))
+            .collect::<Vec<_>>();
+
+        let mut groups: Vec<(String, Vec<Record>)> = vec![];
+        for record in &records {
+            let group = group_ids
+                .iter()
+                .filter_map(|&(name, id)| {
+                    let value = record.data.get(id).filter(|value| !value.is_empty())?;
+                    Some(format!("{} {}", name, value))
+                })
+                .collect::<Vec<_>>()
+                .join(" / ");
+            let group = if group.is_empty() {
+                "Ungrouped".to_string()
+            } else {
+                group
+            };
+            match groups.iter_mut().find(|(other, _)| *other == group) {
+                Some((_, group_records)) => group_records.push(record.clone()),
+                None => groups.push((group, vec![record.clone()])),
+            }
+        }
+        groups.sort_by(|(group1, _), (group2, _)| group1.cmp(group2));
+
+        let numbers = records.iter().map(|record| record.index.number);
+        let (first, last) = (numbers.clone().min(), numbers.max());
+        let gaps = match (first, last) {
+            (Some(first), Some(last)) => self
+                .skips(index_format)
+                .into_iter()
+                .filter_map(|skip| match skip {
+                    Skip::Free(number) if first < number && number < last => Some(number),
+                    _ => None,
+                })
+                .collect(),
+            _ => vec![],
+        };
+
+        DailyReport {
+            date: date.to_string(),
+            groups,
+            gaps,
+            replacements: self
+                .get_operations()
+                .into_iter()
+                .filter(|operation| {
+                    operation.kind == OperationKind::Replace && operation.time.starts_with(date)
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Lays the Markdown text out on A4 pages, the headings larger.
+/// The given font is used when it can be loaded, so non-latin text is printed too.
+pub fn markdown_to_pdf(title: &str, markdown: &str, font: &Path) -> Result<Vec<u8>, String> {
+    const MARGIN: f32 = 20.;
+    const WIDTH: f32 = 210.;
+    const HEIGHT: f32 = 297.;
+
+    let (document, page, layer) = PdfDocument::new(title, Mm(WIDTH), Mm(HEIGHT), "report");
+    let font = match File::open(font) {
+        Ok(file) => document.add_external_font(file),
+        Err(_) => document.add_builtin_font(BuiltinFont::Helvetica),
+    }
+    .map_err(|error| error.to_string())?;
+
+    let mut layer = document.get_page(page).get_layer(layer);
+    let mut y = HEIGHT - MARGIN;
+    for line in markdown.lines() {
+        let (size, line) = match line {
+            line if line.starts_with("### ") => (12., &line[4..]),
+            line if line.starts_with("## ") => (14., &line[3..]),
+            line if line.starts_with("# ") => (18., &line[2..]),
+            line => (10., line),
+        };
+        // points to millimetres
+        let height = size * 0.3528 * 1.4;
+        let max_chars = ((WIDTH - 2. * MARGIN) / (size * 0.3528 * 0.55)) as usize;
+        let chars = line.chars().collect::<Vec<_>>();
+        let parts = if chars.is_empty() {
+            vec![String::new()]
+        } else {
+            chars
+                .chunks(max_chars)
+                .map(|part| part.iter().collect())
+                .collect()
+        };
+        for part in parts {
+            if y - height < MARGIN {
+                let (page, new_layer) = document.add_page(Mm(WIDTH), Mm(HEIGHT), "report");
+                layer = document.get_page(page).get_layer(new_layer);
+                y = HEIGHT - MARGIN;
+            }
+            y -= height;
+            layer.use_text(part, size, Mm(MARGIN), Mm(y), &font);
+        }
+    }
+    document.save_to_bytes().map_err(|error| error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ranges_text, DEFAULT_TEMPLATE};
+    use crate::app::data_base::index::Index;
+    use crate::app::data_base::sync::{Operation, OperationKind};
+    use crate::app::data_base::table::Table;
+    use crate::app::data_base::DataBase;
+    use crate::app::settings::index_format::IndexFormat;
+    use crate::app::settings::report_settings::ReportSettings;
+    use crate::app::settings::{Field, FieldType};
+    use umya_spreadsheet::new_file;
+
+    #[test]
+    fn daily_report() {
+        assert_eq!("15, 17-19", ranges_text(&[15, 17, 18, 19]));
+
+        let format = IndexFormat::default();
+        let fields = vec![
+            Field::new(FieldType::Text, "Name".to_string(), true),
+            Field::new(FieldType::Text, "Square".to_string(), true),
+        ];
+        let mut data_base = DataBase { book: new_file() };
+        for (row, row_num) in [
+            vec!["1", "2024-07-01", "pot", "A1"],
+            vec!["2", "2024-07-02", "bead", "B2"],
+            vec!["4", "2024-07-02", "coin", "A1"],
+            vec!["4.1", "2024-07-02", "coin", "A1"],
+            vec!["6", "2024-07-02", "nail", ""],
+        ]
+        .into_iter()
+        .zip(1..)
+        {
+            data_base
+                .get_sheet_mut()
+                .set_row(row_num, row.into_iter().map(str::to_string).collect());
+        }
+        let replace = Operation {
+            kind: OperationKind::Replace,
+            index: Index::from(2),
+            time: "2024-07-02 14:05:00".to_string(),
+            recorder: "Anna".to_string(),
+            instance: "".to_string(),
+        };
+        let earlier = Operation {
+            index: Index::from(1),
+            time: "2024-07-01 09:00:00".to_string(),
+            ..replace.clone()
+        };
+        data_base.set_operations(&[earlier, replace.clone()]);
+
+        let report = data_base.daily_report("2024-07-02", &fields, &ReportSettings::default(), &format);
+        assert_eq!(4, report.total());
+        assert_eq!(
+            vec!["Square A1", "Square B2", "Ungrouped"],
+            report.groups.iter().map(|(group, _)| group.as_str()).collect::<Vec<_>>()
+        );
+        assert_eq!(vec![3, 5], report.gaps);
+        assert_eq!(vec![replace], report.replacements);
+
+        let markdown = report.to_markdown(DEFAULT_TEMPLATE, &fields, &ReportSettings::default(), &format);
+        assert!(markdown.starts_with("# Excavation report 2024-07-02\n\nFinds registered: 4"));
+        assert!(markdown.contains("### Square A1 (2)\n\n- 4 coin\n- 4.1 coin\n"));
+        assert!(markdown.contains("## Gaps\n\n3, 5\n"));
+        assert!(markdown.contains("## Replacements\n\n- 2 2024-07-02 14:05:00 Anna"));
+    }
+}
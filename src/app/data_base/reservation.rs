@@ -0,0 +1,91 @@
+use crate::app::data_base::report::fill;
+use crate::app::i18n::tr;
+use std::fmt::{Display, Formatter};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Reservation {
+    pub start: u32,
+    pub end: u32,
+    pub team: String,
+}
+
+/// A free index as offered by the auto insert method.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Skip {
+    Free(u32),
+    Reserved(Reservation),
+}
+
+impl Reservation {
+    pub fn contains(&self, number: u32) -> bool {
+        (self.start..=self.end).contains(&number)
+    }
+
+    pub fn overlaps(&self, other: &Reservation) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    pub fn from_row(row: &[String]) -> Option<Self> {
+        Some(Self {
+            start: row.first()?.parse().ok()?,
+            end: row.get(1)?.parse().ok()?,
+            team: row.get(2).cloned().unwrap_or_default(),
+        })
+    }
+
+    pub fn to_row(&self) -> Vec<String> {
+        vec![self.start.to_string(), self.end.to_string(), self.team.clone()]
+    }
+
+    /// The reservation in the language of the interface.
+    pub fn text(&self, language: &str) -> String {
+        let values = [
+            ("start", self.start.to_string()),
+            ("end", self.end.to_string()),
+            ("team", self.team.clone()),
+        ];
+        if self.team.is_empty() {
+            fill(tr(language, "{start}-{end} reserved"), &values)
+        } else {
+            fill(tr(language, "{start}-{end} reserved for {team}"), &values)
+        }
+    }
+}
+
+impl Skip {
+    pub fn text(&self, language: &str) -> String {
+        match self {
+            Skip::Free(number) => number.to_string(),
+            Skip::Reserved(reservation) => fill(
+                tr(language, "{reservation} (unavailable)"),
+                &[("reservation", reservation.text(language))],
+            ),
+        }
+    }
+}
+
+/// A skip offered by the auto insert method, with its text in the language of the interface.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SkipOption {
+    pub skip: Skip,
+    text: String,
+}
+
+impl SkipOption {
+    pub fn new(skip: Skip, language: &str) -> Self {
+        Self {
+            text: skip.text(language),
+            skip,
+        }
+    }
+
+    pub fn all(skips: Vec<Skip>, language: &str) -> Vec<Self> {
+        skips.into_iter().map(|skip| Self::new(skip, language)).collect()
+    }
+}
+
+impl Display for SkipOption {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
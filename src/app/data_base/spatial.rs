@@ -0,0 +1,417 @@
+use crate::app::data_base::integrity::Problem;
+use crate::app::data_base::record::Record;
+use crate::app::data_base::table::Table;
+use crate::app::settings::grid_settings::GridSettings;
+use crate::app::settings::index_format::IndexFormat;
+use crate::app::settings::{Field, FieldType};
+use serde_json::{json, Map, Value};
+use std::fmt::{Display, Formatter};
+use umya_spreadsheet::Worksheet;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PlanFormat {
+    GeoJson,
+    Shapefile,
+    Dxf,
+}
+
+/// A point measured on the site, written into a coordinate field as `x; y; z`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+    pub z: Option<f64>,
+}
+
+impl Point {
+    /// Parses `x; y; z` or `x y z`, the height is optional and a decimal comma is accepted.
+    pub fn parse(text: &str) -> Option<Self> {
+        let values = text
+            .split(|char: char| char == ';' || char.is_whitespace())
+            .filter(|value| !value.is_empty())
+            .map(|value| value.replace(',', ".").parse::<f64>().ok())
+            .collect::<Option<Vec<_>>>()?;
+        match values.as_slice() {
+            [x, y] => Some(Self { x: *x, y: *y, z: None }),
+            [x, y, z] => Some(Self { x: *x, y: *y, z: Some(*z) }),
+            _ => None,
+        }
+    }
+
+    pub fn is_in(&self, (x_min, y_min, x_max, y_max): (f64, f64, f64, f64)) -> bool {
+        x_min <= self.x && self.x <= x_max && y_min <= self.y && self.y <= y_max
+    }
+}
+
+impl PlanFormat {
+    pub const ALL: [PlanFormat; 3] = [PlanFormat::GeoJson, PlanFormat::Shapefile, PlanFormat::Dxf];
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            PlanFormat::GeoJson => "geojson",
+            PlanFormat::Shapefile => "shp",
+            PlanFormat::Dxf => "dxf",
+        }
+    }
+}
+
+impl Display for PlanFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlanFormat::GeoJson => write!(f, "GeoJSON"),
+            PlanFormat::Shapefile => write!(f, "Shapefile"),
+            PlanFormat::Dxf => write!(f, "DXF"),
+        }
+    }
+}
+
+impl Display for Point {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}; {}", self.x, self.y)?;
+        if let Some(z) = self.z {
+            write!(f, "; {}", z)?;
+        }
+        Ok(())
+    }
+}
+
+/// The position of the first coordinate field in the record data.
+pub fn coordinates_id(fields: &[Field]) -> Option<usize> {
+    fields
+        .iter()
+        .position(|field| field.field_type == FieldType::Coordinates)
+        .map(|id| id + 1)
+}
+
+/// Checks a coordinate value against the square declared for the find.
+/// An empty value or an unknown square is not an error.
+pub fn check_point(text: &str, square: &str, grid: &GridSettings) -> Result<(), String> {
+    if text.trim().is_empty() {
+        return Ok(());
+    }
+    let point = Point::parse(text).ok_or(format!("\"{}\" are not coordinates", text))?;
+    match grid.square_bounds(square) {
+        Some(bounds) if !point.is_in(bounds) => {
+            Err(format!("{} is outside of the square {}", point, square.trim()))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// The square a coordinate value falls in, to prefill the square field.
+pub fn square_from_coordinates(text: &str, grid: &GridSettings) -> Option<String> {
+    let point = Point::parse(text)?;
+    grid.square_of(point.x, point.y)
+}
+
+/// The records with coordinates and their points.
+pub fn plotted(records: Vec<Record>, fields: &[Field]) -> Vec<(Record, Point)> {
+    let Some(coordinates_id) = coordinates_id(fields) else {
+        return vec![];
+    };
+    records
+        .into_iter()
+        .filter_map(|record| {
+            let point = Point::parse(record.data.get(coordinates_id)?)?;
+            Some((record, point))
+        })
+        .collect()
+}
+
+pub trait Spatial {
+    /// Malformed coordinates and points outside of their square, for the integrity check.
+    fn check_coordinates(
+        &self,
+        fields: &[Field],
+        grid: &GridSettings,
+        index_format: &IndexFormat,
+    ) -> Vec<Problem>;
+}
+
+impl Spatial for Worksheet {
+    fn check_coordinates(
+        &self,
+        fields: &[Field],
+        grid: &GridSettings,
+        index_format: &IndexFormat,
+    ) -> Vec<Problem> {
+        let Some(coordinates_id) = coordinates_id(fields) else {
+            return vec![];
+        };
+        let square_id = fields
+            .iter()
+            .position(|field| field.name == grid.square_field)
+            .map(|id| id + 1);
+        self.get_records(fields.len() + 1, index_format)
+            .into_iter()
+            .filter_map(|record| {
+                let coordinates = record.data.get(coordinates_id).cloned().unwrap_or_default();
+                let square = square_id
+                    .and_then(|square_id| record.data.get(square_id).cloned())
+                    .unwrap_or_default();
+                let problem = check_point(&coordinates, &square, grid).err()?;
+                Some(Problem::Coordinates {
+                    row: record.row,
+                    value: index_format.format(record.index),
+                    problem,
+                })
+            })
+            .collect()
+    }
+}
+
+/// The attributes of a find: the index, the date and the fields other than the coordinates.
+fn attributes(record: &Record, fields: &[Field], index_format: &IndexFormat) -> Vec<(String, String)> {
+    [
+        ("index".to_string(), index_format.format(record.index)),
+        ("date".to_string(), record.data.first().cloned().unwrap_or_default()),
+    ]
+    .into_iter()
+    .chain(
+        fields
+            .iter()
+            .zip(record.data.iter().skip(1))
+            .filter(|(field, _)| field.field_type != FieldType::Coordinates)
+            .map(|(field, value)| (field.name.clone(), value.clone())),
+    )
+    .collect()
+}
+
+pub fn to_geojson(
+    points: &[(Record, Point)],
+    fields: &[Field],
+    grid: &GridSettings,
+    index_format: &IndexFormat,
+) -> String {
+    let features = points
+        .iter()
+        .map(|(record, point)| {
+            let coordinates = match point.z {
+                Some(z) => json!([point.x, point.y, z]),
+                None => json!([point.x, point.y]),
+            };
+            json!({
+                "type": "Feature",
+                "geometry": {"type": "Point", "coordinates": coordinates},
+                "properties": attributes(record, fields, index_format)
+                    .into_iter()
+                    .map(|(name, value)| (name, Value::String(value)))
+                    .collect::<Map<_, _>>(),
+            })
+        })
+        .collect::<Vec<_>>();
+    let mut collection = json!({"type": "FeatureCollection", "features": features});
+    if grid.epsg != 0 {
+        collection["crs"] = json!({
+            "type": "name",
+            "properties": {"name": format!("urn:ogc:def:crs:EPSG::{}", grid.epsg)}
+        });
+    }
+    serde_json::to_string_pretty(&collection).expect("Can not write GeoJSON")
+}
+
+/// An R12 DXF with a point and the index as a label for every find, the height kept as z.
+pub fn to_dxf(points: &[(Record, Point)], index_format: &IndexFormat) -> String {
+    let entities = points
+        .iter()
+        .map(|(record, point)| {
+            let z = point.z.unwrap_or(0.);
+            format!(
+                "0\nPOINT\n8\nFINDS\n10\n{x}\n20\n{y}\n30\n{z}\n0\nTEXT\n8\nLABELS\n10\n{label_x}\n20\n{y}\n30\n{z}\n40\n0.05\n1\n{index}\n",
+                x = point.x,
+                y = point.y,
+                z = z,
+                label_x = point.x + 0.05,
+                index = index_format.format(record.index),
+            )
+        })
+        .collect::<String>();
+    format!("0\nSECTION\n2\nENTITIES\n{}0\nENDSEC\n0\nEOF\n", entities)
+}
+
+/// The files of an ESRI Shapefile of PointZ shapes: `.shp`, `.shx`, `.dbf` and `.cpg`.
+/// No `.prj` is written, as its WKT can't be built from the EPSG code alone:
+/// only GeoJSON carries the CRS.
+pub fn to_shapefile(
+    points: &[(Record, Point)],
+    fields: &[Field],
+    index_format: &IndexFormat,
+) -> Vec<(&'static str, Vec<u8>)> {
+    const POINT_Z: i32 = 11;
+    // shape type, x, y, z and m
+    const CONTENT_LENGTH: i32 = 4 + 4 * 8;
+
+    let bound = |value: fn(&Point) -> f64, max: bool| {
+        points
+            .iter()
+            .map(|(_, point)| value(point))
+            .reduce(if max { f64::max } else { f64::min })
+            .unwrap_or(0.)
+    };
+    let header = |file_length: i32| {
+        let mut header = vec![];
+        header.extend(9994_i32.to_be_bytes());
+        header.extend([0; 20]);
+        // in 16-bit words
+        header.extend((file_length / 2).to_be_bytes());
+        header.extend(1000_i32.to_le_bytes());
+        header.extend(POINT_Z.to_le_bytes());
+        for value in [
+            bound(|point| point.x, false),
+            bound(|point| point.y, false),
+            bound(|point| point.x, true),
+            bound(|point| point.y, true),
+            bound(|point| point.z.unwrap_or(0.), false),
+            bound(|point| point.z.unwrap_or(0.), true),
+            0.,
+            0.,
+        ] {
+            header.extend(value.to_le_bytes());
+        }
+        header
+    };
+
+    let record_length = 8 + CONTENT_LENGTH;
+    let mut shp = header(100 + points.len() as i32 * record_length);
+    let mut shx = header(100 + points.len() as i32 * 8);
+    for (number, (_, point)) in (1_i32..).zip(points) {
+        shx.extend((shp.len() as i32 / 2).to_be_bytes());
+        shx.extend((CONTENT_LENGTH / 2).to_be_bytes());
+        shp.extend(number.to_be_bytes());
+        shp.extend((CONTENT_LENGTH / 2).to_be_bytes());
+        shp.extend(POINT_Z.to_le_bytes());
+        for value in [point.x, point.y, point.z.unwrap_or(0.), 0.] {
+            shp.extend(value.to_le_bytes());
+        }
+    }
+
+    let rows = points
+        .iter()
+        .map(|(record, _)| attributes(record, fields, index_format))
+        .collect::<Vec<_>>();
+    let names = ["index", "date"]
+        .into_iter()
+        .map(str::to_string)
+        .chain(
+            fields
+                .iter()
+                .filter(|field| field.field_type != FieldType::Coordinates)
+                .map(|field| field.name.clone()),
+        )
+        .collect::<Vec<_>>();
+    let lengths = (0..names.len())
+        .map(|i| {
+            rows.iter()
+                .filter_map(|row| row.get(i))
+                .map(|(_, value)| value.len())
+                .max()
+                .unwrap_or(1)
+                .clamp(1, 254)
+        })
+        .collect::<Vec<_>>();
+
+    let mut dbf = vec![0x03, 124, 1, 1];
+    dbf.extend((points.len() as u32).to_le_bytes());
+    dbf.extend((32 + 32 * names.len() as u16 + 1).to_le_bytes());
+    dbf.extend((1 + lengths.iter().sum::<usize>() as u16).to_le_bytes());
+    dbf.extend([0; 20]);
+    for (name, &length) in names.iter().zip(&lengths) {
+        // at most 10 bytes, cut at a character boundary
+        let mut name = name.clone();
+        while name.len() > 10 {
+            name.pop();
+        }
+        let mut descriptor = name.into_bytes();
+        descriptor.resize(11, 0);
+        descriptor.push(b'C');
+        descriptor.extend([0; 4]);
+        descriptor.extend([length as u8, 0]);
+        descriptor.extend([0; 14]);
+        dbf.extend(descriptor);
+    }
+    dbf.push(0x0D);
+    for row in &rows {
+        dbf.push(b' ');
+        for (i, &length) in lengths.iter().enumerate() {
+            let mut value = row.get(i).map(|(_, value)| value.clone()).unwrap_or_default();
+            while value.len() > length {
+                value.pop();
+            }
+            let mut value = value.into_bytes();
+            value.resize(length, b' ');
+            dbf.extend(value);
+        }
+    }
+    dbf.push(0x1A);
+
+    vec![
+        ("shp", shp),
+        ("shx", shx),
+        ("dbf", dbf),
+        ("cpg", b"UTF-8".to_vec()),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_point, plotted, square_from_coordinates, to_geojson, to_shapefile, Point};
+    use crate::app::data_base::table::{test::FromMatrix, Table};
+    use crate::app::settings::grid_settings::GridSettings;
+    use crate::app::settings::index_format::IndexFormat;
+    use crate::app::settings::{Field, FieldType};
+    use serde_json::{json, Value};
+    use umya_spreadsheet::new_file;
+
+    #[test]
+    fn coordinates() {
+        assert_eq!(
+            Some(Point { x: 3.5, y: 1.25, z: Some(-0.4) }),
+            Point::parse("3,5; 1.25; -0.4")
+        );
+        assert_eq!(Some(Point { x: 3., y: 1., z: None }), Point::parse("3 1"));
+        assert_eq!(None, Point::parse("3; north"));
+
+        let grid = GridSettings::default();
+        assert_eq!(Some((2., 4., 4., 6.)), grid.square_bounds("b3"));
+        assert_eq!(Some((52., 0., 54., 2.)), grid.square_bounds("AA1"));
+        assert_eq!(None, grid.square_bounds("3"));
+        assert!(check_point("3; 5", "B3", &grid).is_ok());
+        assert!(check_point("1; 5", "B3", &grid).is_err());
+        assert!(check_point("1; 5", "north", &grid).is_ok());
+        assert!(check_point("", "B3", &grid).is_ok());
+
+        assert_eq!(Some("B3".to_string()), square_from_coordinates("3; 5", &grid));
+        assert_eq!(Some("AB1".to_string()), square_from_coordinates("55; 1", &grid));
+        assert_eq!(None, square_from_coordinates("-1; 5", &grid));
+        let swapped = GridSettings {
+            letters_along_x: false,
+            ..GridSettings::default()
+        };
+        assert_eq!(Some("C2".to_string()), square_from_coordinates("3; 5", &swapped));
+        assert_eq!(Some((2., 4., 4., 6.)), swapped.square_bounds("C2"));
+
+        let format = IndexFormat::default();
+        let fields = vec![
+            Field::new(FieldType::Text, "Name".to_string(), true),
+            Field::new(FieldType::Coordinates, "Point".to_string(), false),
+        ];
+        let mut book = new_file();
+        let records = book
+            .matrix_to_sheet(vec![
+                vec!["1", "2024-07-01", "pot", "3; 5; 1.5"],
+                vec!["2", "2024-07-01", "bead", ""],
+            ])
+            .get_records(3, &format);
+        let points = plotted(records, &fields);
+        assert_eq!(1, points.len());
+
+        let geojson: Value = serde_json::from_str(&to_geojson(&points, &fields, &grid, &format)).unwrap();
+        let feature = &geojson["features"][0];
+        assert_eq!(json!([3., 5., 1.5]), feature["geometry"]["coordinates"]);
+        assert_eq!(json!({"index": "1", "date": "2024-07-01", "Name": "pot"}), feature["properties"]);
+
+        let files = to_shapefile(&points, &fields, &format);
+        assert_eq!(100 + 8 + 36, files[0].1.len());
+        assert_eq!(100 + 8, files[1].1.len());
+    }
+}
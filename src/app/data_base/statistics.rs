@@ -0,0 +1,143 @@
+use crate::app::data_base::report::fill;
+use crate::app::data_base::table::Table;
+use crate::app::i18n::tr;
+use crate::app::settings::index_format::IndexFormat;
+use std::collections::{BTreeSet, HashMap};
+use umya_spreadsheet::Worksheet;
+
+/// How much of the range of index numbers is taken.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Coverage {
+    pub first: u32,
+    pub last: u32,
+    /// Numbers taken by a record.
+    pub used: u32,
+    pub fragments: u32,
+}
+
+impl Coverage {
+    pub fn free(&self) -> u32 {
+        if self.used == 0 {
+            0
+        } else {
+            self.last - self.first + 1 - self.used
+        }
+    }
+
+    pub fn percent(&self) -> f32 {
+        if self.used == 0 {
+            0.
+        } else {
+            self.used as f32 * 100. / (self.last - self.first + 1) as f32
+        }
+    }
+
+    /// The coverage in the language of the interface.
+    pub fn text(&self, language: &str) -> String {
+        fill(
+            tr(
+                language,
+                "{first}-{last}: {used} used, {free} free ({percent}% covered), {fragments} fragments",
+            ),
+            &[
+                ("first", self.first.to_string()),
+                ("last", self.last.to_string()),
+                ("used", self.used.to_string()),
+                ("free", self.free().to_string()),
+                ("percent", format!("{:.1}", self.percent())),
+                ("fragments", self.fragments.to_string()),
+            ],
+        )
+    }
+}
+
+pub trait Statistics {
+    /// The number of records per value of a cell, `data_id` 0 is the date.
+    /// The most frequent values come first, the dates are kept in order.
+    fn counts_by(&self, data_id: usize, index_format: &IndexFormat) -> Vec<(String, u32)>;
+
+    fn coverage(&self, index_format: &IndexFormat) -> Coverage;
+}
+
+impl Statistics for Worksheet {
+    fn counts_by(&self, data_id: usize, index_format: &IndexFormat) -> Vec<(String, u32)> {
+        let mut counts = HashMap::<String, u32>::new();
+        for (row_num, _) in self.get_indices(index_format) {
+            let value = self.get_value((data_id as u32 + 2, row_num)).trim().to_string();
+            *counts.entry(value).or_default() += 1;
+        }
+        let mut counts = counts.into_iter().collect::<Vec<_>>();
+        if data_id == 0 {
+            counts.sort();
+        } else {
+            counts.sort_by(|(value1, count1), (value2, count2)| {
+                count2.cmp(count1).then(value1.cmp(value2))
+            });
+        }
+        counts
+    }
+
+    fn coverage(&self, index_format: &IndexFormat) -> Coverage {
+        let indices = self.get_indices(index_format);
+        let numbers = indices
+            .iter()
+            .filter(|(_, index)| index.sub.is_none())
+            .map(|(_, index)| index.number)
+            .collect::<BTreeSet<_>>();
+        Coverage {
+            first: numbers.first().copied().unwrap_or(0),
+            last: numbers.last().copied().unwrap_or(0),
+            used: numbers.len() as u32,
+            fragments: indices.iter().filter(|(_, index)| index.sub.is_some()).count() as u32,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Coverage, Statistics};
+    use crate::app::data_base::table::test::FromMatrix;
+    use crate::app::settings::index_format::IndexFormat;
+    use umya_spreadsheet::new_file;
+
+    #[test]
+    fn statistics() {
+        let format = IndexFormat::default();
+        let mut book = new_file();
+        let test_sheet = book.matrix_to_sheet(vec![
+            vec!["1", "2024-07-02", "bone"],
+            vec!["2", "2024-07-01", "pottery"],
+            vec!["2.1", "2024-07-01", "pottery"],
+            vec!["5", "2024-07-02", "bone"],
+            vec!["6", "2024-07-02", "glass"],
+        ]);
+
+        assert_eq!(
+            vec![("2024-07-01".to_string(), 2), ("2024-07-02".to_string(), 3)],
+            test_sheet.counts_by(0, &format)
+        );
+        assert_eq!(
+            vec![
+                ("bone".to_string(), 2),
+                ("pottery".to_string(), 2),
+                ("glass".to_string(), 1)
+            ],
+            test_sheet.counts_by(1, &format)
+        );
+        let coverage = test_sheet.coverage(&format);
+        assert_eq!(
+            Coverage {
+                first: 1,
+                last: 6,
+                used: 4,
+                fragments: 1
+            },
+            coverage
+        );
+        assert_eq!(2, coverage.free());
+        assert_eq!(
+            "1-6: занято 4, свободно 2 (заполнено 66.7%), фрагментов 1",
+            coverage.text("ru")
+        );
+    }
+}
@@ -0,0 +1,181 @@
+use crate::app::data_base::audit::AuditTrail;
+use crate::app::data_base::index::Index;
+use crate::app::data_base::sync::OperationKind;
+use crate::app::data_base::table::Table;
+use crate::app::data_base::DataBase;
+use crate::app::settings::index_format::IndexFormat;
+use crate::app::settings::status_settings::StatusSettings;
+use std::collections::BTreeMap;
+use std::path::Path;
+use umya_spreadsheet::Worksheet;
+
+/// Counts of finds per group and status, finds without a status are counted under "".
+pub type StatusCounts = BTreeMap<String, BTreeMap<String, u32>>;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StatusChange {
+    pub changed: Vec<Index>,
+    /// Finds whose status does not allow the change, with that status.
+    pub rejected: Vec<(Index, String)>,
+}
+
+/// Parses ranges of index numbers like `15-40, 52` or `15..40`.
+pub fn parse_ranges(text: &str, index_format: &IndexFormat) -> Result<Vec<(u32, u32)>, String> {
+    text.split([',', ';'])
+        .map(str::trim)
+        .filter(|range| !range.is_empty())
+        .map(|range| {
+            if let Some(index) = index_format.parse(range) {
+                return Ok((index.number, index.number));
+            }
+            // the prefix may contain dashes too, so every separator is tried
+            range
+                .match_indices("..")
+                .chain(range.match_indices('-'))
+                .find_map(|(at, separator)| {
+                    let start = index_format.parse(&range[..at])?;
+                    let end = index_format.parse(&range[at + separator.len()..])?;
+                    Some((start.number, end.number))
+                })
+                .filter(|(start, end)| start <= end)
+                .ok_or(format!("\"{}\" is not an index or a range of indices", range))
+        })
+        .collect()
+}
+
+pub trait StatusTable {
+    fn status_counts(
+        &self,
+        status_id: usize,
+        group_id: Option<usize>,
+        index_format: &IndexFormat,
+    ) -> StatusCounts;
+}
+
+impl StatusTable for Worksheet {
+    fn status_counts(
+        &self,
+        status_id: usize,
+        group_id: Option<usize>,
+        index_format: &IndexFormat,
+    ) -> StatusCounts {
+        let mut counts = StatusCounts::new();
+        for (row_num, _) in self.get_indices(index_format) {
+            let group = match group_id {
+                Some(group_id) => self.get_value((group_id as u32 + 2, row_num)),
+                None => index_format.trench.clone(),
+            };
+            let status = self.get_value((status_id as u32 + 2, row_num));
+            *counts.entry(group).or_default().entry(status).or_default() += 1;
+        }
+        counts
+    }
+}
+
+impl DataBase {
+    /// Moves the finds of the ranges, fragments included, to a status their current one allows.
+    /// `status_id` counts from the date, so the first field is 1.
+    pub fn change_status(
+        path: &Path,
+        ranges: &[(u32, u32)],
+        status_id: usize,
+        to: &str,
+        status_settings: &StatusSettings,
+        index_format: &IndexFormat,
+        recorder: Option<&str>,
+    ) -> StatusChange {
+        let mut data_base = DataBase::from(path);
+        let sheet = data_base.get_sheet_mut();
+        let mut change = StatusChange::default();
+        let mut indices = sheet.get_indices(index_format);
+        indices.sort_by_key(|(_, index)| *index);
+        for (row_num, index) in indices {
+            if !ranges
+                .iter()
+                .any(|(start, end)| (*start..=*end).contains(&index.number))
+            {
+                continue;
+            }
+            let col_num = status_id as u32 + 2;
+            let from = sheet.get_value((col_num, row_num));
+            if from == to {
+                continue;
+            }
+            if status_settings.can_change(&from, to) {
+                sheet.get_cell_mut((col_num, row_num)).set_value(to);
+                if let Some(recorder) = recorder {
+                    sheet.stamp(row_num, recorder, false);
+                }
+                change.changed.push(index);
+            } else {
+                change.rejected.push((index, from));
+            }
+        }
+        data_base.log_operations(
+            OperationKind::Replace,
+            &change.changed,
+            recorder.unwrap_or_default(),
+        );
+        data_base.save(path);
+        change
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_ranges, StatusTable};
+    use crate::app::data_base::table::test::FromMatrix;
+    use crate::app::settings::index_format::IndexFormat;
+    use crate::app::settings::status_settings::StatusSettings;
+    use umya_spreadsheet::new_file;
+
+    #[test]
+    fn ranges() {
+        let format = IndexFormat::default();
+        assert_eq!(Ok(vec![(15, 40), (52, 52)]), parse_ranges("15-40, 52", &format));
+        assert_eq!(Ok(vec![(3, 3)]), parse_ranges("3.1", &format));
+        assert!(parse_ranges("40-15", &format).is_err());
+        assert!(parse_ranges("bead", &format).is_err());
+        assert_eq!(Ok(vec![(15, 40)]), parse_ranges("15..40", &format));
+
+        let format = IndexFormat {
+            prefix_template: "{site}{year}-T{trench}-".to_string(),
+            site: "KR".to_string(),
+            year: "24".to_string(),
+            trench: "3".to_string(),
+            padding: 4,
+            ..IndexFormat::default()
+        };
+        assert_eq!(
+            Ok(vec![(15, 40), (52, 52)]),
+            parse_ranges("KR24-T3-0015-KR24-T3-0040, KR24-T3-0052", &format)
+        );
+        assert_eq!(Ok(vec![(15, 40)]), parse_ranges("KR24-T3-0015 - KR24-T3-0040", &format));
+        assert_eq!(Ok(vec![(15, 40)]), parse_ranges("KR24-T3-0015..0040", &format));
+    }
+
+    #[test]
+    fn status_counts() {
+        let mut book = new_file();
+        let test_sheet = book.matrix_to_sheet(vec![
+            vec!["1", "01.07", "T1", "washed"],
+            vec!["2", "01.07", "T1", "washed"],
+            vec!["3", "01.07", "T2", "registered"],
+            vec!["4", "01.07", "T2"],
+        ]);
+        let counts = test_sheet.status_counts(2, Some(1), &IndexFormat::default());
+        assert_eq!(Some(&2), counts["T1"].get("washed"));
+        assert_eq!(Some(&1), counts["T2"].get("registered"));
+        assert_eq!(Some(&1), counts["T2"].get(""));
+    }
+
+    #[test]
+    fn can_change() {
+        let settings = StatusSettings::default();
+        assert!(settings.can_change("", "registered"));
+        assert!(!settings.can_change("", "washed"));
+        assert!(settings.can_change("registered", "washed"));
+        assert!(!settings.can_change("registered", "archived"));
+        assert!(!settings.can_change("archived", "registered"));
+    }
+}
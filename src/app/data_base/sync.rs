@@ -0,0 +1,460 @@
+use crate::app::data_base::audit::{now, Audit, AuditTrail};
+use crate::app::data_base::create_record::CreateRecord;
+use crate::app::data_base::index::Index;
+use crate::app::data_base::meta_data::MetaData;
+use crate::app::data_base::photos::Photos;
+use crate::app::data_base::report::fill;
+use crate::app::data_base::table::Table;
+use crate::app::data_base::DataBase;
+use crate::app::i18n::tr;
+use crate::app::settings::index_format::IndexFormat;
+use crate::app::settings::Field;
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::time::{SystemTime, UNIX_EPOCH};
+use umya_spreadsheet::Worksheet;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum OperationKind {
+    Create,
+    Replace,
+}
+
+/// A change applied to the local database, kept so registers can be merged later.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Operation {
+    pub kind: OperationKind,
+    pub index: Index,
+    pub time: String,
+    pub recorder: String,
+    /// The register the change was made in,
+    /// empty for the changes logged before registers had an ID.
+    pub instance: String,
+}
+
+/// A record both registers changed, or that this register has from before the log,
+/// with different data.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Conflict {
+    pub index: Index,
+    /// The date followed by the fields.
+    pub local: Vec<String>,
+    pub other: Vec<String>,
+    pub other_audit: Audit,
+    pub other_photos: Vec<String>,
+    /// The operations of the other register on the record, logged when the conflict is resolved.
+    pub operations: Vec<Operation>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MergeReport {
+    pub added: Vec<Index>,
+    pub updated: Vec<Index>,
+    pub conflicts: Vec<Conflict>,
+}
+
+impl Display for OperationKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OperationKind::Create => write!(f, "create"),
+            OperationKind::Replace => write!(f, "replace"),
+        }
+    }
+}
+
+impl Operation {
+    pub fn from_row(row: &[String]) -> Option<Self> {
+        Some(Self {
+            kind: match row.first()?.as_str() {
+                "create" => OperationKind::Create,
+                "replace" => OperationKind::Replace,
+                _ => return None,
+            },
+            index: Index::new(
+                row.get(1)?.parse().ok()?,
+                row.get(2).and_then(|sub| sub.parse().ok()),
+            ),
+            time: row.get(3).cloned().unwrap_or_default(),
+            recorder: row.get(4).cloned().unwrap_or_default(),
+            instance: row.get(5).cloned().unwrap_or_default(),
+        })
+    }
+
+    pub fn to_row(&self) -> Vec<String> {
+        vec![
+            self.kind.to_string(),
+            self.index.number.to_string(),
+            self.index.sub.map(|sub| sub.to_string()).unwrap_or_default(),
+            self.time.clone(),
+            self.recorder.clone(),
+            self.instance.clone(),
+        ]
+    }
+}
+
+impl MergeReport {
+    /// The counts of the report in the language of the interface.
+    pub fn text(&self, language: &str) -> String {
+        fill(
+            tr(language, "{added} added, {updated} updated, {conflicts} conflicts"),
+            &[
+                ("added", self.added.len().to_string()),
+                ("updated", self.updated.len().to_string()),
+                ("conflicts", self.conflicts.len().to_string()),
+            ],
+        )
+    }
+}
+
+/// Row data without the empty cells at the end, so rows written by different instances compare equal.
+fn trimmed(mut data: Vec<String>) -> Vec<String> {
+    while data.last().is_some_and(|cell| cell.is_empty()) {
+        data.pop();
+    }
+    data
+}
+
+/// Adds the photos of the same record in another register.
+fn add_photos(sheet: &mut Worksheet, row_num: u32, photos: &[String]) {
+    let mut all = sheet.get_photos(row_num);
+    let count = all.len();
+    for photo in photos {
+        if !all.contains(photo) {
+            all.push(photo.clone());
+        }
+    }
+    if all.len() > count {
+        sheet.set_photos(row_num, &all);
+    }
+}
+
+impl DataBase {
+    pub fn get_operations(&self) -> Vec<Operation> {
+        self.book
+            .get_meta_data("operations")
+            .iter()
+            .filter_map(|row| Operation::from_row(row))
+            .collect()
+    }
+
+    pub fn set_operations(&mut self, operations: &[Operation]) {
+        self.book.set_meta_data(
+            "operations",
+            operations.iter().map(Operation::to_row).collect(),
+        )
+    }
+
+    fn get_instance(&self) -> Option<String> {
+        self.book
+            .get_meta_data("instance")
+            .first()?
+            .first()
+            .filter(|instance| !instance.is_empty())
+            .cloned()
+    }
+
+    /// The ID of this register, logged with its operations to tell them from the merged ones.
+    fn instance(&mut self) -> String {
+        if let Some(instance) = self.get_instance() {
+            return instance;
+        }
+        let instance = format!(
+            "{:x}",
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos()
+        );
+        self.book.set_meta_data("instance", vec![vec![instance.clone()]]);
+        instance
+    }
+
+    pub fn log_operations(&mut self, kind: OperationKind, indices: &[Index], recorder: &str) {
+        let time = now();
+        let instance = self.instance();
+        let mut operations = self.get_operations();
+        operations.extend(indices.iter().map(|&index| Operation {
+            kind,
+            index,
+            time: time.clone(),
+            recorder: recorder.to_string(),
+            instance: instance.clone(),
+        }));
+        self.set_operations(&operations)
+    }
+
+    /// Takes the records the other register created or replaced. A record this register changed
+    /// since it last took the record from elsewhere, or has from before the log, is left as it is
+    /// and returned as a conflict.
+    pub fn merge(
+        &mut self,
+        other: &DataBase,
+        fields: &[Field],
+        index_format: &IndexFormat,
+        recorder: Option<&str>,
+    ) -> MergeReport {
+        let instance = self.instance();
+        let is_local = |operation: &Operation| {
+            operation.instance.is_empty() || operation.instance == instance
+        };
+        let mut operations = self.get_operations();
+        let mut known = operations.iter().cloned().collect::<HashSet<_>>();
+        // the operations the other register logged before it had an ID are its own
+        let other_instance = other.get_instance().unwrap_or_else(|| "other".to_string());
+        let other_operations = other
+            .get_operations()
+            .into_iter()
+            .map(|mut operation| {
+                if operation.instance.is_empty() {
+                    operation.instance = other_instance.clone();
+                }
+                operation
+            })
+            .filter(|operation| !is_local(operation))
+            .collect::<Vec<_>>();
+        let other_sheet = other.get_sheet();
+        let mut report = MergeReport::default();
+        let mut seen = HashSet::new();
+
+        // the last operation on an index decides its data, so go from the newest one
+        for operation in other_operations.iter().rev() {
+            let index = operation.index;
+            if !seen.insert(index) || known.contains(operation) {
+                continue;
+            }
+            let Some(other_row) = other_sheet.get_row_index_from_index(index, index_format) else {
+                continue;
+            };
+            let other_data = trimmed(other_sheet.get_row_data(other_row));
+            let other_audit = other_sheet.get_audit(other_row);
+            let other_photos = other_sheet.get_photos(other_row);
+            let taken = other_operations
+                .iter()
+                .filter(|operation| operation.index == index && !known.contains(operation))
+                .cloned()
+                .collect::<Vec<_>>();
+            let old_references = self.get_all_references(index, fields, index_format);
+
+            let sheet = self.get_sheet_mut();
+            let is_written = match sheet.get_row_index_from_index(index, index_format) {
+                None => {
+                    sheet.create_record_from_index(index, other_data, index_format, recorder);
+                    // the audit of the other register is kept if it has one
+                    if let Some(row) = sheet.get_row_index_from_index(index, index_format) {
+                        sheet.set_audit(row, &other_audit);
+                        add_photos(sheet, row, &other_photos);
+                    }
+                    report.added.push(index);
+                    true
+                }
+                Some(row) => {
+                    let local_data = trimmed(sheet.get_row_data(row));
+                    if local_data == other_data {
+                        add_photos(sheet, row, &other_photos);
+                        false
+                    } else {
+                        let is_changed = match operations.iter().rposition(|operation| {
+                            operation.index == index && !is_local(operation)
+                        }) {
+                            Some(last_taken) => operations[last_taken + 1..]
+                                .iter()
+                                .any(|operation| operation.index == index),
+                            None => true,
+                        };
+                        if is_changed {
+                            report.conflicts.push(Conflict {
+                                index,
+                                local: local_data,
+                                other: other_data,
+                                other_audit,
+                                other_photos,
+                                operations: taken,
+                            });
+                            continue;
+                        }
+                        sheet.set_record(row, index, other_data, index_format, recorder);
+                        sheet.set_audit(row, &other_audit);
+                        add_photos(sheet, row, &other_photos);
+                        report.updated.push(index);
+                        true
+                    }
+                }
+            };
+            if is_written {
+                self.link_all_references(index, &old_references, index_format);
+            }
+            // keep the other log, so the next merge knows these records were taken
+            known.extend(taken.iter().cloned());
+            operations.extend(taken);
+        }
+        report.added.sort();
+        report.updated.sort();
+        report.conflicts.sort_by_key(|conflict| conflict.index);
+        self.set_operations(&operations);
+        report
+    }
+
+    /// Writes the chosen side of a conflict into the register. Both choices are logged,
+    /// so the next merge does not show the conflict again.
+    pub fn resolve(
+        &mut self,
+        conflict: Conflict,
+        take_other: bool,
+        fields: &[Field],
+        index_format: &IndexFormat,
+        recorder: Option<&str>,
+    ) {
+        let mut operations = self.get_operations();
+        operations.extend(conflict.operations);
+        self.set_operations(&operations);
+        if !take_other {
+            // keeping this side is a change made here
+            self.log_operations(
+                OperationKind::Replace,
+                &[conflict.index],
+                recorder.unwrap_or_default(),
+            );
+            return;
+        }
+        let old_references = self.get_all_references(conflict.index, fields, index_format);
+        let sheet = self.get_sheet_mut();
+        if let Some(row) = sheet.get_row_index_from_index(conflict.index, index_format) {
+            sheet.set_record(row, conflict.index, conflict.other, index_format, recorder);
+            sheet.set_audit(row, &conflict.other_audit);
+            add_photos(sheet, row, &conflict.other_photos);
+            self.link_all_references(conflict.index, &old_references, index_format);
+        }
+    }
+
+    /// The operations applied to one record, oldest first.
+    pub fn history(&self, index: Index) -> Vec<Operation> {
+        self.get_operations()
+            .into_iter()
+            .filter(|operation| operation.index == index)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::data_base::audit::{Audit, AuditTrail};
+    use crate::app::data_base::index::Index;
+    use crate::app::data_base::photos::Photos;
+    use crate::app::data_base::sync::{trimmed, Conflict, OperationKind};
+    use crate::app::data_base::table::Table;
+    use crate::app::data_base::DataBase;
+    use crate::app::settings::index_format::IndexFormat;
+    use crate::app::settings::{Field, FieldType};
+    use umya_spreadsheet::new_file;
+
+    fn data_base(matrix: Vec<Vec<&str>>) -> DataBase {
+        let mut data_base = DataBase { book: new_file() };
+        for (row, row_num) in matrix.into_iter().zip(1..) {
+            data_base
+                .get_sheet_mut()
+                .set_row(row_num, row.into_iter().map(str::to_string).collect());
+        }
+        data_base
+    }
+
+    fn data(cells: &[&str]) -> Vec<String> {
+        cells.iter().map(|cell| cell.to_string()).collect()
+    }
+
+    fn row_data(data_base: &DataBase, row_num: u32) -> Vec<String> {
+        trimmed(data_base.get_sheet().get_row_data(row_num))
+    }
+
+    #[test]
+    fn merge() {
+        let format = IndexFormat::default();
+        let mut local = data_base(vec![
+            vec!["1", "01.07", "pot"],
+            vec!["2", "01.07", "bead"],
+            vec!["3", "02.07", "coin"],
+        ]);
+        local.log_operations(OperationKind::Create, &[Index::from(3)], "");
+
+        let mut other = data_base(vec![
+            vec!["1", "01.07", "pot"],
+            vec!["2", "01.07", "bead, broken"],
+            vec!["3", "02.07", "ring"],
+            vec!["4", "02.07", "nail"],
+        ]);
+        let audit = Audit {
+            recorder: "Anna".to_string(),
+            created: "2024-07-02 10:00:00".to_string(),
+            modified: "2024-07-02 10:00:00".to_string(),
+        };
+        other.get_sheet_mut().set_audit(4, &audit);
+        other.get_sheet_mut().set_photos(4, &["4_1.jpg".to_string()]);
+        other.log_operations(OperationKind::Replace, &[Index::from(2)], "Anna");
+        other.log_operations(OperationKind::Create, &[Index::from(3), Index::from(4)], "Anna");
+
+        // 2 is in this register from before the log, so it is not overwritten either
+        let mut report = local.merge(&other, &[], &format, None);
+        assert_eq!(vec![Index::from(4)], report.added);
+        assert!(report.updated.is_empty());
+        assert_eq!(
+            vec![Index::from(2), Index::from(3)],
+            report.conflicts.iter().map(|conflict| conflict.index).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Conflict {
+                index: Index::from(3),
+                local: data(&["02.07", "coin"]),
+                other: data(&["02.07", "ring"]),
+                other_audit: Audit::default(),
+                other_photos: vec![],
+                operations: vec![other.history(Index::from(3))[0].clone()],
+            },
+            report.conflicts[1]
+        );
+        assert_eq!(audit, local.get_sheet().get_audit(4));
+        assert_eq!(vec!["4_1.jpg"], local.get_sheet().get_photos(4));
+
+        // merging the same register again changes nothing
+        let again = local.merge(&other, &[], &format, None);
+        assert!(again.added.is_empty() && again.updated.is_empty());
+        assert_eq!(2, again.conflicts.len());
+
+        let keep = report.conflicts.remove(1);
+        local.resolve(keep, false, &[], &format, None);
+        let take = report.conflicts.remove(0);
+        local.resolve(take, true, &[], &format, None);
+        assert!(local.merge(&other, &[], &format, None).conflicts.is_empty());
+        assert_eq!(data(&["01.07", "bead, broken"]), row_data(&local, 2));
+        assert_eq!(data(&["02.07", "coin"]), row_data(&local, 3));
+        assert_eq!(data(&["02.07", "nail"]), row_data(&local, 4));
+
+        // a record taken from the other register follows its later changes
+        other
+            .get_sheet_mut()
+            .set_row(2, data(&["2", "01.07", "bead, glued"]));
+        other.log_operations(OperationKind::Replace, &[Index::from(2)], "Boris");
+        let report = local.merge(&other, &[], &format, None);
+        assert_eq!(vec![Index::from(2)], report.updated);
+        assert!(report.conflicts.is_empty());
+        // the kept record still differs
+        other.log_operations(OperationKind::Replace, &[Index::from(3)], "Boris");
+        assert_eq!(1, local.merge(&other, &[], &format, None).conflicts.len());
+    }
+
+    #[test]
+    fn merge_references() {
+        let format = IndexFormat::default();
+        let fields = vec![
+            Field::new(FieldType::Text, "Name".to_string(), true),
+            Field::new(FieldType::Reference, "Joins".to_string(), false),
+        ];
+        let mut local = data_base(vec![vec!["1", "01.07", "sherd", ""]]);
+        let mut other = data_base(vec![
+            vec!["1", "01.07", "sherd", ""],
+            vec!["2", "01.07", "sherd", "1"],
+        ]);
+        other.log_operations(OperationKind::Create, &[Index::from(2)], "Anna");
+
+        local.merge(&other, &fields, &format, None);
+        assert_eq!(data(&["01.07", "sherd", "2"]), row_data(&local, 1));
+    }
+}
@@ -1,3 +1,10 @@
+use crate::app::data_base::audit::{AuditTrail, AUDIT_COLUMN};
+use crate::app::data_base::index::Index;
+use crate::app::data_base::photos::Photos;
+use crate::app::data_base::record::Record;
+use crate::app::data_base::reservation::{Reservation, Skip};
+use crate::app::data_base::sort_cells::sort_cells;
+use crate::app::settings::index_format::IndexFormat;
 use umya_spreadsheet::{Cell, Spreadsheet, Worksheet};
 
 pub trait Table {
@@ -13,21 +20,41 @@ pub trait Table {
 
     fn get_string_matrix<'row, 'sheet: 'row>(&'sheet self) -> Vec<Vec<String>>;
 
-    fn get_row_index_from_index(&self, row_num: u32) -> Option<u32>;
+    fn get_indices(&self, index_format: &IndexFormat) -> Vec<(u32, Index)>;
 
-    fn skips(&self) -> Vec<u32>;
+    fn get_row_index_from_index(
+        &self,
+        index: impl Into<Index>,
+        index_format: &IndexFormat,
+    ) -> Option<u32>;
 
-    fn get_end_index(&self) -> f64;
+    fn get_row_data(&self, row_num: u32) -> Vec<String>;
+
+    fn get_record(&self, row_num: u32, size: usize, index_format: &IndexFormat) -> Option<Record>;
+
+    fn get_records(&self, size: usize, index_format: &IndexFormat) -> Vec<Record>;
+
+    fn skips(&self, index_format: &IndexFormat) -> Vec<u32>;
+
+    fn skips_with_reservations(
+        &self,
+        index_format: &IndexFormat,
+        reservations: &[Reservation],
+    ) -> Vec<Skip>;
+
+    fn sub_skips(&self, parent: u32, index_format: &IndexFormat) -> Vec<u32>;
+
+    fn get_end_index(&self, index_format: &IndexFormat) -> u32;
 
     fn get_end_line(&self) -> Vec<&Cell>;
 
     fn get_end_line_index(&self) -> u32;
 
-    fn get_start_index(&self) -> f64;
+    fn get_start_index(&self, index_format: &IndexFormat) -> u32;
 
-    fn get_start_line(&self) -> Vec<&Cell>;
+    fn get_start_line(&self, index_format: &IndexFormat) -> Vec<&Cell>;
 
-    fn get_start_line_index(&self) -> u32;
+    fn get_start_line_index(&self, index_format: &IndexFormat) -> u32;
 
     fn get_begin_cell_in_row<'cell, 'sheet: 'cell>(
         &'sheet self,
@@ -41,8 +68,33 @@ pub trait Table {
 
     fn set_row(&mut self, row_num: u32, cells: Vec<String>);
 
+    /// Writes a record, with a recorder its audit columns are stamped.
+    fn set_record(
+        &mut self,
+        row_num: u32,
+        index: Index,
+        data: Vec<String>,
+        index_format: &IndexFormat,
+        recorder: Option<&str>,
+    );
+
+    fn insert_record(
+        &mut self,
+        row_num: u32,
+        index: Index,
+        data: Vec<String>,
+        index_format: &IndexFormat,
+        recorder: Option<&str>,
+    );
+
     fn row_is_empty(&self, row_num: u32) -> bool;
 
+    fn row_is_blank(&self, row_num: u32) -> bool;
+
+    fn compact(&mut self, index_format: &IndexFormat);
+
+    fn sort_by_index(&mut self, index_format: &IndexFormat);
+
     fn get_count_empty_rows(&self, line_index: u32, top: bool) -> u32;
 }
 
@@ -100,24 +152,74 @@ impl Table for Worksheet {
             .collect::<Vec<_>>()
     }
 
-    fn get_row_index_from_index(&self, index: u32) -> Option<u32> {
-        self.get_collection_by_column(&1)
+    fn get_indices(&self, index_format: &IndexFormat) -> Vec<(u32, Index)> {
+        let mut result = self
+            .get_collection_by_column(&1)
             .iter()
-            .find(|cell| cell.get_value_number().unwrap_or(0.) == index as f64)
-            .map(|cell| *cell.get_coordinate().get_row_num())
+            .filter_map(|cell| {
+                index_format
+                    .parse(&cell.get_value())
+                    .map(|index| (*cell.get_coordinate().get_row_num(), index))
+            })
+            .collect::<Vec<_>>();
+        result.sort_by_key(|(row_num, _)| *row_num);
+        result
     }
 
-    fn skips(&self) -> Vec<u32> {
+    fn get_row_index_from_index(
+        &self,
+        index: impl Into<Index>,
+        index_format: &IndexFormat,
+    ) -> Option<u32> {
+        let index = index.into();
+        self.get_indices(index_format)
+            .into_iter()
+            .find(|(_, row_index)| *row_index == index)
+            .map(|(row_num, _)| row_num)
+    }
+
+    fn get_row_data(&self, row_num: u32) -> Vec<String> {
+        match self.get_end_cell_in_row(row_num) {
+            Some(cell) => (2..=(*cell.get_coordinate().get_col_num()).min(AUDIT_COLUMN - 1))
+                .map(|col_num| self.get_value((col_num, row_num)))
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    fn get_record(&self, row_num: u32, size: usize, index_format: &IndexFormat) -> Option<Record> {
+        let index = index_format.parse(&self.get_value((1, row_num)))?;
+        Some(Record {
+            row: row_num,
+            index,
+            data: sort_cells(self.get_row(row_num), size, 2, |cell| {
+                (
+                    *cell.get_coordinate().get_col_num() as usize,
+                    cell.get_value().to_string(),
+                )
+            }),
+            audit: self.get_audit(row_num),
+            photos: self.get_photos(row_num),
+        })
+    }
+
+    fn get_records(&self, size: usize, index_format: &IndexFormat) -> Vec<Record> {
+        let mut result = self
+            .get_indices(index_format)
+            .into_iter()
+            .filter_map(|(row_num, _)| self.get_record(row_num, size, index_format))
+            .collect::<Vec<_>>();
+        result.sort_by_key(|record| record.index);
+        result
+    }
+
+    fn skips(&self, index_format: &IndexFormat) -> Vec<u32> {
+        // fragments are not counted: a number is only taken by its parent record
         let nums = self
-            .get_row_dimensions()
-            .iter()
-            .filter_map(|row| {
-                self.get_begin_cell_in_row(*row.get_row_num()).map(|cell| {
-                    cell.get_value_number()
-                        .map(|num| num as u32)
-                        .unwrap_or(0)
-                })
-            })
+            .get_indices(index_format)
+            .into_iter()
+            .filter(|(_, index)| index.sub.is_none())
+            .map(|(_, index)| index.number)
             .collect::<Vec<_>>();
 
         (*nums.iter().min().unwrap_or(&0)..*nums.iter().max().unwrap_or(&0))
@@ -125,9 +227,39 @@ impl Table for Worksheet {
             .collect()
     }
 
-    fn get_end_index(&self) -> f64 {
-        self.get_value_number((1, self.get_end_line_index()))
-            .unwrap_or(0.0)
+    fn skips_with_reservations(
+        &self,
+        index_format: &IndexFormat,
+        reservations: &[Reservation],
+    ) -> Vec<Skip> {
+        let mut result = self
+            .skips(index_format)
+            .into_iter()
+            .filter(|&skip| !reservations.iter().any(|reservation| reservation.contains(skip)))
+            .map(Skip::Free)
+            .collect::<Vec<_>>();
+        result.extend(reservations.iter().cloned().map(Skip::Reserved));
+        result
+    }
+
+    fn sub_skips(&self, parent: u32, index_format: &IndexFormat) -> Vec<u32> {
+        let subs = self
+            .get_indices(index_format)
+            .into_iter()
+            .filter(|(_, index)| index.number == parent)
+            .filter_map(|(_, index)| index.sub)
+            .collect::<Vec<_>>();
+
+        (1..*subs.iter().max().unwrap_or(&0))
+            .filter(|&i| subs.iter().find(|&&sub| sub == i).is_none())
+            .collect()
+    }
+
+    fn get_end_index(&self, index_format: &IndexFormat) -> u32 {
+        index_format
+            .parse(&self.get_value((1, self.get_end_line_index())))
+            .map(|index| index.number)
+            .unwrap_or(0)
     }
 
     fn get_end_line(&self) -> Vec<&Cell> {
@@ -152,16 +284,18 @@ impl Table for Worksheet {
         result
     }
 
-    fn get_start_index(&self) -> f64 {
-        self.get_value_number((1, self.get_start_line_index()))
-            .unwrap_or(0.0)
+    fn get_start_index(&self, index_format: &IndexFormat) -> u32 {
+        index_format
+            .parse(&self.get_value((1, self.get_start_line_index(index_format))))
+            .map(|index| index.number)
+            .unwrap_or(0)
     }
 
-    fn get_start_line(&self) -> Vec<&Cell> {
-        self.get_row(self.get_start_line_index())
+    fn get_start_line(&self, index_format: &IndexFormat) -> Vec<&Cell> {
+        self.get_row(self.get_start_line_index(index_format))
     }
 
-    fn get_start_line_index(&self) -> u32 {
+    fn get_start_line_index(&self, index_format: &IndexFormat) -> u32 {
         let mut result = None;
         for num_row in self
             .get_row_dimensions()
@@ -169,7 +303,9 @@ impl Table for Worksheet {
             .map(|row| *row.get_row_num())
         {
             if !self.get_collection_by_row(&num_row).is_empty()
-                && self.get_begin_cell_in_row(num_row).unwrap().get_value_number().is_some()
+                && index_format
+                    .parse(&self.get_begin_cell_in_row(num_row).unwrap().get_value())
+                    .is_some()
                 && (result.is_none() || num_row < result.unwrap())
             {
                 result = Some(num_row);
@@ -218,10 +354,97 @@ impl Table for Worksheet {
         }
     }
 
+    fn set_record(
+        &mut self,
+        row_num: u32,
+        index: Index,
+        data: Vec<String>,
+        index_format: &IndexFormat,
+        recorder: Option<&str>,
+    ) {
+        self.set_row(row_num, [vec![index_format.format(index)], data].concat());
+        if !index_format.is_numeric(index) {
+            self.get_cell_mut((1, row_num))
+                .set_value_string(index_format.format(index));
+        }
+        if let Some(recorder) = recorder {
+            // a new row has no audit yet, so it is stamped as created
+            self.stamp(row_num, recorder, false);
+        }
+    }
+
+    fn insert_record(
+        &mut self,
+        row_num: u32,
+        index: Index,
+        data: Vec<String>,
+        index_format: &IndexFormat,
+        recorder: Option<&str>,
+    ) {
+        self.insert_new_row(&row_num, &1);
+        self.set_record(row_num, index, data, index_format, recorder);
+    }
+
     fn row_is_empty(&self, row_num: u32) -> bool {
         self.get_begin_cell_in_row(row_num).map(|cell| cell.get_value().is_empty()).unwrap_or(true)
     }
 
+    fn row_is_blank(&self, row_num: u32) -> bool {
+        self.get_row(row_num).iter().all(|cell| cell.get_value().is_empty())
+    }
+
+    fn compact(&mut self, index_format: &IndexFormat) {
+        let start_line_index = self.get_start_line_index(index_format);
+        let end_line_index = self.get_end_line_index();
+        if end_line_index == 0 {
+            return;
+        }
+
+        for row_num in (start_line_index..=end_line_index).rev() {
+            if self.row_is_blank(row_num) {
+                self.remove_row(&row_num, &1);
+            }
+        }
+    }
+
+    fn sort_by_index(&mut self, index_format: &IndexFormat) {
+        let start_line_index = self.get_start_line_index(index_format);
+        let end_line_index = self.get_end_line_index();
+        if end_line_index == 0 {
+            return;
+        }
+
+        let mut rows = (start_line_index..=end_line_index)
+            .filter(|&row_num| !self.row_is_blank(row_num))
+            .map(|row_num| {
+                (
+                    index_format.parse(&self.get_value((1, row_num))),
+                    self.get_value((1, row_num)),
+                    self.get_row_data(row_num),
+                    self.get_audit(row_num),
+                    self.get_photos(row_num),
+                )
+            })
+            .collect::<Vec<_>>();
+        // rows with a malformed index are kept at the end in their original order
+        rows.sort_by_key(|(index, _, _, _, _)| (index.is_none(), *index));
+
+        self.remove_row(&start_line_index, &(end_line_index - start_line_index + 1));
+        self.insert_new_row(&start_line_index, &(rows.len() as u32));
+        for ((index, value, data, audit, photos), row_num) in
+            rows.into_iter().zip(start_line_index..)
+        {
+            match index {
+                Some(index) => self.set_record(row_num, index, data, index_format, None),
+                None => self.set_row(row_num, [vec![value], data].concat()),
+            }
+            self.set_audit(row_num, &audit);
+            if !photos.is_empty() {
+                self.set_photos(row_num, &photos);
+            }
+        }
+    }
+
     fn get_count_empty_rows(&self, line_index: u32, top: bool) -> u32 {
         let mut result = 0;
         let line_index_iter: Box<dyn Iterator<Item = u32>> = if top { 
@@ -244,6 +467,9 @@ impl Table for Worksheet {
 #[cfg(test)]
 pub(in crate::app::data_base) mod test {
     use super::Table;
+    use crate::app::data_base::index::Index;
+    use crate::app::data_base::reservation::{Reservation, Skip};
+    use crate::app::settings::index_format::IndexFormat;
     use sugar::hashmap;
     use umya_spreadsheet::{new_file, Spreadsheet, Worksheet};
 
@@ -417,7 +643,7 @@ pub(in crate::app::data_base) mod test {
                     vec!["3", "b", "c"],
                     vec!["5", "b", "c"],
                 ])
-                .skips()
+                .skips(&IndexFormat::default())
         );
         assert_eq!(
             vec![2, 4],
@@ -428,7 +654,7 @@ pub(in crate::app::data_base) mod test {
                     vec!["3", "b", "c"],
                     vec!["5", "b", "c"],
                 ])
-                .skips()
+                .skips(&IndexFormat::default())
         );
         assert_eq!(
             vec![2, 4],
@@ -441,10 +667,128 @@ pub(in crate::app::data_base) mod test {
                     vec!["3", "b", "c"],
                     vec!["", "b", "c"],
                 ])
-                .skips()
+                .skips(&IndexFormat::default())
+        );
+    }
+
+    #[test]
+    fn skips_with_index_format() {
+        let index_format = IndexFormat {
+            prefix_template: "{site}-".to_string(),
+            site: "KR".to_string(),
+            padding: 4,
+            ..IndexFormat::default()
+        };
+        assert_eq!(
+            vec![2, 4],
+            new_file()
+                .matrix_to_sheet(vec![
+                    vec!["index", "b", "c"],
+                    vec!["KR-0001", "b", "c"],
+                    vec!["KR-0003", "b", "c"],
+                    vec!["KR-0003.1", "b", "c"],
+                    vec!["KR-0005", "b", "c"],
+                ])
+                .skips(&index_format)
+        );
+    }
+
+    #[test]
+    fn skips_with_sub_records() {
+        assert_eq!(
+            vec![2, 3],
+            new_file()
+                .matrix_to_sheet(vec![
+                    vec!["1", "b", "c"],
+                    vec!["1.1", "b", "c"],
+                    vec!["3.1", "b", "c"],
+                    vec!["4", "b", "c"],
+                ])
+                .skips(&IndexFormat::default())
         );
     }
 
+    #[test]
+    fn skips_with_reservations() {
+        let reservation = Reservation {
+            start: 3,
+            end: 4,
+            team: "sieving".to_string(),
+        };
+        assert_eq!(
+            vec![Skip::Free(2), Skip::Free(6), Skip::Reserved(reservation.clone())],
+            new_file()
+                .matrix_to_sheet(vec![
+                    vec!["1", "b", "c"],
+                    vec!["5", "b", "c"],
+                    vec!["7", "b", "c"],
+                ])
+                .skips_with_reservations(&IndexFormat::default(), &[reservation])
+        );
+    }
+
+    #[test]
+    fn sub_skips() {
+        let mut book = new_file();
+        let sheet = book.matrix_to_sheet(vec![
+            vec!["1", "b", "c"],
+            vec!["1.1", "b", "c"],
+            vec!["1.4", "b", "c"],
+            vec!["2", "b", "c"],
+        ]);
+        assert_eq!(vec![2, 3], sheet.sub_skips(1, &IndexFormat::default()));
+        assert_eq!(Vec::<u32>::new(), sheet.sub_skips(2, &IndexFormat::default()));
+    }
+
+    #[test]
+    fn get_records() {
+        let mut book = new_file();
+        let sheet = book.matrix_to_sheet(vec![
+            vec!["index", "date", "name"],
+            vec!["2", "d", "b"],
+            vec!["1", "d", "c"],
+            vec!["1.1", "d"],
+        ]);
+        let records = sheet.get_records(3, &IndexFormat::default());
+        assert_eq!(
+            vec![
+                (3, Index::new(1, None)),
+                (4, Index::new(1, Some(1))),
+                (2, Index::new(2, None)),
+            ],
+            records
+                .iter()
+                .map(|record| (record.row, record.index))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(vec!["d", "c", ""], records[0].data);
+        assert_eq!(vec!["d", "", ""], records[1].data);
+    }
+
+    #[test]
+    fn get_row_index_from_index() {
+        let index_format = IndexFormat {
+            prefix_template: "{site}-".to_string(),
+            site: "KR".to_string(),
+            padding: 4,
+            ..IndexFormat::default()
+        };
+        let mut book = new_file();
+        let sheet = book.matrix_to_sheet(vec![
+            vec!["index", "b", "c"],
+            vec!["KR-0157", "b", "c"],
+            vec!["KR-0157.2", "b", "c"],
+            vec!["158", "b", "c"],
+        ]);
+        assert_eq!(Some(2), sheet.get_row_index_from_index(157, &index_format));
+        assert_eq!(
+            Some(3),
+            sheet.get_row_index_from_index(Index::new(157, Some(2)), &index_format)
+        );
+        assert_eq!(Some(4), sheet.get_row_index_from_index(158, &index_format));
+        assert_eq!(None, sheet.get_row_index_from_index(159, &index_format));
+    }
+
     #[test]
     fn get_start_line_index() {
         assert_eq!(
@@ -455,7 +799,7 @@ pub(in crate::app::data_base) mod test {
                     vec!["3.", "b", "c"],
                     vec!["5.", "b", "c"],
                 ])
-                .get_start_line_index()
+                .get_start_line_index(&IndexFormat::default())
         );
         assert_eq!(
             2,
@@ -465,7 +809,7 @@ pub(in crate::app::data_base) mod test {
                     vec!["3", "b", "c"],
                     vec!["5", "b", "c"],
                 ])
-                .get_start_line_index()
+                .get_start_line_index(&IndexFormat::default())
         );
         assert_eq!(
             3,
@@ -476,7 +820,7 @@ pub(in crate::app::data_base) mod test {
                     vec!["1", "b", "c"],
                     vec!["2", "b", "c"],
                 ])
-                .get_start_line_index()
+                .get_start_line_index(&IndexFormat::default())
         );
     }
     
@@ -496,6 +840,60 @@ pub(in crate::app::data_base) mod test {
         assert!(!sheet.row_is_empty(3));
     }
 
+    #[test]
+    fn compact() {
+        let mut book = new_file();
+        let sheet = book.matrix_to_sheet(vec![
+            vec!["index", "name", "info"],
+            vec!["", "", ""],
+            vec!["1", "b", "c"],
+            vec!["", "", ""],
+            vec!["", "b", ""],
+            vec!["", "", ""],
+            vec!["3", "b", "c"],
+        ]);
+        sheet.compact(&IndexFormat::default());
+        assert_eq!(
+            vec![
+                vec!["index", "name", "info"],
+                vec!["", "", ""],
+                vec!["1", "b", "c"],
+                vec!["", "b", ""],
+                vec!["3", "b", "c"],
+            ],
+            sheet.get_string_matrix()
+        );
+
+        let mut book = new_file();
+        let sheet = book.matrix_to_sheet(vec![]);
+        sheet.compact(&IndexFormat::default());
+        assert_eq!(0, sheet.get_end_line_index());
+    }
+
+    #[test]
+    fn sort_by_index() {
+        let mut book = new_file();
+        let sheet = book.matrix_to_sheet(vec![
+            vec!["index", "name", "info"],
+            vec!["3", "b", "c"],
+            vec!["", "", ""],
+            vec!["x", "d", "e"],
+            vec!["1.1", "f", "g"],
+            vec!["1", "h", "i"],
+        ]);
+        sheet.sort_by_index(&IndexFormat::default());
+        assert_eq!(
+            vec![
+                vec!["index", "name", "info"],
+                vec!["1", "h", "i"],
+                vec!["1.1", "f", "g"],
+                vec!["3", "b", "c"],
+                vec!["x", "d", "e"],
+            ],
+            sheet.get_string_matrix()
+        );
+    }
+
     #[test]
     fn get_count_empty_rows() {
         assert_eq!(
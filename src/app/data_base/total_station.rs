@@ -0,0 +1,317 @@
+use crate::app::data_base::audit::AuditTrail;
+use crate::app::data_base::create_record::CreateRecord;
+use crate::app::data_base::index::Index;
+use crate::app::data_base::report::fill;
+use crate::app::data_base::spatial::{coordinates_id, Point};
+use crate::app::data_base::sync::OperationKind;
+use crate::app::data_base::table::Table;
+use crate::app::data_base::DataBase;
+use crate::app::i18n::tr;
+use crate::app::settings::index_format::IndexFormat;
+use crate::app::settings::Field;
+use std::path::Path;
+
+/// A point measured by the total station, `id` is the point number typed on the instrument.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StationPoint {
+    pub id: String,
+    pub point: Point,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ImportReport {
+    /// Finds whose coordinates were filled.
+    pub filled: Vec<Index>,
+    /// Finds whose other coordinates were replaced.
+    pub overwritten: Vec<Index>,
+    /// Records created for points without a find.
+    pub created: Vec<Index>,
+    /// Point IDs matching no find.
+    pub unmatched: Vec<String>,
+    /// Finds still without coordinates.
+    pub without_point: Vec<Index>,
+}
+
+impl ImportReport {
+    /// The counts of the report in the language of the interface.
+    pub fn text(&self, language: &str) -> String {
+        fill(
+            tr(
+                language,
+                "{filled} filled, {overwritten} overwritten, {created} created, \
+                 {unmatched} points without a find, {without_point} finds without a point",
+            ),
+            &[
+                ("filled", self.filled.len().to_string()),
+                ("overwritten", self.overwritten.len().to_string()),
+                ("created", self.created.len().to_string()),
+                ("unmatched", self.unmatched.len().to_string()),
+                ("without_point", self.without_point.len().to_string()),
+            ],
+        )
+    }
+}
+
+/// Points from a CSV export: `id, east, north[, height]` separated by `,`, `;` or tabs.
+/// Lines that are not points, like a header, are skipped.
+pub fn parse_csv(text: &str) -> Vec<StationPoint> {
+    text.lines()
+        .filter_map(|line| {
+            let separator = [';', '\t', ','].into_iter().find(|&separator| line.contains(separator))?;
+            let cells = line.split(separator).map(str::trim).collect::<Vec<_>>();
+            let id = cells.first().filter(|id| !id.is_empty())?;
+            Some(StationPoint {
+                id: id.to_string(),
+                point: Point::parse(&cells[1..cells.len().min(4)].join(";"))?,
+            })
+        })
+        .collect()
+}
+
+/// Points from a Leica GSI-8 or GSI-16 file: word 11 is the point number,
+/// 81, 82 and 83 the east, north and height. Only metric units are read.
+pub fn parse_gsi(text: &str) -> Result<Vec<StationPoint>, String> {
+    text.lines()
+        .filter_map(|line| parse_gsi_line(line).transpose())
+        .collect()
+}
+
+/// The point of a GSI line, `None` if the line is not a point.
+fn parse_gsi_line(line: &str) -> Result<Option<StationPoint>, String> {
+    let mut id = None;
+    let (mut x, mut y, mut z) = (None, None, None);
+    for word in line.split_whitespace() {
+        let word = word.trim_start_matches('*');
+        if word.len() < 8 || !word.is_ascii() {
+            continue;
+        }
+        let data = word[7..].trim_start_matches('0');
+        match &word[..2] {
+            "11" => id = Some(if data.is_empty() { "0" } else { data }.to_string()),
+            "81" | "82" | "83" => {
+                // the last digit of the word info is the unit
+                let scale = match &word[5..6] {
+                    "0" => 0.001,
+                    "6" => 0.0001,
+                    "8" => 0.00001,
+                    "1" | "7" => return Err("Coordinates in feet are not supported".to_string()),
+                    unit => return Err(format!("Unknown GSI unit {}", unit)),
+                };
+                let sign = if &word[6..7] == "-" { -1. } else { 1. };
+                let value = sign * data.parse::<i64>().unwrap_or(0) as f64 * scale;
+                match &word[..2] {
+                    "81" => x = Some(value),
+                    "82" => y = Some(value),
+                    _ => z = Some(value),
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(id.zip(x).zip(y).map(|((id, x), y)| StationPoint {
+        id,
+        point: Point { x, y, z },
+    }))
+}
+
+impl DataBase {
+    fn fill_points(
+        &mut self,
+        points: &[StationPoint],
+        fields: &[Field],
+        create_missing: bool,
+        date: &str,
+        index_format: &IndexFormat,
+        recorder: Option<&str>,
+    ) -> Result<ImportReport, String> {
+        let coordinates_id =
+            coordinates_id(fields).ok_or("There is no coordinate field".to_string())?;
+        let col_num = coordinates_id as u32 + 2;
+        let mut report = ImportReport::default();
+        let sheet = self.get_sheet_mut();
+        for station_point in points {
+            let Some(index) = index_format.parse(&station_point.id) else {
+                report.unmatched.push(station_point.id.clone());
+                continue;
+            };
+            let value = station_point.point.to_string();
+            match sheet.get_row_index_from_index(index, index_format) {
+                Some(row) => {
+                    let previous = sheet.get_value((col_num, row));
+                    if previous.trim().is_empty() {
+                        report.filled.push(index);
+                    } else if previous != value {
+                        report.overwritten.push(index);
+                    } else {
+                        continue;
+                    }
+                    sheet.get_cell_mut((col_num, row)).set_value_string(value);
+                    if let Some(recorder) = recorder {
+                        sheet.stamp(row, recorder, false);
+                    }
+                }
+                None if create_missing => {
+                    let mut data = vec![date.to_string()];
+                    data.resize(fields.len() + 1, "".to_string());
+                    data[coordinates_id] = value;
+                    report.created.extend(sheet.create_record_from_index(
+                        index,
+                        data,
+                        index_format,
+                        recorder,
+                    ));
+                }
+                None => report.unmatched.push(station_point.id.clone()),
+            }
+        }
+        report.without_point = sheet
+            .get_records(fields.len() + 1, index_format)
+            .into_iter()
+            .filter(|record| {
+                !record
+                    .data
+                    .get(coordinates_id)
+                    .is_some_and(|value| !value.trim().is_empty())
+            })
+            .map(|record| record.index)
+            .collect();
+
+        self.log_operations(
+            OperationKind::Replace,
+            &[report.filled.clone(), report.overwritten.clone()].concat(),
+            recorder.unwrap_or_default(),
+        );
+        self.log_operations(
+            OperationKind::Create,
+            &report.created,
+            recorder.unwrap_or_default(),
+        );
+        Ok(report)
+    }
+
+    /// Writes the points into the coordinate field of the finds with the same index.
+    /// Points without a find become new records dated `date` if `create_missing` is set.
+    pub fn import_points(
+        path: &Path,
+        points: &[StationPoint],
+        fields: &[Field],
+        create_missing: bool,
+        date: &str,
+        index_format: &IndexFormat,
+        recorder: Option<&str>,
+    ) -> Result<ImportReport, String> {
+        let mut data_base = DataBase::from(path);
+        let report =
+            data_base.fill_points(points, fields, create_missing, date, index_format, recorder)?;
+        data_base.save(path);
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_csv, parse_gsi, StationPoint};
+    use crate::app::data_base::index::Index;
+    use crate::app::data_base::spatial::Point;
+    use crate::app::data_base::table::Table;
+    use crate::app::data_base::DataBase;
+    use crate::app::settings::index_format::IndexFormat;
+    use crate::app::settings::{Field, FieldType};
+    use umya_spreadsheet::new_file;
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            vec![
+                StationPoint {
+                    id: "15".to_string(),
+                    point: Point { x: 100.5, y: 200.25, z: Some(3.) }
+                },
+                StationPoint {
+                    id: "16".to_string(),
+                    point: Point { x: 101., y: 201., z: None }
+                }
+            ],
+            parse_csv("PtID;East;North;Height\n15;100,5;200,25;3\n16,101,201\n")
+        );
+        assert_eq!(
+            vec![StationPoint {
+                id: "15".to_string(),
+                point: Point { x: 5.387, y: -0.992, z: Some(0.4) }
+            }],
+            parse_gsi(
+                "110001+00000015 81..10+00005387 82..10-00000992 83..10+00000400\n\
+                 410002+00000000\n"
+            )
+            .unwrap()
+        );
+        let east = |unit: char| {
+            parse_gsi(&format!("110001+00000015 81..1{unit}+00053870 82..1{unit}-00009920"))
+                .map(|points| points[0].point.x)
+        };
+        for (unit, expected) in [('0', 53.87), ('6', 5.387), ('8', 0.5387)] {
+            assert!((east(unit).unwrap() - expected).abs() < 1e-9);
+        }
+        assert!(east('1').is_err());
+        assert!(east('7').is_err());
+    }
+
+    #[test]
+    fn fill_points() {
+        let format = IndexFormat::default();
+        let fields = vec![
+            Field::new(FieldType::Text, "Name".to_string(), true),
+            Field::new(FieldType::Coordinates, "Point".to_string(), false),
+        ];
+        let mut data_base = DataBase { book: new_file() };
+        for (row, row_num) in [vec!["1", "01.07", "pot"], vec!["2", "01.07", "bead"]]
+            .into_iter()
+            .zip(1..)
+        {
+            data_base
+                .get_sheet_mut()
+                .set_row(row_num, row.into_iter().map(str::to_string).collect());
+        }
+        let points = [
+            StationPoint {
+                id: "1".to_string(),
+                point: Point { x: 3., y: 5., z: Some(1.5) },
+            },
+            StationPoint {
+                id: "4".to_string(),
+                point: Point { x: 4., y: 5., z: None },
+            },
+            StationPoint {
+                id: "base".to_string(),
+                point: Point { x: 0., y: 0., z: None },
+            },
+        ];
+
+        let report = data_base
+            .fill_points(&points, &fields, false, "02.07", &format, None)
+            .unwrap();
+        assert_eq!(vec![Index::from(1)], report.filled);
+        assert_eq!(vec!["4".to_string(), "base".to_string()], report.unmatched);
+        assert_eq!(vec![Index::from(2)], report.without_point);
+        assert_eq!(vec!["01.07", "pot", "3; 5; 1.5"], data_base.get_sheet().get_row_data(1));
+
+        let moved = [StationPoint {
+            id: "1".to_string(),
+            point: Point { x: 3., y: 6., z: None },
+        }];
+        let report = data_base
+            .fill_points(&moved, &fields, false, "02.07", &format, None)
+            .unwrap();
+        assert!(report.filled.is_empty());
+        assert_eq!(vec![Index::from(1)], report.overwritten);
+
+        let report = data_base
+            .fill_points(&points, &fields, true, "02.07", &format, None)
+            .unwrap();
+        assert_eq!(vec![Index::from(4)], report.created);
+        assert_eq!(vec!["base".to_string()], report.unmatched);
+        assert!(data_base
+            .fill_points(&points, &fields[..1], true, "02.07", &format, None)
+            .is_err());
+    }
+}
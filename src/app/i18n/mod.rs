@@ -0,0 +1,140 @@
+mod ru;
+
+use std::fmt::{Display, Formatter};
+
+/// A language of the interface. The interface is written in English,
+/// so the English catalogue is empty and every other one maps the English text to its own.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Language {
+    English,
+    Russian,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::Russian];
+
+    /// The code stored in `Settings::current_language`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Russian => "ru",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Language::ALL
+            .into_iter()
+            .find(|language| language.code() == code)
+    }
+
+    fn catalogue(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Language::English => &[],
+            Language::Russian => ru::CATALOGUE,
+        }
+    }
+}
+
+impl Display for Language {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Language::English => write!(f, "English"),
+            Language::Russian => write!(f, "Русский"),
+        }
+    }
+}
+
+/// Translates a text of the interface, an unknown language or text stays in English.
+pub fn tr(language: &str, text: &'static str) -> &'static str {
+    Language::from_code(language)
+        .and_then(|language| {
+            language
+                .catalogue()
+                .iter()
+                .find(|(english, _)| *english == text)
+        })
+        .map(|(_, translation)| *translation)
+        .unwrap_or(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{tr, Language};
+    use std::fs;
+    use std::path::Path;
+
+    /// The string literal at the start of a call argument, unescaped.
+    fn literal(argument: &str) -> Option<String> {
+        let mut chars = argument.strip_prefix('"')?.chars();
+        let mut text = String::new();
+        while let Some(char) = chars.next() {
+            match char {
+                '"' => return Some(text),
+                '\\' => match chars.next()? {
+                    // a line continuation skips the indentation of the next line
+                    '\n' => {
+                        chars = chars.as_str().trim_start().chars();
+                    }
+                    'n' => text.push('\n'),
+                    escaped => text.push(escaped),
+                },
+                char => text.push(char),
+            }
+        }
+        None
+    }
+
+    /// The texts passed to `tr` in the sources of a folder,
+    /// as `self.tr("...")` or `tr(language, "...")`. The catalogues are skipped.
+    fn translated_texts(folder: &Path, texts: &mut Vec<String>) {
+        for entry in fs::read_dir(folder).unwrap().flatten() {
+            let path = entry.path();
+            if path.ends_with("i18n") {
+                continue;
+            }
+            if path.is_dir() {
+                translated_texts(&path, texts);
+            } else if path.extension().is_some_and(|extension| extension == "rs") {
+                let source = fs::read_to_string(&path).unwrap();
+                let calls = source.match_indices("tr(").filter(|(start, _)| {
+                    !source[..*start]
+                        .ends_with(|char: char| char.is_alphanumeric() || char == '_')
+                });
+                for (start, _) in calls {
+                    let argument = source[start + 3..].trim_start();
+                    let argument = match argument.split_once(',') {
+                        Some((language, text)) if language.trim() == "language" => {
+                            text.trim_start()
+                        }
+                        _ => argument,
+                    };
+                    texts.extend(literal(argument));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn catalogues() {
+        assert_eq!("create", tr("en", "create"));
+        assert_eq!("создать", tr("ru", "create"));
+        assert_eq!("create", tr("xx", "create"));
+
+        let mut texts = vec![];
+        translated_texts(
+            &Path::new(env!("CARGO_MANIFEST_DIR")).join("src/app"),
+            &mut texts,
+        );
+        assert!(texts.iter().any(|text| text == "create"));
+        for language in Language::ALL.into_iter().skip(1) {
+            for text in &texts {
+                assert!(
+                    language.catalogue().iter().any(|(english, _)| english == text),
+                    "\"{}\" has no {} translation",
+                    text,
+                    language
+                );
+            }
+        }
+    }
+}
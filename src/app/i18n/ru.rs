@@ -0,0 +1,329 @@
+/// The Russian interface, by English text.
+pub const CATALOGUE: &[(&str, &str)] = &[
+    // tabs
+    ("Main", "Главная"),
+    ("Records", "Записи"),
+    ("Check", "Проверка"),
+    ("Scan", "Сканирование"),
+    ("Boxes", "Ящики"),
+    ("Status", "Статус"),
+    ("Statistics", "Статистика"),
+    ("Catalogue", "Каталог"),
+    ("Plan", "План"),
+    ("Contexts", "Контексты"),
+    ("Sync", "Синхронизация"),
+    ("Settings", "Настройки"),
+    ("Update", "Обновление"),
+    // main menu
+    ("Date", "Дата"),
+    ("Insets method", "Способ вставки"),
+    ("Start/End", "Начало/конец"),
+    ("Input", "Ввод"),
+    ("Auto insert", "Автовставка"),
+    ("Fragments", "Фрагменты"),
+    ("End", "В конец"),
+    ("Start", "В начало"),
+    (
+        "If the first index is equal to 1, the Start option is not available",
+        "Если первый номер равен 1, вставка в начало недоступна",
+    ),
+    ("Enter index", "Введите номер"),
+    ("are you sure you want to replace the data", "вы уверены, что хотите заменить данные"),
+    ("record into the range {reservation}", "записать в диапазон {reservation}"),
+    ("Pick index", "Выберите номер"),
+    ("Enter parent index", "Введите номер родительской записи"),
+    (
+        "Fragments copy the fields of the parent record",
+        "Фрагменты копируют поля родительской записи",
+    ),
+    ("The parent record does not exist", "Родительской записи не существует"),
+    ("Enter {field}", "Введите {field}"),
+    ("x; y; z expected", "ожидается x; y; z"),
+    ("indices separated by ;", "номера через ;"),
+    ("indices separated by ; expected", "ожидаются номера через ;"),
+    ("the coordinates are in {square}", "координаты в квадрате {square}"),
+    ("the coordinates are outside of the grid", "координаты вне сетки"),
+    ("!!! This record already exist", "!!! Эта запись уже существует"),
+    ("quantity:", "количество:"),
+    (
+        "The field is only available in start/end and fragments modes",
+        "Поле доступно только в режимах начало/конец и фрагменты",
+    ),
+    ("Replace", "Заменить"),
+    ("Not replace", "Не заменять"),
+    ("create", "создать"),
+    // records
+    ("Select a record", "Выберите запись"),
+    ("Row {row} does not contain a valid index", "Строка {row} не содержит правильного номера"),
+    (
+        "recorded by {recorder}, created {created}, modified {modified}",
+        "записал {recorder}, создано {created}, изменено {modified}",
+    ),
+    ("Photos", "Фотографии"),
+    ("attach", "прикрепить"),
+    ("History", "История"),
+    ("Parent and other fragments", "Родительская запись и другие фрагменты"),
+    // check and plan
+    ("go to", "перейти"),
+    ("No problems found", "Проблем не найдено"),
+    ("{count} problems found", "Найдено проблем: {count}"),
+    ("import total station file", "импорт файла тахеометра"),
+    ("create records for unknown points", "создавать записи для неизвестных точек"),
+    ("Overwritten coordinates", "Перезаписанные координаты"),
+    ("Points without a find", "Точки без находки"),
+    ("Finds without a point", "Находки без точки"),
+    (
+        "{finds} finds with coordinates, {problems} problems, {grid}",
+        "находок с координатами: {finds}, проблем: {problems}, {grid}",
+    ),
+    ("local grid", "местная сетка"),
+    ("export", "экспорт"),
+    // scan and boxes
+    ("Scan a find tag", "Отсканируйте этикетку находки"),
+    ("scan a find tag", "отсканируйте этикетку находки"),
+    ("Scanned", "Отсканировано"),
+    ("clear", "очистить"),
+    ("set", "установить"),
+    ("to", "в"),
+    ("apply to scanned", "применить к отсканированным"),
+    ("Select a box", "Выберите ящик"),
+    ("label", "этикетка"),
+    ("export contents", "экспорт содержимого"),
+    ("indices, e.g. 15, 16, 20.1", "номера, например 15, 16, 20.1"),
+    ("put into box", "положить в ящик"),
+    ("put scanned finds", "положить отсканированные"),
+    ("Contents", "Содержимое"),
+    ("Moves", "Перемещения"),
+    ("box id", "номер ящика"),
+    ("shelf", "полка"),
+    ("description", "описание"),
+    ("add box", "добавить ящик"),
+    // status and statistics
+    ("finds", "находки"),
+    ("e.g. 15-40, 52", "например 15-40, 52"),
+    ("become", "получают статус"),
+    ("change", "изменить"),
+    ("no status", "без статуса"),
+    ("total", "всего"),
+    ("(empty)", "(пусто)"),
+    ("Index coverage", "Заполненность номеров"),
+    ("daily report for", "дневной отчёт за"),
+    ("date", "дата"),
+    ("count by", "считать по"),
+    ("{count} records", "записей: {count}"),
+    ("export CSV", "экспорт CSV"),
+    // catalogue and contexts
+    ("title", "заголовок"),
+    ("entry", "запись"),
+    ("filter", "фильтр"),
+    ("sort by", "сортировать по"),
+    ("{count} entries", "записей: {count}"),
+    ("save context", "сохранить контекст"),
+    ("remove", "удалить"),
+    ("add relation", "добавить связь"),
+    ("Harris matrix", "Матрица Харриса"),
+    ("export DOT", "экспорт DOT"),
+    ("export PNG", "экспорт PNG"),
+    ("export SVG", "экспорт SVG"),
+    // sync
+    ("this register", "этот реестр"),
+    ("other register", "другой реестр"),
+    ("keep this", "оставить этот"),
+    ("take other", "взять другой"),
+    ("register to merge", "реестр для слияния"),
+    ("merge", "слить"),
+    // settings
+    ("select file", "выбрать файл"),
+    ("Base", "База"),
+    ("Fields", "Поля"),
+    ("path to db", "путь к базе"),
+    ("photos folder", "папка фотографий"),
+    ("camera folder", "папка камеры"),
+    ("camera ingest", "фото с камеры"),
+    ("attach new camera photos", "прикреплять новые фото с камеры"),
+    ("register order", "порядок реестра"),
+    ("insert at the sorted position", "вставлять по порядку"),
+    ("sort register by index", "сортировать реестр по номеру"),
+    ("reserved ranges", "зарезервированные номера"),
+    ("first index", "первый номер"),
+    ("last index", "последний номер"),
+    ("team", "отряд"),
+    ("reserve", "зарезервировать"),
+    ("required fields", "обязательные поля"),
+    ("field names in this language", "названия полей на этом языке"),
+    ("new field", "новое поле"),
+    ("add", "добавить"),
+    ("add coordinates", "добавить координаты"),
+    ("add reference", "добавить ссылку"),
+    ("Find status", "Статус находок"),
+    ("status field", "поле статуса"),
+    ("group by field", "группировать по полю"),
+    ("next:", "следующие:"),
+    ("new status", "новый статус"),
+    ("Daily report", "Дневной отчёт"),
+    ("group by fields", "группировать по полям"),
+    ("template (empty for the built-in one)", "шаблон (пусто для встроенного)"),
+    ("find context field", "поле контекста находки"),
+    ("context fields", "поля контекста"),
+    ("Plan grid", "Сетка плана"),
+    ("EPSG (0 for a local grid)", "EPSG (0 для местной сетки)"),
+    ("origin of A1 (x, y)", "начало A1 (x, y)"),
+    ("square size", "размер квадрата"),
+    ("square field", "поле квадрата"),
+    ("square names", "названия квадратов"),
+    ("letters along x, numbers along y", "буквы по x, цифры по y"),
+    ("Index", "Номер"),
+    ("prefix ({site}, {year}, {trench})", "префикс ({site}, {year}, {trench})"),
+    ("site", "памятник"),
+    ("year", "год"),
+    ("trench", "раскоп"),
+    ("zero padding", "дополнение нулями"),
+    ("sub-number separator", "разделитель подномера"),
+    ("example", "пример"),
+    ("Print Settings", "Настройки печати"),
+    ("path to font", "путь к шрифту"),
+    ("size", "размер"),
+    ("size image", "размер изображения"),
+    ("position image", "положение изображения"),
+    ("text size", "размер текста"),
+    ("Y position of text", "положение текста по Y"),
+    ("API", "API"),
+    ("local HTTP API", "локальный HTTP API"),
+    ("enabled", "включён"),
+    ("port", "порт"),
+    ("network", "сеть"),
+    ("standalone", "автономно"),
+    ("server", "сервер"),
+    ("client", "клиент"),
+    ("local network", "локальная сеть"),
+    (
+        "accept connections, anyone on the network can create records",
+        "принимать подключения, любой в сети сможет создавать записи",
+    ),
+    ("server address", "адрес сервера"),
+    ("Profile", "Профиль"),
+    ("recorder name", "имя записывающего"),
+    ("audit columns", "столбцы аудита"),
+    ("record who and when", "записывать кто и когда"),
+    ("Application", "Приложение"),
+    ("Language", "Язык"),
+    ("Theme", "Тема"),
+    ("Select theme", "Выберите тему"),
+    ("Keyboard", "Клавиатура"),
+    ("today's date", "сегодняшняя дата"),
+    ("quantity up", "увеличить количество"),
+    ("quantity down", "уменьшить количество"),
+    ("unknown key", "неизвестная клавиша"),
+    // messages and reports
+    (
+        "Row {row}: index {index} duplicates row {first_row}",
+        "Строка {row}: индекс {index} повторяет строку {first_row}",
+    ),
+    ("Index {index} is missing", "Индекс {index} пропущен"),
+    ("Row {row}: the index is empty", "Строка {row}: индекс пуст"),
+    ("Row {row}: malformed index \"{index}\"", "Строка {row}: неверный индекс \"{index}\""),
+    (
+        "Row {row}: {index} has empty required fields: {fields}",
+        "Строка {row}: у {index} пусты обязательные поля: {fields}",
+    ),
+    ("Row {row}: {index} is out of order", "Строка {row}: {index} не по порядку"),
+    ("Row {row} is blank", "Строка {row} пуста"),
+    ("Row {row}: {index}: {problem}", "Строка {row}: {index}: {problem}"),
+    (
+        "{first}-{last}: {used} used, {free} free ({percent}% covered), {fragments} fragments",
+        "{first}-{last}: занято {used}, свободно {free} (заполнено {percent}%), фрагментов {fragments}",
+    ),
+    (
+        "{filled} filled, {overwritten} overwritten, {created} created, \
+         {unmatched} points without a find, {without_point} finds without a point",
+        "заполнено {filled}, перезаписано {overwritten}, создано {created}, \
+         точек без находки {unmatched}, находок без точки {without_point}",
+    ),
+    (
+        "{added} added, {updated} updated, {conflicts} conflicts",
+        "добавлено {added}, обновлено {updated}, конфликтов {conflicts}",
+    ),
+    ("{start}-{end} reserved", "{start}-{end} зарезервированы"),
+    ("{start}-{end} reserved for {team}", "{start}-{end} зарезервированы для {team}"),
+    ("{reservation} (unavailable)", "{reservation} (недоступно)"),
+    ("{count} camera photos wait for a record", "Фото с камеры ждут записи: {count}"),
+    ("Photos can only be attached on the server", "Фото можно прикрепить только на сервере"),
+    ("Select a record to attach the photos to", "Выберите запись, к которой прикрепить фото"),
+    ("Attached {photos}", "Прикреплено: {photos}"),
+    ("\"{scan}\" does not contain an index", "\"{scan}\" не содержит индекса"),
+    ("The record {index} does not exist", "Записи {index} нет"),
+    (
+        "Bulk updates can only be made on the server",
+        "Массовые изменения возможны только на сервере",
+    ),
+    (
+        "{field} = \"{value}\" set for {count} records",
+        "{field} = \"{value}\" задано для записей: {count}",
+    ),
+    ("Boxes can only be managed on the server", "Ящиками можно управлять только на сервере"),
+    ("{count} finds put into {box}", "Находок положено в {box}: {count}"),
+    (
+        "{count} finds put into {box}, not found: {missing}",
+        "Находок положено в {box}: {count}, не найдены: {missing}",
+    ),
+    ("Saved {path}", "Сохранено {path}"),
+    ("Statuses can only be changed on the server", "Статусы можно менять только на сервере"),
+    (
+        "There is no field \"{field}\", add it in the settings",
+        "Нет поля \"{field}\", добавьте его в настройках",
+    ),
+    ("no status", "без статуса"),
+    ("{count} finds are {status} now", "Находок со статусом {status}: {count}"),
+    (
+        "{count} finds are {status} now, not allowed for: {rejected}",
+        "Находок со статусом {status}: {count}, не разрешено для: {rejected}",
+    ),
+    ("Can not read {path}: {error}", "Не удалось прочитать {path}: {error}"),
+    ("Points can only be imported on the server", "Точки можно импортировать только на сервере"),
+    ("{count} points read: {report}", "Прочитано точек: {count}: {report}"),
+    ("Enter the ID of the context", "Введите ID контекста"),
+    ("Saved {context}", "Сохранён {context}"),
+    ("Added {relation}", "Добавлено {relation}"),
+    ("Excavation report {date}", "Отчёт о раскопках {date}"),
+    ("Saved {count} finds to {path}", "Сохранено находок в {path}: {count}"),
+    (
+        "Saved {count} finds to {path}, \
+         set its CRS to EPSG:{epsg} when loading it",
+        "Сохранено находок в {path}: {count}, при загрузке укажите систему координат EPSG:{epsg}",
+    ),
+    ("The index {index} is {reservation}", "Номер {index}: {reservation}"),
+    (
+        "There are not enough free indices before the first record",
+        "Перед первой записью недостаточно свободных номеров",
+    ),
+    ("The record {index} already exists", "Запись {index} уже существует"),
+    ("The parent record {index} does not exist", "Родительской записи {index} не существует"),
+    (
+        "The first index of the range is after the last one",
+        "Первый номер диапазона больше последнего",
+    ),
+    ("The range overlaps {reservation}", "Диапазон пересекается с {reservation}"),
+    ("The record {index} is already in the range", "Запись {index} уже есть в диапазоне"),
+    (
+        "Can not start the API on port {port}: {error}",
+        "Не удалось запустить API на порту {port}: {error}",
+    ),
+    (
+        "{field} expects indices separated by \";\", got \"{text}\"",
+        "{field}: ожидаются номера через \";\", получено \"{text}\"",
+    ),
+    ("The linked record {index} does not exist", "Связанной записи {index} не существует"),
+    ("The context {context} does not exist", "Контекста {context} не существует"),
+    (
+        "The context {context} can not be related to itself",
+        "Контекст {context} не может быть связан сам с собой",
+    ),
+    (
+        "{relation} contradicts the matrix: {later} is already earlier than {earlier}",
+        "{relation} противоречит матрице: {later} уже раньше {earlier}",
+    ),
+    ("The context {context} is not in the register", "Контекста {context} нет в реестре"),
+    ("The register can only be changed on the server", "Реестр можно менять только на сервере"),
+    ("Contexts can only be managed on the server", "Контекстами можно управлять только на сервере"),
+    ("Registers can only be merged on the server", "Реестры можно объединять только на сервере"),
+];
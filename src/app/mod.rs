@@ -1,13 +1,43 @@
+use crate::app::data_base::catalogue::{catalogue_entries, render_catalogue, CatalogueFormat};
+use crate::app::data_base::index::Index;
+use crate::app::data_base::export::to_csv;
+use crate::app::data_base::integrity::{Integrity, Problem};
+use crate::app::data_base::photos::{is_image, IMAGE_EXTENSIONS};
+use crate::app::data_base::record::Record;
+use crate::app::data_base::report::{fill, markdown_to_pdf, DEFAULT_TEMPLATE};
+use crate::app::data_base::reservation::{Reservation, Skip, SkipOption};
+use crate::app::data_base::spatial::{coordinates_id, plotted, square_from_coordinates, to_dxf, to_geojson, to_shapefile, PlanFormat, Point, Spatial};
+use crate::app::data_base::statistics::{Coverage, Statistics};
 use crate::app::data_base::table::Table;
+use crate::app::data_base::total_station::{parse_csv, parse_gsi, ImportReport};
 use crate::app::settings::insert_methods::{InsertMethods, InsertMethodsMessage, StartEnd};
+use crate::app::settings::shortcut_settings::ShortcutAction;
 use crate::app::settings::{MessageSettings, Settings};
+use crate::app::ui::boxes_menu::{BoxesData, BoxesMessage};
+use crate::app::ui::contexts_menu::{ContextsData, ContextsMessage};
+use crate::app::ui::records_menu::{RecordsData, RecordsMessage};
+use crate::app::ui::saved;
+use crate::app::ui::scan_menu::{ScanData, ScanMessage};
+use crate::app::ui::status_menu::{StatusData, StatusMessage};
+use crate::app::ui::sync_menu::{SyncData, SyncMessage};
 use data_base::DataBase;
 use iced::font::{Family, Stretch, Weight};
-use iced::widget::combo_box;
-use iced::{window, Font, Subscription, Task, Theme};
+use iced::keyboard::key::Named;
+use iced::keyboard::{Key, Modifiers};
+use iced::widget::{combo_box, focus_next, focus_previous};
+use iced::{event, keyboard, window, Event, Font, Subscription, Task, Theme};
 use iced_aw::date_picker;
+use native_dialog::FileDialog;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
 
+mod api;
+mod camera;
+pub mod cli;
+mod client;
 pub mod data_base;
+mod i18n;
 mod settings;
 pub mod theme;
 mod ui;
@@ -16,6 +46,8 @@ mod ui;
 pub enum Message {
     None,
     Create,
+    /// Enter in an input of the main menu.
+    Submit,
     OnReplace(bool),
     Update,
     Terminal(iced_term::Event),
@@ -26,11 +58,56 @@ pub enum Message {
     SetMenu(MenuStatus),
     SetSettings(MessageSettings),
     RebootAutoInsertState,
+    Records(RecordsMessage),
+    CheckIntegrity,
+    SetReservationData(String, usize),
+    AddReservation,
+    RemoveReservation(usize),
+    SortDataBase,
+    DataBaseChanged,
+    Sync(SyncMessage),
+    AttachPhotos,
+    FileDropped(PathBuf),
+    CameraFiles(Vec<PathBuf>),
+    Scan(ScanMessage),
+    Boxes(BoxesMessage),
+    Status(StatusMessage),
+    SetStatisticsField(String),
+    ExportStatistics,
+    SetReportDate(String),
+    ExportReport(&'static str),
+    SetCatalogueEntry(String),
+    SetCatalogueFilter(String),
+    SetCatalogueSort(String),
+    SetCatalogueTitle(String),
+    ExportCatalogue(CatalogueFormat),
+    ExportPlan(PlanFormat),
+    SetCreateMissing(bool),
+    ImportStation,
+    Contexts(ContextsMessage),
+    KeyPressed(Key, Modifiers, event::Status),
+    ApiStarted(mpsc::Sender<Settings>),
+    /// The API server could not listen on its port.
+    ApiFailed(String),
+    IndexChecked(Index, Result<bool, String>),
+    RecordsLoaded(Result<Vec<Record>, String>),
+    SkipsLoaded(Result<Vec<Skip>, String>),
+    Created(Result<Vec<String>, String>),
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum MenuStatus {
     Main,
+    Records,
+    Integrity,
+    Scan,
+    Boxes,
+    Status,
+    Statistics,
+    Catalogue,
+    Plan,
+    Contexts,
+    Sync,
     Settings,
     Update,
 }
@@ -41,10 +118,52 @@ pub struct ArchaeologicalAssistant {
     pub quantity: String,
     pub settings: Settings,
     pub state_themes: combo_box::State<theme::Theme>,
-    pub state_auto_insert: combo_box::State<u32>,
+    pub state_auto_insert: combo_box::State<SkipOption>,
     pub is_can_start_insert: bool,
     pub is_replace: bool,
     pub on_replace: bool,
+    pub is_parent_exist: bool,
+    pub records_data: RecordsData,
+    pub problems: Vec<Problem>,
+    pub reservations: Vec<Reservation>,
+    pub reservation_data: Vec<String>,
+    /// The answer to reserving indices or sorting the register.
+    pub register_message: Option<String>,
+    pub network_error: Option<String>,
+    pub api_error: Option<String>,
+    pub sync_data: SyncData,
+    pub photo_status: Option<String>,
+    pub last_created: Option<Index>,
+    /// Camera images waiting for a record to be attached to.
+    pub pending_photos: Vec<PathBuf>,
+    pub scan_data: ScanData,
+    pub boxes_data: BoxesData,
+    pub status_data: StatusData,
+    /// The field the statistics count by, "Date" counts the finds per day.
+    pub statistics_field: String,
+    pub statistics: Vec<(String, u32)>,
+    pub coverage: Coverage,
+    pub statistics_message: Option<String>,
+    /// The day the report is made for, as written by the date picker.
+    pub report_date: String,
+    /// The entries of the catalogue with the current template, or why it can not be made.
+    pub catalogue: Result<Vec<String>, String>,
+    catalogue_records: Vec<Record>,
+    /// The finds with coordinates.
+    pub plan: Vec<(Record, Point)>,
+    pub plan_problems: Vec<Problem>,
+    pub plan_message: Option<String>,
+    /// Create records for total station points without a find.
+    pub create_missing: bool,
+    pub import_report: Option<ImportReport>,
+    /// The square last written into the square field from the coordinates.
+    computed_square: Option<String>,
+    /// Why the last record could not be created.
+    pub create_error: Option<String>,
+    pub contexts_data: ContextsData,
+    pub catalogue_message: Option<String>,
+    /// Passes the settings to the running API server.
+    api_settings: Option<mpsc::Sender<Settings>>,
     term: iced_term::Terminal,
 }
 
@@ -56,13 +175,7 @@ impl Default for ArchaeologicalAssistant {
             data: {
                 [vec![date_picker::Date::today().to_string()], {
                     if settings.path_to_db.exists() {
-                        DataBase::from(&*settings.path_to_db)
-                            .get_sheet()
-                            .get_end_line()
-                            .iter()
-                            .skip(2)
-                            .map(|cell| cell.get_value().to_string())
-                            .collect::<Vec<_>>()
+                        DataBase::from(&*settings.path_to_db).last_fields(settings.fields.len())
                     } else {
                         vec!["".to_string(); settings.fields.len()]
                     }
@@ -71,7 +184,10 @@ impl Default for ArchaeologicalAssistant {
             },
             state_auto_insert: {
                 if settings.path_to_db.exists() {
-                    combo_box::State::new(DataBase::from(&*settings.path_to_db).get_sheet().skips())
+                    combo_box::State::new(SkipOption::all(
+                        DataBase::from(&*settings.path_to_db).skips(&settings.index_format),
+                        &settings.current_language,
+                    ))
                 } else {
                     combo_box::State::default()
                 }
@@ -79,14 +195,17 @@ impl Default for ArchaeologicalAssistant {
             quantity: "1".to_string(),
             is_can_start_insert: DataBase::from(&*settings.path_to_db)
                 .get_sheet()
-                .get_start_index()
-                > 1.,
+                .get_start_index(&settings.index_format)
+                > 1,
             is_replace: {
                 if settings.path_to_db.exists() {
                     if settings.insert_methods_data.insert_methods == InsertMethods::Input {
                         DataBase::from(&*settings.path_to_db)
                             .get_sheet()
-                            .get_row_index_from_index(settings.insert_methods_data.input)
+                            .get_row_index_from_index(
+                                settings.insert_methods_data.input,
+                                &settings.index_format,
+                            )
                             .is_some()
                     } else { 
                         false
@@ -96,6 +215,45 @@ impl Default for ArchaeologicalAssistant {
                 }
             },
             on_replace: false,
+            is_parent_exist: settings.path_to_db.exists()
+                && DataBase::from(&*settings.path_to_db)
+                    .get_sheet()
+                    .get_row_index_from_index(
+                        settings.insert_methods_data.parent,
+                        &settings.index_format,
+                    )
+                    .is_some(),
+            records_data: RecordsData::default(),
+            problems: vec![],
+            reservations: DataBase::from(&*settings.path_to_db).get_reservations(),
+            reservation_data: vec!["".to_string(); 3],
+            register_message: None,
+            network_error: None,
+            api_error: None,
+            sync_data: SyncData::default(),
+            photo_status: None,
+            last_created: None,
+            pending_photos: vec![],
+            scan_data: ScanData::default(),
+            boxes_data: BoxesData::default(),
+            status_data: StatusData::default(),
+            statistics_field: "Date".to_string(),
+            statistics: vec![],
+            coverage: Coverage::default(),
+            statistics_message: None,
+            report_date: date_picker::Date::today().to_string(),
+            catalogue: Ok(vec![]),
+            catalogue_records: vec![],
+            plan: vec![],
+            plan_problems: vec![],
+            plan_message: None,
+            create_missing: false,
+            import_report: None,
+            computed_square: None,
+            create_error: None,
+            contexts_data: ContextsData::default(),
+            catalogue_message: None,
+            api_settings: None,
             settings,
             state_themes: combo_box::State::new(Vec::from(theme::Theme::ALL)),
             term: iced_term::Terminal::new(
@@ -132,52 +290,423 @@ impl ArchaeologicalAssistant {
     pub(crate) fn subscription(&self) -> Subscription<Message> {
         let term_subscription = iced_term::Subscription::new(self.term.id);
         let term_event_stream = term_subscription.event_stream();
-        Subscription::run_with_id(self.term.id, term_event_stream).map(Message::Terminal)
+        let term = Subscription::run_with_id(self.term.id, term_event_stream).map(Message::Terminal);
+        let events = event::listen_with(|event, status, _| match event {
+            Event::Window(window::Event::FileDropped(path)) => Some(Message::FileDropped(path)),
+            Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) => {
+                Some(Message::KeyPressed(key, modifiers, status))
+            }
+            _ => None,
+        });
+
+        let events = if self.settings.camera_ingest && self.settings.camera_folder.is_dir() {
+            Subscription::batch([
+                events,
+                camera::subscription(self.settings.camera_folder.to_path_buf()),
+            ])
+        } else {
+            events
+        };
+
+        if self.settings.api.is_client() {
+            return Subscription::batch([
+                term,
+                events,
+                client::subscription(self.settings.api.server_address.clone()),
+            ]);
+        }
+        match u16::try_from(self.settings.api.port) {
+            Ok(port) if self.settings.api.enabled => Subscription::batch([
+                term,
+                events,
+                api::subscription(port, self.settings.api.is_lan()),
+            ]),
+            _ => Subscription::batch([term, events]),
+        }
+    }
+
+    /// Attaches the waiting camera images to the selected record or the last created one.
+    fn ingest_pending_photos(&mut self) {
+        if self.pending_photos.is_empty() || self.settings.api.is_client() {
+            return;
+        }
+        let selected = self
+            .records_data
+            .selected_index()
+            .filter(|_| self.menu_status == MenuStatus::Records);
+        let Some(index) = selected.or(self.last_created) else {
+            self.photo_status = Some(fill(
+                self.tr("{count} camera photos wait for a record"),
+                &[("count", self.pending_photos.len().to_string())],
+            ));
+            return;
+        };
+
+        let _lock = DataBase::lock();
+        self.photo_status = Some(
+            match DataBase::ingest_photos(
+                &self.settings.path_to_db,
+                &self.settings.path_to_media,
+                index,
+                &self.pending_photos,
+                &self.settings.index_format,
+            ) {
+                Ok(mapping) => {
+                    self.pending_photos.clear();
+                    mapping
+                        .iter()
+                        .map(|(original, photo)| format!("{} -> {}", original, photo))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                }
+                Err(error) => error,
+            },
+        );
+    }
+
+    fn attach_photos(&mut self, files: Vec<PathBuf>) -> Task<Message> {
+        self.photo_status = Some(match self.records_data.selected_index() {
+            _ if self.settings.api.is_client() => {
+                self.tr("Photos can only be attached on the server").to_string()
+            }
+            None => self.tr("Select a record to attach the photos to").to_string(),
+            Some(index) => {
+                let _lock = DataBase::lock();
+                match DataBase::attach_photos(
+                    &self.settings.path_to_db,
+                    &self.settings.path_to_media,
+                    index,
+                    &files,
+                    &self.settings.index_format,
+                ) {
+                    Ok(photos) => fill(
+                        self.tr("Attached {photos}"),
+                        &[("photos", photos.join(", "))],
+                    ),
+                    Err(error) => error,
+                }
+            }
+        });
+        self.reload_records()
+    }
+
+    /// Checks whether a record exists, the answer comes with `Message::IndexChecked`.
+    fn check_index(&self, index: impl Into<Index>) -> Task<Message> {
+        let index = index.into();
+        if self.settings.api.is_client() {
+            let settings = self.settings.clone();
+            Task::perform(
+                client::run(move || client::record(&settings, index).map(|record| record.is_some())),
+                move |exists| Message::IndexChecked(index, exists),
+            )
+        } else {
+            let exists = DataBase::from(&*self.settings.path_to_db)
+                .get_sheet()
+                .get_row_index_from_index(index, &self.settings.index_format)
+                .is_some();
+            Task::done(Message::IndexChecked(index, Ok(exists)))
+        }
+    }
+
+    fn save_settings(&mut self) {
+        self.settings.save();
+        if let Some(sender) = &self.api_settings {
+            let _ = sender.send(self.settings.clone());
+        }
+    }
+
+    /// Keeps the error of the last request to the server, so the main menu can show it.
+    fn network<T: Default>(&mut self, result: Result<T, String>) -> T {
+        match result {
+            Ok(value) => {
+                self.network_error = None;
+                value
+            }
+            Err(error) => {
+                self.network_error = Some(error);
+                T::default()
+            }
+        }
+    }
+
+    fn reload_records(&mut self) -> Task<Message> {
+        if self.settings.api.is_client() {
+            let settings = self.settings.clone();
+            return Task::perform(
+                client::run(move || client::records(&settings)),
+                Message::RecordsLoaded,
+            );
+        }
+        self.records_data.records = DataBase::from(&*self.settings.path_to_db)
+            .get_sheet()
+            .get_records(self.data.len(), &self.settings.index_format);
+        Task::none()
+    }
+
+    fn reload_skips(&mut self) -> Task<Message> {
+        if self.settings.api.is_client() {
+            let settings = self.settings.clone();
+            return Task::perform(client::run(move || client::skips(&settings)), Message::SkipsLoaded);
+        }
+        self.state_auto_insert = combo_box::State::new(SkipOption::all(
+            DataBase::from(&*self.settings.path_to_db).skips(&self.settings.index_format),
+            &self.settings.current_language,
+        ));
+        Task::none()
+    }
+
+    /// The message shown once a file is written.
+    fn saved(&self, path: &Path) -> String {
+        saved(&self.settings.current_language, path)
     }
 
-    pub fn update(&mut self, message: Message) {
+    fn reload_statistics(&mut self) {
+        let data_base = DataBase::from(&*self.settings.path_to_db);
+        let sheet = data_base.get_sheet();
+        let data_id = self.settings.data_id(&self.statistics_field).unwrap_or(0);
+        self.statistics = sheet.counts_by(data_id, &self.settings.index_format);
+        self.coverage = sheet.coverage(&self.settings.index_format);
+    }
+
+    /// The report of `report_date` as Markdown, filled into the template from the settings.
+    fn daily_report(&self) -> Result<String, String> {
+        let template = if self.settings.report.template.as_os_str().is_empty() {
+            DEFAULT_TEMPLATE.to_string()
+        } else {
+            fs::read_to_string(&self.settings.report.template).map_err(|error| {
+                fill(
+                    self.tr("Can not read {path}: {error}"),
+                    &[
+                        ("path", self.settings.report.template.to_string_lossy().to_string()),
+                        ("error", error.to_string()),
+                    ],
+                )
+            })?
+        };
+        Ok(DataBase::from(&*self.settings.path_to_db)
+            .daily_report(
+                self.report_date.trim(),
+                &self.settings.fields,
+                &self.settings.report,
+                &self.settings.index_format,
+            )
+            .to_markdown(
+                &template,
+                &self.settings.fields,
+                &self.settings.report,
+                &self.settings.index_format,
+            ))
+    }
+
+    fn reload_catalogue(&mut self) {
+        self.catalogue_records = DataBase::from(&*self.settings.path_to_db)
+            .get_sheet()
+            .get_records(self.settings.fields.len() + 1, &self.settings.index_format);
+        self.update_catalogue();
+    }
+
+    /// Fills the template again without reading the register, while it is being edited.
+    fn update_catalogue(&mut self) {
+        self.catalogue = catalogue_entries(
+            self.catalogue_records.clone(),
+            &self.settings.fields,
+            &self.settings.catalogue,
+            &self.settings.index_format,
+        );
+    }
+
+    fn reload_plan(&mut self) {
+        let data_base = DataBase::from(&*self.settings.path_to_db);
+        let sheet = data_base.get_sheet();
+        self.plan = plotted(
+            sheet.get_records(self.settings.fields.len() + 1, &self.settings.index_format),
+            &self.settings.fields,
+        );
+        self.plan_problems = sheet.check_coordinates(
+            &self.settings.fields,
+            &self.settings.grid,
+            &self.settings.index_format,
+        );
+    }
+
+    /// Writes the square computed from the coordinates into the square field,
+    /// unless the recorder typed another square there.
+    fn prefill_square(&mut self, coordinates: &str) {
+        let Some(square_id) = self.settings.data_id(&self.settings.grid.square_field) else {
+            return;
+        };
+        let square = &mut self.data[square_id];
+        if square.is_empty() || self.computed_square.as_deref() == Some(square.as_str()) {
+            let computed = square_from_coordinates(coordinates, &self.settings.grid);
+            *square = computed.clone().unwrap_or_default();
+            self.computed_square = computed;
+        }
+    }
+
+    fn import_station(&mut self) {
+        if self.settings.api.is_client() {
+            self.plan_message =
+                Some(self.tr("Points can only be imported on the server").to_string());
+            return;
+        }
+        let path = FileDialog::new()
+            .add_filter("total station", &["gsi", "csv", "txt"])
+            .show_open_single_file();
+        let Ok(Some(path)) = path else {
+            return;
+        };
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(error) => {
+                self.plan_message = Some(error.to_string());
+                return;
+            }
+        };
+        let is_gsi = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("gsi"));
+        let points = if is_gsi {
+            match parse_gsi(&text) {
+                Ok(points) => points,
+                Err(error) => {
+                    self.plan_message = Some(error);
+                    return;
+                }
+            }
+        } else {
+            parse_csv(&text)
+        };
+        let result = {
+            let _lock = DataBase::lock();
+            DataBase::import_points(
+                &self.settings.path_to_db,
+                &points,
+                &self.settings.fields,
+                self.create_missing,
+                &self.data[0],
+                &self.settings.index_format,
+                self.settings.audit_recorder(),
+            )
+        };
+        match result {
+            Ok(report) => {
+                self.plan_message = Some(fill(
+                    self.tr("{count} points read: {report}"),
+                    &[
+                        ("count", points.len().to_string()),
+                        ("report", report.text(&self.settings.current_language)),
+                    ],
+                ));
+                self.import_report = Some(report);
+            }
+            Err(error) => self.plan_message = Some(error),
+        }
+        self.reload_plan();
+    }
+
+    fn check_integrity(&mut self) {
+        let data_base = DataBase::from(&*self.settings.path_to_db);
+        let sheet = data_base.get_sheet();
+        self.problems = sheet.check_integrity(&self.settings.fields, &self.settings.index_format);
+        self.problems.extend(sheet.check_coordinates(
+            &self.settings.fields,
+            &self.settings.grid,
+            &self.settings.index_format,
+        ));
+    }
+
+    /// Keyboard entry in the main menu: Tab moves between the inputs, the rest is configurable.
+    fn key_pressed(
+        &mut self,
+        key: Key,
+        modifiers: Modifiers,
+        status: event::Status,
+    ) -> Task<Message> {
+        if key == Key::Named(Named::Tab) {
+            return if modifiers.shift() {
+                focus_previous()
+            } else {
+                focus_next()
+            };
+        }
+        // Enter in an input comes as Message::Submit
+        let captured = status == event::Status::Captured;
+        let Some(action) = self.settings.shortcuts.action(&key, modifiers, captured) else {
+            return Task::none();
+        };
+        let quantity = self.quantity.parse::<u32>().unwrap_or(0);
+        self.update(match action {
+            ShortcutAction::Create => Message::Create,
+            ShortcutAction::StartEnd => Message::SetInsertMethods(InsertMethods::StartEnd),
+            ShortcutAction::Input => Message::SetInsertMethods(InsertMethods::Input),
+            ShortcutAction::AutoInsert => Message::SetInsertMethods(InsertMethods::AutoInsert),
+            ShortcutAction::Fragments => Message::SetInsertMethods(InsertMethods::Fragments),
+            ShortcutAction::Today => Message::SetData(date_picker::Date::today().to_string(), 0),
+            ShortcutAction::QuantityUp => Message::SetQuantity((quantity + 1).to_string()),
+            ShortcutAction::QuantityDown => {
+                Message::SetQuantity(quantity.saturating_sub(1).max(1).to_string())
+            }
+        })
+    }
+
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        let mut task = Task::none();
         match message {
             Message::None => {}
             Message::SetInsertMethods(insert_methods) => {
                 self.on_replace = false;
-                self.is_replace = if insert_methods == InsertMethods::Input {
-                    DataBase::from(&*self.settings.path_to_db)
-                        .get_sheet().get_row_index_from_index(self.settings.insert_methods_data.input).is_some()
-                } else {
-                    false
+                self.is_replace = false;
+                task = match insert_methods {
+                    InsertMethods::Input => {
+                        self.check_index(self.settings.insert_methods_data.input)
+                    }
+                    InsertMethods::Fragments => {
+                        self.check_index(self.settings.insert_methods_data.parent)
+                    }
+                    InsertMethods::AutoInsert
+                        if insert_methods != self.settings.insert_methods_data.insert_methods =>
+                    {
+                        self.reload_skips()
+                    }
+                    _ => Task::none(),
                 };
 
-                if insert_methods == InsertMethods::AutoInsert
-                    && insert_methods != self.settings.insert_methods_data.insert_methods
-                {
-                    self.state_auto_insert = combo_box::State::new(
-                        DataBase::from(&*self.settings.path_to_db.clone())
-                            .get_sheet()
-                            .skips(),
-                    )
-                }
-
                 self.settings.insert_methods_data.insert_methods = insert_methods;
-                self.settings.save()
+                self.save_settings()
             }
             Message::SetInsertMethodsData(insert_methods_input_types) => {
                 self.on_replace = false;
-                self.is_replace = match insert_methods_input_types.clone() {
-                    InsertMethodsMessage::Input(input) => if let Ok(input) = input.parse() {
-                        DataBase::from(&*self.settings.path_to_db)
-                            .get_sheet().get_row_index_from_index(input).is_some()
-                    } else {
-                        false
+                self.is_replace = false;
+                match insert_methods_input_types.clone() {
+                    InsertMethodsMessage::Input(input) => {
+                        if let Some(input) = self.settings.index_format.parse(&input) {
+                            task = self.check_index(input)
+                        }
                     }
-                    _ => false,
-                };
-                self
-                    .settings
+                    InsertMethodsMessage::Parent(parent) => {
+                        match self.settings.index_format.parse_parent(&parent) {
+                            Some(parent) => task = self.check_index(parent),
+                            None => self.is_parent_exist = false,
+                        }
+                    }
+                    _ => {}
+                }
+                self.settings
                     .insert_methods_data
-                    .update(insert_methods_input_types);
-                self.settings.save()
+                    .update(insert_methods_input_types, &self.settings.index_format);
+                self.save_settings()
+            }
+            Message::SetData(str, id) => {
+                if coordinates_id(&self.settings.fields) == Some(id) {
+                    self.prefill_square(&str);
+                }
+                self.data[id] = str
+            }
+            Message::KeyPressed(key, modifiers, status) => {
+                if self.menu_status == MenuStatus::Main {
+                    task = self.key_pressed(key, modifiers, status);
+                }
             }
-            Message::SetData(str, id) => self.data[id] = str,
             Message::SetQuantity(quantity) => {
                 if quantity.is_empty() {
                     self.quantity = "".to_string()
@@ -187,39 +716,114 @@ impl ArchaeologicalAssistant {
             }
             Message::SetMenu(menu_status) => {
                 if self.menu_status != menu_status {
-                    if self.menu_status == MenuStatus::Settings {
-                        self.settings.save()
+                    // the catalogue template is saved when leaving the tab, not on every keystroke
+                    if matches!(self.menu_status, MenuStatus::Settings | MenuStatus::Catalogue) {
+                        self.save_settings()
                     } else if self.menu_status == MenuStatus::Main {
-                        self.state_auto_insert = combo_box::State::new(
-                            DataBase::from(&*self.settings.path_to_db.clone())
-                                .get_sheet()
-                                .skips(),
-                        )
+                        task = self.reload_skips()
                     }
                 }
+                if menu_status == MenuStatus::Settings {
+                    self.reservations = DataBase::from(&*self.settings.path_to_db).get_reservations();
+                }
+                if menu_status == MenuStatus::Boxes {
+                    self.boxes_data.reload(&self.settings);
+                }
+                if menu_status == MenuStatus::Status {
+                    self.status_data.reload(&self.settings);
+                }
+                if menu_status == MenuStatus::Statistics {
+                    self.reload_statistics();
+                }
+                if menu_status == MenuStatus::Catalogue {
+                    self.reload_catalogue();
+                }
+                if menu_status == MenuStatus::Plan {
+                    self.reload_plan();
+                }
+                if menu_status == MenuStatus::Contexts {
+                    self.contexts_data.reload(&self.settings);
+                }
+                if menu_status == MenuStatus::Records {
+                    task = Task::batch([task, self.reload_records()]);
+                } else if menu_status == MenuStatus::Integrity {
+                    self.check_integrity();
+                }
                 self.menu_status = menu_status
             }
+            Message::Submit => {
+                if self.settings.shortcuts.is_enter(ShortcutAction::Create) {
+                    task = self.update(Message::Create)
+                }
+            }
             Message::Create => if !self.is_replace || (self.is_replace && self.on_replace) {
+                let replace = self.on_replace;
                 self.on_replace = false;
                 if self.settings.insert_methods_data.insert_methods == InsertMethods::Input {
                     self.is_replace = true;
                 }
-                DataBase::create_record(
-                    &self.settings.path_to_db,
-                    &self.settings.print_settings,
-                    self.quantity.parse().unwrap_or(0),
-                    self.data.clone(),
-                    &self.settings.insert_methods_data,
-                )
+                let created = if self.settings.api.is_client() {
+                    let settings = self.settings.clone();
+                    let quantity = self.quantity.parse().unwrap_or(0);
+                    let data = self.data.clone();
+                    task = Task::perform(
+                        client::run(move || client::create(&settings, quantity, &data, replace)),
+                        Message::Created,
+                    );
+                    vec![]
+                } else {
+                    let _lock = DataBase::lock();
+                    let data_base = DataBase::from(&*self.settings.path_to_db);
+                    self.create_error = data_base
+                        .check_context(
+                            &self.data,
+                            &self.settings.fields,
+                            &self.settings.contexts,
+                            &self.settings.current_language,
+                        )
+                        .and_then(|_| {
+                            data_base.check_references(
+                                &self.data,
+                                &self.settings.fields,
+                                &self.settings.index_format,
+                                &self.settings.current_language,
+                            )
+                        })
+                        .and_then(|_| {
+                            data_base.check_create(
+                                self.quantity.parse().unwrap_or(0),
+                                &self.settings.insert_methods_data,
+                                replace,
+                                &self.settings.index_format,
+                                &self.settings.current_language,
+                            )
+                        })
+                        .err();
+                    if self.create_error.is_some() {
+                        vec![]
+                    } else {
+                        DataBase::create_record(
+                            &self.settings.path_to_db,
+                            self.quantity.parse().unwrap_or(0),
+                            self.data.clone(),
+                            &self.settings.fields,
+                            &self.settings.insert_methods_data,
+                            &self.settings.index_format,
+                            self.settings.audit_recorder(),
+                        )
+                    }
+                };
+                if let Some(&index) = created.last() {
+                    self.last_created = Some(index);
+                    self.ingest_pending_photos();
+                }
             },
-            Message::SetSettings(message_settings) => self.settings.update(message_settings),
-            Message::RebootAutoInsertState => {
-                self.state_auto_insert = combo_box::State::new(
-                    DataBase::from(&*self.settings.path_to_db.clone())
-                        .get_sheet()
-                        .skips(),
-                )
+            Message::SetSettings(message_settings) => {
+                self.settings.update(message_settings);
+                // a new field gets an empty input
+                self.data.resize(self.settings.fields.len() + 1, "".to_string());
             }
+            Message::RebootAutoInsertState => task = self.reload_skips(),
             Message::Update => self.term.input("cargo install --git https://github.com/Andrewkoro105/Archaeological_Assistant.git\nexit\n".to_string()),
             Message::Terminal(iced_term::Event::CommandReceived(_, cmd)) => {
                 match self.term.update(cmd) {
@@ -232,12 +836,291 @@ impl ArchaeologicalAssistant {
             Message::OnReplace(input) => {
                 self.on_replace = input;
             }
+            Message::Records(message) => {
+                if let RecordsMessage::JumpTo(_) = message {
+                    task = self.reload_records();
+                    self.menu_status = MenuStatus::Records;
+                } else {
+                    self.photo_status = None;
+                }
+                self.records_data.update(message, &self.settings);
+            }
+            Message::CheckIntegrity => self.check_integrity(),
+            Message::DataBaseChanged => {
+                task = if self.menu_status == MenuStatus::Records {
+                    Task::batch([self.reload_records(), self.reload_skips()])
+                } else {
+                    self.reload_skips()
+                };
+            }
+            Message::SortDataBase
+            | Message::AddReservation
+            | Message::RemoveReservation(_)
+                if self.settings.api.is_client() =>
+            {
+                self.register_message =
+                    Some(self.tr("The register can only be changed on the server").to_string())
+            }
+            Message::SortDataBase => {
+                let _lock = DataBase::lock();
+                DataBase::sort_by_index(&self.settings.path_to_db, &self.settings.index_format)
+            }
+            Message::SetReservationData(str, id) => {
+                if id == 2 || str.is_empty() || str.parse::<u32>().is_ok() {
+                    self.reservation_data[id] = str
+                }
+            }
+            Message::AddReservation => {
+                if let Some(reservation) = Reservation::from_row(&self.reservation_data) {
+                    // another instance may have reserved or recorded since the list was shown
+                    let _lock = DataBase::lock();
+                    let mut data_base = DataBase::from(&*self.settings.path_to_db);
+                    self.reservations = data_base.get_reservations();
+                    self.register_message = data_base
+                        .check_reservation(
+                            &reservation,
+                            &self.settings.index_format,
+                            &self.settings.current_language,
+                        )
+                        .err();
+                    if self.register_message.is_none() {
+                        self.reservations.push(reservation);
+                        data_base.set_reservations(&self.reservations);
+                        data_base.save(&self.settings.path_to_db);
+                        self.reservation_data = vec!["".to_string(); 3];
+                    }
+                }
+            }
+            Message::AttachPhotos => {
+                let files = FileDialog::new()
+                    .add_filter("images", IMAGE_EXTENSIONS)
+                    .show_open_multiple_file()
+                    .unwrap_or_default();
+                if !files.is_empty() {
+                    task = self.attach_photos(files)
+                }
+            }
+            Message::FileDropped(path) => {
+                if self.menu_status == MenuStatus::Records && is_image(&path) {
+                    task = self.attach_photos(vec![path])
+                }
+            }
+            Message::CameraFiles(files) => {
+                self.pending_photos.extend(files);
+                self.ingest_pending_photos();
+                if self.menu_status == MenuStatus::Records {
+                    task = self.reload_records();
+                }
+            }
+            Message::Scan(message) => task = self.scan_data.update(message, &self.settings),
+            Message::Boxes(message) => self.boxes_data.update(message, &self.settings),
+            Message::Status(message) => self.status_data.update(message, &self.settings),
+            Message::SetStatisticsField(field) => {
+                self.statistics_field = field;
+                self.reload_statistics();
+            }
+            Message::ExportStatistics => {
+                let path = FileDialog::new()
+                    .set_filename(&format!("{}.csv", self.statistics_field))
+                    .add_filter("csv", &["csv"])
+                    .show_save_single_file();
+                if let Ok(Some(path)) = path {
+                    let rows = [vec![self.statistics_field.clone(), "count".to_string()]]
+                        .into_iter()
+                        .chain(
+                            self.statistics
+                                .iter()
+                                .map(|(value, count)| vec![value.clone(), count.to_string()]),
+                        )
+                        .collect::<Vec<_>>();
+                    self.statistics_message = Some(match fs::write(&path, to_csv(&rows)) {
+                        Ok(_) => self.saved(&path),
+                        Err(error) => error.to_string(),
+                    });
+                }
+            }
+            Message::SetReportDate(date) => self.report_date = date,
+            Message::ExportReport(extension) => {
+                let path = FileDialog::new()
+                    .set_filename(&format!("report_{}.{}", self.report_date.trim(), extension))
+                    .add_filter(extension, &[extension])
+                    .show_save_single_file();
+                if let Ok(Some(path)) = path {
+                    let content = self.daily_report().and_then(|markdown| {
+                        if extension == "pdf" {
+                            markdown_to_pdf(
+                                &fill(
+                                    self.tr("Excavation report {date}"),
+                                    &[("date", self.report_date.trim().to_string())],
+                                ),
+                                &markdown,
+                                &self.settings.print_settings.font,
+                            )
+                        } else {
+                            Ok(markdown.into_bytes())
+                        }
+                    });
+                    self.statistics_message = Some(
+                        match content.and_then(|content| {
+                            fs::write(&path, content).map_err(|error| error.to_string())
+                        }) {
+                            Ok(_) => self.saved(&path),
+                            Err(error) => error,
+                        },
+                    );
+                }
+            }
+            Message::SetCatalogueEntry(entry) => {
+                self.settings.catalogue.entry = entry;
+                self.update_catalogue()
+            }
+            Message::SetCatalogueFilter(filter) => {
+                self.settings.catalogue.filter = filter;
+                self.update_catalogue()
+            }
+            Message::SetCatalogueSort(sort_by) => {
+                self.settings.catalogue.sort_by = sort_by;
+                self.update_catalogue()
+            }
+            Message::SetCatalogueTitle(title) => self.settings.catalogue.title = title,
+            Message::ExportCatalogue(format) => {
+                self.save_settings();
+                let path = FileDialog::new()
+                    .set_filename(&format!("catalogue.{}", format.extension()))
+                    .add_filter(&format.to_string(), &[format.extension()])
+                    .show_save_single_file();
+                if let Ok(Some(path)) = path {
+                    self.reload_catalogue();
+                    let content = self.catalogue.clone().and_then(|entries| {
+                        render_catalogue(&self.settings.catalogue.title, &entries, format)
+                    });
+                    self.catalogue_message = Some(
+                        match content.and_then(|content| {
+                            fs::write(&path, content).map_err(|error| error.to_string())
+                        }) {
+                            Ok(_) => self.saved(&path),
+                            Err(error) => error,
+                        },
+                    );
+                }
+            }
+            Message::ExportPlan(format) => {
+                let path = FileDialog::new()
+                    .set_filename(&format!("finds.{}", format.extension()))
+                    .add_filter(&format.to_string(), &[format.extension()])
+                    .show_save_single_file();
+                if let Ok(Some(path)) = path {
+                    self.reload_plan();
+                    let (fields, index_format) = (&self.settings.fields, &self.settings.index_format);
+                    let files = match format {
+                        PlanFormat::GeoJson => vec![(
+                            "geojson",
+                            to_geojson(&self.plan, fields, &self.settings.grid, index_format)
+                                .into_bytes(),
+                        )],
+                        PlanFormat::Shapefile => to_shapefile(&self.plan, fields, index_format),
+                        PlanFormat::Dxf => vec![("dxf", to_dxf(&self.plan, index_format).into_bytes())],
+                    };
+                    self.plan_message = Some(
+                        match files.into_iter().try_for_each(|(extension, content)| {
+                            fs::write(path.with_extension(extension), content)
+                        }) {
+                            Ok(_) => {
+                                let values = [
+                                    ("count", self.plan.len().to_string()),
+                                    ("path", path.to_string_lossy().to_string()),
+                                    ("epsg", self.settings.grid.epsg.to_string()),
+                                ];
+                                if format != PlanFormat::GeoJson && self.settings.grid.epsg != 0 {
+                                    fill(
+                                        self.tr(
+                                            "Saved {count} finds to {path}, \
+                                             set its CRS to EPSG:{epsg} when loading it",
+                                        ),
+                                        &values,
+                                    )
+                                } else {
+                                    fill(self.tr("Saved {count} finds to {path}"), &values)
+                                }
+                            }
+                            Err(error) => error.to_string(),
+                        },
+                    );
+                }
+            }
+            Message::SetCreateMissing(create_missing) => self.create_missing = create_missing,
+            Message::ImportStation => self.import_station(),
+            Message::Contexts(message) => self.contexts_data.update(message, &self.settings),
+            Message::Sync(message) => {
+                // the register to merge is typed in the tab, so it is saved when merging
+                if let SyncMessage::Merge = message {
+                    self.save_settings();
+                }
+                self.sync_data.update(message, &self.settings)
+            }
+            Message::RemoveReservation(id) => {
+                let _lock = DataBase::lock();
+                let mut data_base = DataBase::from(&*self.settings.path_to_db);
+                self.reservations = data_base.get_reservations();
+                if id < self.reservations.len() {
+                    self.reservations.remove(id);
+                }
+                data_base.set_reservations(&self.reservations);
+                data_base.save(&self.settings.path_to_db);
+            }
+            Message::ApiStarted(sender) => {
+                let _ = sender.send(self.settings.clone());
+                self.api_settings = Some(sender);
+                self.api_error = None;
+            }
+            Message::ApiFailed(error) => {
+                self.api_error = Some(fill(
+                    self.tr("Can not start the API on port {port}: {error}"),
+                    &[("port", self.settings.api.port.to_string()), ("error", error)],
+                ))
+            }
+            Message::IndexChecked(index, exists) => {
+                let exists = self.network(exists);
+                let insert_methods_data = &self.settings.insert_methods_data;
+                // an answer to an index that was changed since is ignored
+                match insert_methods_data.insert_methods {
+                    InsertMethods::Input if index == insert_methods_data.input => {
+                        self.is_replace = exists
+                    }
+                    InsertMethods::Fragments if index == insert_methods_data.parent.into() => {
+                        self.is_parent_exist = exists
+                    }
+                    _ => {}
+                }
+            }
+            Message::RecordsLoaded(records) => self.records_data.records = self.network(records),
+            Message::SkipsLoaded(skips) => {
+                let skips = self.network(skips);
+                self.state_auto_insert = combo_box::State::new(SkipOption::all(
+                    skips,
+                    &self.settings.current_language,
+                ));
+            }
+            Message::Created(created) => {
+                let created = self.network(created);
+                if let Some(index) = created
+                    .last()
+                    .and_then(|index| self.settings.index_format.parse(index))
+                {
+                    self.last_created = Some(index);
+                }
+            }
         };
 
+        // the server decides whether start indices are left
+        if self.settings.api.is_client() {
+            self.is_can_start_insert = true;
+            return task;
+        }
         self.is_can_start_insert = if DataBase::from(&*self.settings.path_to_db)
             .get_sheet()
-            .get_start_index()
-            > 1.
+            .get_start_index(&self.settings.index_format)
+            > 1
         {
             true
         } else {
@@ -245,6 +1128,7 @@ impl ArchaeologicalAssistant {
                 self.settings.insert_methods_data.start_end = StartEnd::End;
             }
             false
-        }
+        };
+        task
     }
 }
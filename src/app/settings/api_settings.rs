@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum NetworkMode {
+    /// The database is local, the API is only served to this computer.
+    Standalone,
+    /// The database is local and served to the recorders on the local network.
+    Server,
+    /// Records are read from and created on a server.
+    Client,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ApiSettings {
+    pub enabled: bool,
+    pub input_port: String,
+    pub port: u32,
+    pub mode: NetworkMode,
+    /// Whether a server listens on every network interface. The API has no authentication,
+    /// so anyone on the network can then read and create records.
+    pub accept_network: bool,
+    pub server_address: String,
+}
+
+impl ApiSettings {
+    pub fn is_client(&self) -> bool {
+        self.mode == NetworkMode::Client
+    }
+
+    /// Whether the API is served beyond this computer.
+    pub fn is_lan(&self) -> bool {
+        self.mode == NetworkMode::Server && self.accept_network
+    }
+}
+
+impl Default for ApiSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            input_port: "7878".to_string(),
+            port: 7878,
+            mode: NetworkMode::Standalone,
+            accept_network: false,
+            server_address: "".to_string(),
+        }
+    }
+}
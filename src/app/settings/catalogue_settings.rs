@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CatalogueSettings {
+    /// The text of one entry, `{index}`, `{date}` and `{<field name>}` are replaced.
+    pub entry: String,
+    /// Conditions like `Square=A1; Name~pot`, see `catalogue::Filter`.
+    pub filter: String,
+    /// "Index", "Date" or a field name.
+    pub sort_by: String,
+    pub title: String,
+}
+
+impl Default for CatalogueSettings {
+    fn default() -> Self {
+        Self {
+            entry: "{index}. {Name}. Square {Square}. {info}".to_string(),
+            filter: "".to_string(),
+            sort_by: "Index".to_string(),
+            title: "Catalogue of finds".to_string(),
+        }
+    }
+}
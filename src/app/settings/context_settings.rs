@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContextSettings {
+    /// The field of a find naming the context it was found in.
+    pub find_field: String,
+    /// The fields of a context after its ID, e.g. its type and description.
+    pub fields: Vec<String>,
+    pub input_fields: String,
+}
+
+impl Default for ContextSettings {
+    fn default() -> Self {
+        Self {
+            find_field: "Context".to_string(),
+            fields: vec!["Type".to_string(), "Description".to_string()],
+            input_fields: "Type, Description".to_string(),
+        }
+    }
+}
+
+impl ContextSettings {
+    pub fn set_fields(&mut self, input_fields: String) {
+        self.fields = input_fields
+            .split(',')
+            .map(|field| field.trim().to_string())
+            .filter(|field| !field.is_empty())
+            .collect();
+        self.input_fields = input_fields;
+    }
+}
@@ -0,0 +1,123 @@
+use serde::{Deserialize, Serialize};
+
+/// The excavation grid: squares of `square_size` metres from `origin`, named by letters
+/// and a number, e.g. `B3`. The letters count along x and the numbers along y, or the other way round.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GridSettings {
+    /// The coordinate system of the points, 0 for a local grid.
+    pub epsg: u32,
+    pub input_epsg: String,
+    /// The south-west corner of square `A1`.
+    pub origin: (f64, f64),
+    pub input_origin: (String, String),
+    pub square_size: f64,
+    pub input_square_size: String,
+    /// The field holding the square a find was declared in.
+    pub square_field: String,
+    pub letters_along_x: bool,
+}
+
+impl Default for GridSettings {
+    fn default() -> Self {
+        Self {
+            epsg: 0,
+            input_epsg: "0".to_string(),
+            origin: (0., 0.),
+            input_origin: ("0".to_string(), "0".to_string()),
+            square_size: 2.,
+            input_square_size: "2".to_string(),
+            square_field: "Square".to_string(),
+            letters_along_x: true,
+        }
+    }
+}
+
+impl GridSettings {
+    /// Parses a number written with a decimal point or comma, an empty text is 0.
+    pub fn set_float(input: String, stored_input: &mut String, value: &mut f64) {
+        if input.is_empty() || input == "-" {
+            *stored_input = input;
+            *value = 0.;
+        } else if let Ok(new_value) = input.replace(',', ".").parse::<f64>() {
+            *stored_input = input;
+            *value = new_value;
+        }
+    }
+
+    /// The positions of the letters and of the number of a square, from 0,
+    /// e.g. `B3` is `(1, 2)` and `AA1` is `(26, 0)`.
+    fn square_position(name: &str) -> Option<(u32, u32)> {
+        let name = name.trim().to_uppercase();
+        let letters = name
+            .chars()
+            .take_while(|char| char.is_ascii_alphabetic())
+            .collect::<String>();
+        let number = name[letters.len()..].trim().parse::<u32>().ok()?;
+        if letters.is_empty() || number == 0 {
+            return None;
+        }
+        // too many letters overflow, such a name is not of the grid
+        let column = letters.chars().try_fold(0_u32, |column, letter| {
+            column.checked_mul(26)?.checked_add(letter as u32 - 'A' as u32 + 1)
+        })?;
+        Some((column - 1, number - 1))
+    }
+
+    /// `A`..`Z`, then `AA`, `AB`...
+    fn letters(mut position: u32) -> String {
+        let mut letters = vec![];
+        loop {
+            letters.push((b'A' + (position % 26) as u8) as char);
+            if position < 26 {
+                break;
+            }
+            position = position / 26 - 1;
+        }
+        letters.into_iter().rev().collect()
+    }
+
+    /// The corners `(x min, y min, x max, y max)` of a square, `None` if the name is not of the grid.
+    pub fn square_bounds(&self, name: &str) -> Option<(f64, f64, f64, f64)> {
+        let (letters, number) = Self::square_position(name)?;
+        let (column, row) = if self.letters_along_x {
+            (letters, number)
+        } else {
+            (number, letters)
+        };
+        let x = self.origin.0 + column as f64 * self.square_size;
+        let y = self.origin.1 + row as f64 * self.square_size;
+        Some((x, y, x + self.square_size, y + self.square_size))
+    }
+
+    /// The name of the square a point is in, `None` outside of the grid.
+    pub fn square_of(&self, x: f64, y: f64) -> Option<String> {
+        if self.square_size <= 0. {
+            return None;
+        }
+        let column = ((x - self.origin.0) / self.square_size).floor();
+        let row = ((y - self.origin.1) / self.square_size).floor();
+        if column < 0. || row < 0. {
+            return None;
+        }
+        let (letters, number) = if self.letters_along_x {
+            (column as u32, row as u32)
+        } else {
+            (row as u32, column as u32)
+        };
+        Some(format!("{}{}", Self::letters(letters), number + 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GridSettings;
+
+    #[test]
+    fn square_position() {
+        assert_eq!(Some((1, 2)), GridSettings::square_position("B3"));
+        assert_eq!(Some((26, 0)), GridSettings::square_position("aa1"));
+        assert_eq!(None, GridSettings::square_position("Extension1"));
+        assert_eq!(None, GridSettings::square_position("A0"));
+    }
+}
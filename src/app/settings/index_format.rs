@@ -0,0 +1,161 @@
+use crate::app::data_base::index::Index;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IndexFormat {
+    pub prefix_template: String,
+    pub site: String,
+    pub year: String,
+    pub trench: String,
+    pub input_padding: String,
+    pub padding: u32,
+    pub sub_separator: String,
+}
+
+impl IndexFormat {
+    /// Prefix template with `{site}`, `{year}` and `{trench}` tokens replaced.
+    pub fn prefix(&self) -> String {
+        self.prefix_template
+            .replace("{site}", &self.site)
+            .replace("{year}", &self.year)
+            .replace("{trench}", &self.trench)
+    }
+
+    pub fn format(&self, index: Index) -> String {
+        let number = format!(
+            "{}{:0width$}",
+            self.prefix(),
+            index.number,
+            width = self.padding as usize
+        );
+        match index.sub {
+            Some(sub) => format!("{}{}{}", number, self.sub_separator, sub),
+            None => number,
+        }
+    }
+
+    /// Whether the index can be written as a plain number cell without losing its format.
+    pub fn is_numeric(&self, index: Index) -> bool {
+        self.prefix().is_empty() && self.padding <= 1 && index.sub.is_none()
+    }
+
+    /// Parses an index cell. The prefix is optional so that plain numbers are still understood.
+    pub fn parse(&self, value: &str) -> Option<Index> {
+        let value = value.trim();
+        let prefix = self.prefix();
+        let value = value.strip_prefix(prefix.as_str()).unwrap_or(value);
+        let (number, sub) = if self.sub_separator.is_empty() {
+            (value, "")
+        } else {
+            value
+                .split_once(self.sub_separator.as_str())
+                .unwrap_or((value, ""))
+        };
+
+        Some(Index::new(
+            Self::parse_number(number)?,
+            if sub.is_empty() {
+                None
+            } else {
+                Some(Self::parse_number(sub)?)
+            },
+        ))
+    }
+
+    /// Parses the index of a parent record, fragments can not have fragments of their own.
+    pub fn parse_parent(&self, value: &str) -> Option<u32> {
+        self.parse(value)
+            .filter(|index| index.sub.is_none())
+            .map(|index| index.number)
+    }
+
+    /// Finds the index in scanned text, such as the content of a QR code on a find tag.
+    pub fn find(&self, text: &str) -> Option<Index> {
+        self.parse(text).or_else(|| {
+            text.split(|char: char| char.is_whitespace() || ",;|".contains(char))
+                .find_map(|token| self.parse(token))
+        })
+    }
+
+    fn parse_number(value: &str) -> Option<u32> {
+        value.parse::<u32>().ok().or_else(|| {
+            value
+                .parse::<f64>()
+                .ok()
+                .filter(|num| num.fract() == 0. && *num >= 0.)
+                .map(|num| num as u32)
+        })
+    }
+}
+
+impl Default for IndexFormat {
+    fn default() -> Self {
+        Self {
+            prefix_template: "".to_string(),
+            site: "".to_string(),
+            year: "".to_string(),
+            trench: "".to_string(),
+            input_padding: "0".to_string(),
+            padding: 0,
+            sub_separator: ".".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IndexFormat;
+    use crate::app::data_base::index::Index;
+
+    fn kr_format() -> IndexFormat {
+        IndexFormat {
+            prefix_template: "{site}{year}-T{trench}-".to_string(),
+            site: "KR".to_string(),
+            year: "24".to_string(),
+            trench: "3".to_string(),
+            input_padding: "4".to_string(),
+            padding: 4,
+            sub_separator: ".".to_string(),
+        }
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!("157", IndexFormat::default().format(Index::new(157, None)));
+        assert_eq!("157.2", IndexFormat::default().format(Index::new(157, Some(2))));
+        assert_eq!("KR24-T3-0157", kr_format().format(Index::new(157, None)));
+        assert_eq!("KR24-T3-0157.2", kr_format().format(Index::new(157, Some(2))));
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(Some(Index::new(157, None)), IndexFormat::default().parse("157"));
+        assert_eq!(Some(Index::new(1, None)), IndexFormat::default().parse("1."));
+        assert_eq!(Some(Index::new(157, Some(2))), IndexFormat::default().parse("157.2"));
+        assert_eq!(None, IndexFormat::default().parse("index"));
+        assert_eq!(None, IndexFormat::default().parse(""));
+        assert_eq!(Some(Index::new(157, None)), kr_format().parse("KR24-T3-0157"));
+        assert_eq!(Some(Index::new(157, Some(2))), kr_format().parse("KR24-T3-0157.2"));
+        assert_eq!(Some(Index::new(157, None)), kr_format().parse("157"));
+        assert_eq!(None, kr_format().parse("KR23-T3-0157"));
+    }
+
+    #[test]
+    fn parse_parent() {
+        assert_eq!(Some(157), kr_format().parse_parent("KR24-T3-0157"));
+        assert_eq!(Some(157), kr_format().parse_parent("157"));
+        assert_eq!(None, kr_format().parse_parent("KR24-T3-0157.2"));
+        assert_eq!(None, kr_format().parse_parent("KR23-T3-0157"));
+    }
+
+    #[test]
+    fn find() {
+        assert_eq!(Some(Index::new(157, None)), kr_format().find(" KR24-T3-0157\n"));
+        assert_eq!(
+            Some(Index::new(157, Some(2))),
+            kr_format().find("KR24-T3-0157.2;bead;A3")
+        );
+        assert_eq!(None, kr_format().find("bead A"));
+    }
+}
@@ -1,12 +1,15 @@
 use iced::widget::combo_box;
 use serde::{Deserialize, Serialize};
 use crate::app::data_base::DataBase;
+use crate::app::data_base::index::Index;
+use crate::app::settings::index_format::IndexFormat;
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub enum InsertMethods {
     StartEnd,
     Input,
     AutoInsert,
+    Fragments,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
@@ -14,6 +17,8 @@ pub enum InsertMethodsMessage {
     StartEnd(StartEnd),
     Input(String),
     AutoInsert(u32),
+    Parent(String),
+    SortedInsert(bool),
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
@@ -22,45 +27,80 @@ pub enum StartEnd {
     End,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct InputNumberForInsertMethodsData {
     pub input: String,
     pub auto_insert: String,
+    pub parent: String,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct InsertMethodsData {
     pub input_number_for_insert_methods_data: InputNumberForInsertMethodsData,
     pub insert_methods: InsertMethods,
     pub start_end: StartEnd,
-    pub input: u32,
+    pub input: Index,
     pub auto_insert: u32,
+    pub parent: u32,
+    pub sorted_insert: bool,
 }
 
 impl InsertMethodsData {
-    fn set_option_number_settings(str: &String, input: &mut String, num: &mut u32) {
+    fn set_option_parent_settings(
+        str: &String,
+        input: &mut String,
+        num: &mut u32,
+        index_format: &IndexFormat,
+    ) {
         if str.is_empty() {
             *input = "".to_string();
             *num = 1;
-        } else if let Ok(new_num) = str.parse::<u32>() {
+        } else if let Some(new_num) = index_format.parse_parent(str) {
             *input = str.clone();
             *num = new_num;
         }
     }
 
-    pub fn update(&mut self, message: InsertMethodsMessage) {
+    fn set_option_index_settings(
+        str: &String,
+        input: &mut String,
+        index: &mut Index,
+        index_format: &IndexFormat,
+    ) {
+        if str.is_empty() {
+            *input = "".to_string();
+            *index = Index::from(1);
+        } else if let Some(new_index) = index_format.parse(str) {
+            *input = str.clone();
+            *index = new_index;
+        }
+    }
+
+    pub fn update(&mut self, message: InsertMethodsMessage, index_format: &IndexFormat) {
         match message {
             InsertMethodsMessage::StartEnd(start_end) => {
                 self.start_end = start_end;
             }
-            InsertMethodsMessage::Input(input) => Self::set_option_number_settings(
+            InsertMethodsMessage::Input(input) => Self::set_option_index_settings(
                 &input,
                 &mut self.input_number_for_insert_methods_data.input,
                 &mut self.input,
+                index_format,
             ),
             InsertMethodsMessage::AutoInsert(auto_insert) => {
                 self.auto_insert = auto_insert 
             },
+            InsertMethodsMessage::Parent(parent) => Self::set_option_parent_settings(
+                &parent,
+                &mut self.input_number_for_insert_methods_data.parent,
+                &mut self.parent,
+                index_format,
+            ),
+            InsertMethodsMessage::SortedInsert(sorted_insert) => {
+                self.sorted_insert = sorted_insert
+            }
         }
     }
 }
@@ -69,7 +109,8 @@ impl Default for InputNumberForInsertMethodsData {
     fn default() -> Self {
         Self {
             input: "1".to_string(),
-            auto_insert: "".to_string()
+            auto_insert: "".to_string(),
+            parent: "1".to_string(),
         }
     }
 }
@@ -80,8 +121,10 @@ impl Default for InsertMethodsData {
             input_number_for_insert_methods_data: Default::default(),
             insert_methods: InsertMethods::StartEnd,
             start_end: StartEnd::End,
-            input: 1,
+            input: Index::from(1),
             auto_insert: 0,
+            parent: 1,
+            sorted_insert: false,
         }
     }
 }
@@ -1,11 +1,30 @@
+pub mod api_settings;
+pub mod catalogue_settings;
+pub mod context_settings;
+pub mod grid_settings;
+pub mod index_format;
 pub mod insert_methods;
 pub mod print_settings;
+pub mod report_settings;
+pub mod shortcut_settings;
+pub mod status_settings;
 
+use crate::app::data_base::audit::MAX_FIELDS;
+use crate::app::i18n::Language;
+use crate::app::settings::api_settings::{ApiSettings, NetworkMode};
+use crate::app::settings::catalogue_settings::CatalogueSettings;
+use crate::app::settings::context_settings::ContextSettings;
+use crate::app::settings::grid_settings::GridSettings;
+use crate::app::settings::index_format::IndexFormat;
 use crate::app::settings::insert_methods::InsertMethodsData;
+use crate::app::settings::report_settings::ReportSettings;
+use crate::app::settings::shortcut_settings::{ShortcutAction, ShortcutSettings};
+use crate::app::settings::status_settings::{Status, StatusSettings};
 use crate::app::theme::Theme;
 use crate::app::{theme, Message};
 use ciborium::from_reader;
 use ciborium::into_writer;
+use iced_aw::date_picker;
 use native_dialog::FileDialog;
 use print_settings::PrintSettings;
 use serde::{Deserialize, Serialize};
@@ -19,12 +38,52 @@ pub enum MessageSettings {
     SelectFont(Vec<(&'static str, &'static [&'static str])>),
     SetPathToDb(String),
     SetPathToFont(String),
+    SelectSyncDb(Vec<(&'static str, &'static [&'static str])>),
+    SetPathToSync(String),
+    SetPathToMedia(String),
+    SetCameraFolder(String),
+    SetCameraIngest(bool),
     SetSize(String, Axis),
     SetSizeImage(String, Axis),
     SetPositionImage(String, Axis),
     SetHeightText(String),
     SetTextSize(String),
     SetTheme(Theme),
+    SetIndexPrefix(String),
+    SetIndexSite(String),
+    SetIndexYear(String),
+    SetIndexTrench(String),
+    SetIndexPadding(String),
+    SetIndexSubSeparator(String),
+    SetFieldRequired(usize, bool),
+    SetApiEnabled(bool),
+    SetApiPort(String),
+    SetNetworkMode(NetworkMode),
+    SetAcceptNetwork(bool),
+    SetServerAddress(String),
+    SetRecorder(String),
+    SetAudit(bool),
+    SetNewField(String),
+    AddField(FieldType),
+    SetStatusField(String),
+    SetStatusGroupField(String),
+    SetStatusNext(usize, String),
+    SetNewStatus(String),
+    AddStatus,
+    RemoveStatus(usize),
+    SetReportGroupFields(String),
+    SelectReportTemplate(Vec<(&'static str, &'static [&'static str])>),
+    SetReportTemplate(String),
+    SetGridEpsg(String),
+    SetGridOrigin(String, Axis),
+    SetGridSquareSize(String),
+    SetGridSquareField(String),
+    SetGridLettersAlongX(bool),
+    SetContextFindField(String),
+    SetContextFields(String),
+    SetLanguage(Language),
+    SetFieldTranslation(usize, String),
+    SetShortcut(ShortcutAction, String),
 } 
 
 #[derive(Debug, Clone)]
@@ -33,30 +92,92 @@ pub enum Axis {
     Y,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub enum FieldType {
     Text,
+    /// `x; y; z` of a find measured with the total station.
+    Coordinates,
+    /// Indices of other finds, e.g. the sherds a sherd joins, linked both ways.
+    Reference,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Field {
     pub field_type: FieldType,
+    /// The name in the register, the CLI and the API.
     pub name: String,
+    #[serde(default)]
+    pub required: bool,
+    /// The names shown in other languages, by language code.
+    #[serde(default)]
+    pub translations: Vec<(String, String)>,
 }
 
-#[derive(Serialize, Deserialize)]
+/// Settings missing from the file, e.g. the ones added since it was saved, take their defaults.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Settings {
     pub current_language: String,
     pub fields: Vec<Field>,
     pub path_to_db: Box<Path>,
+    /// The register of another instance to merge into this one.
+    pub path_to_sync: Box<Path>,
+    /// The folder photos of the records are copied into.
+    pub path_to_media: Box<Path>,
+    /// New images in this folder are attached to the current record.
+    pub camera_folder: Box<Path>,
+    pub camera_ingest: bool,
     pub print_settings: PrintSettings,
     pub theme: Theme,
     pub insert_methods_data: InsertMethodsData,
+    pub index_format: IndexFormat,
+    pub api: ApiSettings,
+    /// The name of the person using this instance, written into the audit columns.
+    pub recorder: String,
+    pub audit: bool,
+    pub input_new_field: String,
+    pub status: StatusSettings,
+    pub report: ReportSettings,
+    pub catalogue: CatalogueSettings,
+    pub grid: GridSettings,
+    pub contexts: ContextSettings,
+    pub shortcuts: ShortcutSettings,
 }
 
 impl Field {
-    pub fn new(field_type: FieldType, name: String) -> Self {
-        Self { field_type, name }
+    pub fn new(field_type: FieldType, name: String, required: bool) -> Self {
+        Self {
+            field_type,
+            name,
+            required,
+            translations: vec![],
+        }
+    }
+
+    pub fn translated(mut self, language: &str, name: &str) -> Self {
+        self.set_translation(language, name.to_string());
+        self
+    }
+
+    pub fn translation(&self, language: &str) -> Option<&str> {
+        self.translations
+            .iter()
+            .find(|(code, _)| code == language)
+            .map(|(_, name)| name.as_str())
+    }
+
+    /// The name shown in the interface, the name in the register if it is not translated.
+    pub fn display_name(&self, language: &str) -> &str {
+        self.translation(language)
+            .filter(|name| !name.trim().is_empty())
+            .unwrap_or(&self.name)
+    }
+
+    pub fn set_translation(&mut self, language: &str, name: String) {
+        self.translations.retain(|(code, _)| code != language);
+        if !name.is_empty() {
+            self.translations.push((language.to_string(), name));
+        }
     }
 }
 
@@ -65,19 +186,40 @@ impl Default for Settings {
         Self {
             current_language: "ru".to_string(),
             fields: vec![
-                Field::new(FieldType::Text, "Name".to_string()),
-                Field::new(FieldType::Text, "Square".to_string()),
-                Field::new(FieldType::Text, "Tomb".to_string()),
-                Field::new(FieldType::Text, "info".to_string()),
+                Field::new(FieldType::Text, "Name".to_string(), true).translated("ru", "Название"),
+                Field::new(FieldType::Text, "Square".to_string(), true).translated("ru", "Квадрат"),
+                Field::new(FieldType::Text, "Tomb".to_string(), false)
+                    .translated("ru", "Погребение"),
+                Field::new(FieldType::Text, "info".to_string(), false)
+                    .translated("ru", "Примечание"),
             ],
             path_to_db: Path::new(&format!(
                 "{}/Archaeological_assistant/Archaeological_assistant_db.xlsx",
                 env::var("HOME").unwrap()
             ))
             .into(),
+            path_to_sync: Path::new("").into(),
+            path_to_media: Path::new(&format!(
+                "{}/Archaeological_assistant/media",
+                env::var("HOME").unwrap()
+            ))
+            .into(),
+            camera_folder: Path::new("").into(),
+            camera_ingest: false,
             print_settings: PrintSettings::default(),
             theme: Theme::Dark,
             insert_methods_data: InsertMethodsData::default(),
+            index_format: IndexFormat::default(),
+            api: ApiSettings::default(),
+            recorder: "".to_string(),
+            audit: false,
+            input_new_field: "".to_string(),
+            status: StatusSettings::default(),
+            report: ReportSettings::default(),
+            catalogue: CatalogueSettings::default(),
+            grid: GridSettings::default(),
+            contexts: ContextSettings::default(),
+            shortcuts: ShortcutSettings::default(),
         }
     }
 }
@@ -139,6 +281,19 @@ impl Settings {
             MessageSettings::SetPathToFont(path_str) => {
                 self.print_settings.font = Box::from(Path::new(&path_str))
             }
+            MessageSettings::SelectSyncDb(file_types) => {
+                self.select_file(MessageSettings::SetPathToSync, file_types)
+            }
+            MessageSettings::SetPathToSync(path_str) => {
+                self.path_to_sync = Box::from(Path::new(&path_str))
+            }
+            MessageSettings::SetPathToMedia(path_str) => {
+                self.path_to_media = Box::from(Path::new(&path_str))
+            }
+            MessageSettings::SetCameraFolder(path_str) => {
+                self.camera_folder = Box::from(Path::new(&path_str))
+            }
+            MessageSettings::SetCameraIngest(camera_ingest) => self.camera_ingest = camera_ingest,
             MessageSettings::SetSize(size, axis) => {
                 Self::set_number_with_coordinate_settings(
                     &size,
@@ -180,7 +335,146 @@ impl Settings {
             MessageSettings::SetTheme(theme) => {
                 self.theme = theme
             }
+            MessageSettings::SetIndexPrefix(prefix) => self.index_format.prefix_template = prefix,
+            MessageSettings::SetIndexSite(site) => self.index_format.site = site,
+            MessageSettings::SetIndexYear(year) => self.index_format.year = year,
+            MessageSettings::SetIndexTrench(trench) => self.index_format.trench = trench,
+            MessageSettings::SetIndexPadding(padding) => {
+                Self::set_number_settings(
+                    &padding,
+                    &mut self.index_format.input_padding,
+                    &mut self.index_format.padding,
+                )
+            }
+            MessageSettings::SetIndexSubSeparator(separator) => {
+                self.index_format.sub_separator = separator
+            }
+            MessageSettings::SetFieldRequired(id, required) => {
+                if let Some(field) = self.fields.get_mut(id) {
+                    field.required = required
+                }
+            }
+            MessageSettings::SetFieldTranslation(id, name) => {
+                if let Some(field) = self.fields.get_mut(id) {
+                    field.set_translation(&self.current_language, name)
+                }
+            }
+            MessageSettings::SetShortcut(action, binding) => {
+                self.shortcuts.set_binding(action, binding)
+            }
+            MessageSettings::SetLanguage(language) => {
+                self.current_language = language.code().to_string()
+            }
+            MessageSettings::SetApiEnabled(enabled) => self.api.enabled = enabled,
+            MessageSettings::SetApiPort(port) => {
+                Self::set_number_settings(&port, &mut self.api.input_port, &mut self.api.port)
+            }
+            MessageSettings::SetNetworkMode(mode) => self.api.mode = mode,
+            MessageSettings::SetAcceptNetwork(accept_network) => {
+                self.api.accept_network = accept_network
+            }
+            MessageSettings::SetServerAddress(address) => self.api.server_address = address,
+            MessageSettings::SetRecorder(recorder) => self.recorder = recorder,
+            MessageSettings::SetAudit(audit) => self.audit = audit,
+            MessageSettings::SetNewField(name) => self.input_new_field = name,
+            MessageSettings::AddField(field_type) => {
+                let name = self.input_new_field.trim().to_string();
+                if !name.is_empty()
+                    && self.fields.len() < MAX_FIELDS
+                    && !self.fields.iter().any(|field| field.name == name)
+                {
+                    self.fields.push(Field::new(field_type, name, false));
+                    self.input_new_field = "".to_string();
+                }
+            }
+            MessageSettings::SetStatusField(field) => self.status.field = field,
+            MessageSettings::SetStatusGroupField(field) => self.status.group_field = field,
+            MessageSettings::SetStatusNext(id, next) => {
+                if let Some(status) = self.status.statuses.get_mut(id) {
+                    status.set_next(next)
+                }
+            }
+            MessageSettings::SetNewStatus(name) => self.status.input_new_status = name,
+            MessageSettings::AddStatus => {
+                let name = self.status.input_new_status.trim().to_string();
+                if !name.is_empty() && !self.status.names().contains(&name) {
+                    self.status.statuses.push(Status::new(&name, &[]));
+                    self.status.input_new_status = "".to_string();
+                }
+            }
+            MessageSettings::RemoveStatus(id) => {
+                if id < self.status.statuses.len() {
+                    self.status.statuses.remove(id);
+                }
+            }
+            MessageSettings::SetReportGroupFields(fields) => self.report.set_group_fields(fields),
+            MessageSettings::SelectReportTemplate(file_types) => {
+                self.select_file(MessageSettings::SetReportTemplate, file_types)
+            }
+            MessageSettings::SetReportTemplate(path_str) => {
+                self.report.template = Box::from(Path::new(&path_str))
+            }
+            MessageSettings::SetGridEpsg(epsg) => {
+                Self::set_number_settings(&epsg, &mut self.grid.input_epsg, &mut self.grid.epsg)
+            }
+            MessageSettings::SetGridOrigin(origin, axis) => match axis {
+                Axis::X => GridSettings::set_float(
+                    origin,
+                    &mut self.grid.input_origin.0,
+                    &mut self.grid.origin.0,
+                ),
+                Axis::Y => GridSettings::set_float(
+                    origin,
+                    &mut self.grid.input_origin.1,
+                    &mut self.grid.origin.1,
+                ),
+            },
+            MessageSettings::SetGridSquareSize(size) => GridSettings::set_float(
+                size,
+                &mut self.grid.input_square_size,
+                &mut self.grid.square_size,
+            ),
+            MessageSettings::SetGridSquareField(field) => self.grid.square_field = field,
+            MessageSettings::SetGridLettersAlongX(letters_along_x) => {
+                self.grid.letters_along_x = letters_along_x
+            }
+            MessageSettings::SetContextFindField(field) => self.contexts.find_field = field,
+            MessageSettings::SetContextFields(fields) => self.contexts.set_fields(fields),
+        }
+    }
+
+    /// The recorder to write into the audit columns, if they are enabled.
+    pub fn audit_recorder(&self) -> Option<&str> {
+        self.audit.then_some(self.recorder.as_str())
+    }
+
+    /// The position of a field in the record data, which starts with the date.
+    pub fn data_id(&self, name: &str) -> Option<usize> {
+        self.fields
+            .iter()
+            .position(|field| field.name == name)
+            .map(|id| id + 1)
+    }
+
+    /// Builds the data of a new record (the date followed by the fields) from field names.
+    pub fn new_record_data<'a>(
+        &self,
+        date: Option<String>,
+        fields: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> Result<Vec<String>, String> {
+        let mut data = vec![date.unwrap_or_else(|| date_picker::Date::today().to_string())];
+        data.extend(vec!["".to_string(); self.fields.len()]);
+
+        for (name, value) in fields {
+            let id = self
+                .fields
+                .iter()
+                .position(|field| field.name.eq_ignore_ascii_case(name))
+                .ok_or(format!("Unknown field \"{}\"", name))?;
+            data[id + 1] = value.to_string();
         }
+
+        Ok(data)
     }
 
     pub fn load() -> Self {
@@ -211,4 +505,50 @@ impl Settings {
         )
         .expect("can`t write settings.cbor");
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FieldType, Settings};
+    use ciborium::{from_reader, into_writer};
+    use serde::Serialize;
+    use std::path::Path;
+
+    #[test]
+    fn old_settings() {
+        /// A field of an older version.
+        #[derive(Serialize)]
+        struct OldField {
+            field_type: FieldType,
+            name: String,
+        }
+
+        /// The settings of an older version.
+        #[derive(Serialize)]
+        struct OldSettings {
+            current_language: String,
+            fields: Vec<OldField>,
+            path_to_db: Box<Path>,
+        }
+
+        let mut file = vec![];
+        into_writer(
+            &OldSettings {
+                current_language: "en".to_string(),
+                fields: vec![OldField {
+                    field_type: FieldType::Text,
+                    name: "material".to_string(),
+                }],
+                path_to_db: Path::new("/finds/register.xlsx").into(),
+            },
+            &mut file,
+        )
+        .unwrap();
+        let settings: Settings = from_reader(file.as_slice()).unwrap();
+        assert_eq!("en", settings.current_language);
+        assert_eq!(Path::new("/finds/register.xlsx"), &*settings.path_to_db);
+        assert_eq!("material", settings.fields[0].name);
+        assert!(!settings.fields[0].required);
+        assert_eq!("Enter", settings.shortcuts.create);
+    }
+}
@@ -2,7 +2,8 @@ use serde::{Deserialize, Serialize};
 use std::env;
 use std::path::Path;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct InputNumberForPrintSettings {
     pub height_text: String,
     pub pos_image: (String, String),
@@ -11,7 +12,8 @@ pub struct InputNumberForPrintSettings {
     pub text_size: String,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct PrintSettings {
     pub font: Box<Path>,
     pub input_number: InputNumberForPrintSettings,
@@ -40,6 +42,38 @@ impl Default for PrintSettings {
     }
 }
 
+impl PrintSettings {
+    /// Renders a label of `size` millimetres with one line of text per entry, as SVG.
+    pub fn label_svg(&self, lines: &[String]) -> String {
+        // the text size is in points
+        let font_size = self.text_size as f64 * 0.3528;
+        let font = self
+            .font
+            .file_stem()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let text = lines
+            .iter()
+            .enumerate()
+            .map(|(i, line)| {
+                format!(
+                    "  <text x=\"{}\" y=\"{:.2}\">{}</text>\n",
+                    font_size / 2.,
+                    self.height_text as f64 + font_size * (i + 1) as f64,
+                    line.replace('&', "&amp;")
+                        .replace('<', "&lt;")
+                        .replace('>', "&gt;")
+                )
+            })
+            .collect::<String>();
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}mm\" height=\"{h}mm\" viewBox=\"0 0 {w} {h}\" font-family=\"{font}\" font-size=\"{font_size:.2}\">\n  <rect x=\"0\" y=\"0\" width=\"{w}\" height=\"{h}\" fill=\"none\" stroke=\"black\" stroke-width=\"0.3\"/>\n{text}</svg>\n",
+            w = self.size.0,
+            h = self.size.1,
+        )
+    }
+}
+
 impl Default for InputNumberForPrintSettings {
     fn default() -> Self {
         Self {
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReportSettings {
+    /// The fields the finds of the day are grouped by, e.g. square and tomb.
+    pub group_fields: Vec<String>,
+    pub input_group_fields: String,
+    /// A Markdown file with `{date}`, `{total}`, `{finds}`, `{gaps}` and `{replacements}`,
+    /// the built-in template is used when it is empty.
+    pub template: Box<Path>,
+}
+
+impl Default for ReportSettings {
+    fn default() -> Self {
+        Self {
+            group_fields: vec!["Square".to_string(), "Tomb".to_string()],
+            input_group_fields: "Square, Tomb".to_string(),
+            template: Path::new("").into(),
+        }
+    }
+}
+
+impl ReportSettings {
+    pub fn set_group_fields(&mut self, input_group_fields: String) {
+        self.group_fields = input_group_fields
+            .split(',')
+            .map(|field| field.trim().to_string())
+            .filter(|field| !field.is_empty())
+            .collect();
+        self.input_group_fields = input_group_fields;
+    }
+}
@@ -0,0 +1,273 @@
+use iced::keyboard::key::Named;
+use iced::keyboard::{Key, Modifiers};
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ShortcutAction {
+    Create,
+    StartEnd,
+    Input,
+    AutoInsert,
+    Fragments,
+    Today,
+    QuantityUp,
+    QuantityDown,
+}
+
+/// The keys of the main menu, written as `Ctrl+D`, `Alt+Shift+F2` or `PageUp`.
+/// `Ctrl` is `Cmd` on macOS, an empty binding turns the shortcut off.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShortcutSettings {
+    pub create: String,
+    pub start_end: String,
+    pub input: String,
+    pub auto_insert: String,
+    pub fragments: String,
+    pub today: String,
+    pub quantity_up: String,
+    pub quantity_down: String,
+}
+
+/// A parsed binding.
+struct Shortcut {
+    ctrl: bool,
+    alt: bool,
+    shift: bool,
+    key: Key,
+}
+
+const NAMED_KEYS: [(&str, Named); 20] = [
+    ("Enter", Named::Enter),
+    ("Tab", Named::Tab),
+    ("Escape", Named::Escape),
+    ("Space", Named::Space),
+    ("PageUp", Named::PageUp),
+    ("PageDown", Named::PageDown),
+    ("Home", Named::Home),
+    ("End", Named::End),
+    ("Insert", Named::Insert),
+    ("Delete", Named::Delete),
+    ("F1", Named::F1),
+    ("F2", Named::F2),
+    ("F3", Named::F3),
+    ("F4", Named::F4),
+    ("F5", Named::F5),
+    ("F6", Named::F6),
+    ("F7", Named::F7),
+    ("F8", Named::F8),
+    ("F9", Named::F9),
+    ("F10", Named::F10),
+];
+
+/// Keys an input does not take, so their shortcuts work while typing.
+const UNTYPED_KEYS: [Named; 14] = [
+    Named::Escape,
+    Named::PageUp,
+    Named::PageDown,
+    Named::Insert,
+    Named::F1,
+    Named::F2,
+    Named::F3,
+    Named::F4,
+    Named::F5,
+    Named::F6,
+    Named::F7,
+    Named::F8,
+    Named::F9,
+    Named::F10,
+];
+
+impl Shortcut {
+    fn parse(binding: &str) -> Option<Self> {
+        let mut shortcut = Shortcut {
+            ctrl: false,
+            alt: false,
+            shift: false,
+            key: Key::Unidentified,
+        };
+        let (modifiers, key) = binding.trim().rsplit_once('+').unwrap_or(("", binding.trim()));
+        for modifier in modifiers.split('+').filter(|modifier| !modifier.trim().is_empty()) {
+            match modifier.trim().to_lowercase().as_str() {
+                "ctrl" | "cmd" => shortcut.ctrl = true,
+                "alt" => shortcut.alt = true,
+                "shift" => shortcut.shift = true,
+                _ => return None,
+            }
+        }
+        let key = key.trim();
+        shortcut.key = match NAMED_KEYS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(key))
+        {
+            Some((_, named)) => Key::Named(*named),
+            None if key.chars().count() == 1 => Key::Character(key.to_lowercase().into()),
+            None => return None,
+        };
+        Some(shortcut)
+    }
+
+    fn matches(&self, key: &Key, modifiers: Modifiers) -> bool {
+        let key = match key {
+            Key::Character(character) => Key::Character(character.to_lowercase().into()),
+            key => key.clone(),
+        };
+        self.key == key
+            && self.ctrl == modifiers.command()
+            && self.alt == modifiers.alt()
+            && self.shift == modifiers.shift()
+    }
+
+    /// Whether the shortcut can't be typed into an input.
+    fn is_untyped(&self) -> bool {
+        self.ctrl
+            || self.alt
+            || matches!(&self.key, Key::Named(named) if UNTYPED_KEYS.contains(named))
+    }
+}
+
+impl Default for ShortcutSettings {
+    fn default() -> Self {
+        Self {
+            create: "Enter".to_string(),
+            start_end: "Ctrl+1".to_string(),
+            input: "Ctrl+2".to_string(),
+            auto_insert: "Ctrl+3".to_string(),
+            fragments: "Ctrl+4".to_string(),
+            today: "Ctrl+D".to_string(),
+            quantity_up: "PageUp".to_string(),
+            quantity_down: "PageDown".to_string(),
+        }
+    }
+}
+
+impl ShortcutAction {
+    pub const ALL: [ShortcutAction; 8] = [
+        ShortcutAction::Create,
+        ShortcutAction::StartEnd,
+        ShortcutAction::Input,
+        ShortcutAction::AutoInsert,
+        ShortcutAction::Fragments,
+        ShortcutAction::Today,
+        ShortcutAction::QuantityUp,
+        ShortcutAction::QuantityDown,
+    ];
+}
+
+impl ShortcutSettings {
+    pub fn binding(&self, action: ShortcutAction) -> &String {
+        match action {
+            ShortcutAction::Create => &self.create,
+            ShortcutAction::StartEnd => &self.start_end,
+            ShortcutAction::Input => &self.input,
+            ShortcutAction::AutoInsert => &self.auto_insert,
+            ShortcutAction::Fragments => &self.fragments,
+            ShortcutAction::Today => &self.today,
+            ShortcutAction::QuantityUp => &self.quantity_up,
+            ShortcutAction::QuantityDown => &self.quantity_down,
+        }
+    }
+
+    pub fn set_binding(&mut self, action: ShortcutAction, binding: String) {
+        *match action {
+            ShortcutAction::Create => &mut self.create,
+            ShortcutAction::StartEnd => &mut self.start_end,
+            ShortcutAction::Input => &mut self.input,
+            ShortcutAction::AutoInsert => &mut self.auto_insert,
+            ShortcutAction::Fragments => &mut self.fragments,
+            ShortcutAction::Today => &mut self.today,
+            ShortcutAction::QuantityUp => &mut self.quantity_up,
+            ShortcutAction::QuantityDown => &mut self.quantity_down,
+        } = binding
+    }
+
+    /// Whether a binding can be understood, an empty one is valid.
+    pub fn is_valid(binding: &str) -> bool {
+        binding.trim().is_empty() || Shortcut::parse(binding).is_some()
+    }
+
+    /// The action bound to a pressed key. A key `captured` by an input, like a typed letter,
+    /// only triggers the shortcuts that can't be typed.
+    pub fn action(
+        &self,
+        key: &Key,
+        modifiers: Modifiers,
+        captured: bool,
+    ) -> Option<ShortcutAction> {
+        ShortcutAction::ALL.into_iter().find(|&action| {
+            Shortcut::parse(self.binding(action)).is_some_and(|shortcut| {
+                shortcut.matches(key, modifiers) && (!captured || shortcut.is_untyped())
+            })
+        })
+    }
+
+    /// Whether plain `Enter` is bound to the action, then submitting an input triggers it.
+    pub fn is_enter(&self, action: ShortcutAction) -> bool {
+        Shortcut::parse(self.binding(action)).is_some_and(|shortcut| {
+            shortcut.matches(&Key::Named(Named::Enter), Modifiers::empty())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ShortcutAction, ShortcutSettings};
+    use iced::keyboard::key::Named;
+    use iced::keyboard::{Key, Modifiers};
+
+    #[test]
+    fn shortcuts() {
+        let mut shortcuts = ShortcutSettings::default();
+        let character = |character: &str| Key::Character(character.into());
+        assert_eq!(
+            Some(ShortcutAction::Today),
+            shortcuts.action(&character("d"), Modifiers::COMMAND, false)
+        );
+        assert_eq!(
+            Some(ShortcutAction::Today),
+            shortcuts.action(&character("D"), Modifiers::COMMAND, false)
+        );
+        assert_eq!(None, shortcuts.action(&character("d"), Modifiers::empty(), false));
+        assert_eq!(
+            Some(ShortcutAction::AutoInsert),
+            shortcuts.action(&character("3"), Modifiers::COMMAND, false)
+        );
+        assert_eq!(
+            Some(ShortcutAction::QuantityDown),
+            shortcuts.action(&Key::Named(Named::PageDown), Modifiers::empty(), false)
+        );
+        assert_eq!(None, shortcuts.action(&Key::Named(Named::Enter), Modifiers::SHIFT, false));
+
+        shortcuts.set_binding(ShortcutAction::Create, "alt+shift+F2".to_string());
+        assert_eq!(
+            Some(ShortcutAction::Create),
+            shortcuts.action(&Key::Named(Named::F2), Modifiers::ALT | Modifiers::SHIFT, false)
+        );
+        shortcuts.set_binding(ShortcutAction::Today, "".to_string());
+        assert_eq!(None, shortcuts.action(&character("d"), Modifiers::COMMAND, false));
+
+        // while typing, only the shortcuts that can't be typed work
+        shortcuts.set_binding(ShortcutAction::Today, "T".to_string());
+        assert_eq!(
+            Some(ShortcutAction::Today),
+            shortcuts.action(&character("t"), Modifiers::empty(), false)
+        );
+        assert_eq!(None, shortcuts.action(&character("t"), Modifiers::empty(), true));
+        assert_eq!(
+            Some(ShortcutAction::AutoInsert),
+            shortcuts.action(&character("3"), Modifiers::COMMAND, true)
+        );
+        assert_eq!(
+            Some(ShortcutAction::QuantityUp),
+            shortcuts.action(&Key::Named(Named::PageUp), Modifiers::empty(), true)
+        );
+
+        assert!(ShortcutSettings::default().is_enter(ShortcutAction::Create));
+        assert!(!shortcuts.is_enter(ShortcutAction::Create));
+
+        assert!(ShortcutSettings::is_valid("Ctrl+Shift+K"));
+        assert!(ShortcutSettings::is_valid(""));
+        assert!(!ShortcutSettings::is_valid("Hyper+K"));
+        assert!(!ShortcutSettings::is_valid("Ctrl+Banana"));
+    }
+}
@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Status {
+    pub name: String,
+    /// The statuses a find can go to from this one.
+    pub next: Vec<String>,
+    pub input_next: String,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatusSettings {
+    /// The field holding the status of a find.
+    pub field: String,
+    /// The field the dashboard groups the finds by.
+    pub group_field: String,
+    pub statuses: Vec<Status>,
+    pub input_new_status: String,
+}
+
+impl Status {
+    pub fn new(name: &str, next: &[&str]) -> Self {
+        Self {
+            name: name.to_string(),
+            next: next.iter().map(|next| next.to_string()).collect(),
+            input_next: next.join(", "),
+        }
+    }
+
+    pub fn set_next(&mut self, input_next: String) {
+        self.next = input_next
+            .split(',')
+            .map(|next| next.trim().to_string())
+            .filter(|next| !next.is_empty())
+            .collect();
+        self.input_next = input_next;
+    }
+}
+
+impl StatusSettings {
+    /// A find without a status can only get the first one.
+    pub fn can_change(&self, from: &str, to: &str) -> bool {
+        if from.is_empty() {
+            self.statuses.first().is_some_and(|status| status.name == to)
+        } else {
+            self.statuses
+                .iter()
+                .find(|status| status.name == from)
+                .is_some_and(|status| status.next.iter().any(|next| next == to))
+        }
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.statuses.iter().map(|status| status.name.clone()).collect()
+    }
+}
+
+impl Default for StatusSettings {
+    fn default() -> Self {
+        Self {
+            field: "Status".to_string(),
+            group_field: "Trench".to_string(),
+            statuses: vec![
+                Status::new("registered", &["washed"]),
+                Status::new("washed", &["drawn", "photographed"]),
+                Status::new("drawn", &["photographed"]),
+                Status::new("photographed", &["drawn", "conserved", "archived"]),
+                Status::new("conserved", &["archived"]),
+                Status::new("archived", &[]),
+            ],
+            input_new_status: "".to_string(),
+        }
+    }
+}
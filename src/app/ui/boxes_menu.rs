@@ -0,0 +1,317 @@
+use crate::app::data_base::boxes::{Move, StorageBox};
+use crate::app::data_base::export::to_csv;
+use crate::app::data_base::index::Index;
+use crate::app::data_base::record::Record;
+use crate::app::data_base::report::fill;
+use crate::app::data_base::table::Table;
+use crate::app::data_base::DataBase;
+use crate::app::i18n::tr;
+use crate::app::settings::Settings;
+use crate::app::ui::saved;
+use crate::app::{ArchaeologicalAssistant, Message};
+use iced::widget::{button, column, row, scrollable, text, text_input, Column};
+use iced::{alignment, Element, Length, Renderer, Theme};
+use native_dialog::FileDialog;
+use std::fs;
+
+#[derive(Debug, Clone)]
+pub enum BoxesMessage {
+    SetBoxData(String, usize),
+    AddBox,
+    SelectBox(String),
+    SetInput(String),
+    PutIntoBox,
+    /// Puts the scanned finds into the selected box.
+    PutScanned(Vec<Index>),
+    SaveLabel,
+    Export,
+}
+
+pub struct BoxesData {
+    pub boxes: Vec<StorageBox>,
+    /// The new box: id, location and description.
+    pub box_data: Vec<String>,
+    pub selected: Option<String>,
+    pub contents: Vec<Record>,
+    pub moves: Vec<Move>,
+    pub input: String,
+    pub status: Option<String>,
+}
+
+impl BoxesData {
+    /// The boxes are kept in the register on the server, a client has no copy of them.
+    fn refuse(&mut self, settings: &Settings) -> bool {
+        let is_client = settings.api.is_client();
+        if is_client {
+            let language = &settings.current_language;
+            self.status =
+                Some(tr(language, "Boxes can only be managed on the server").to_string());
+        }
+        is_client
+    }
+
+    pub fn reload(&mut self, settings: &Settings) {
+        if self.refuse(settings) {
+            self.boxes = vec![];
+            self.contents = vec![];
+            self.moves = vec![];
+            return;
+        }
+        let data_base = DataBase::from(&*settings.path_to_db);
+        let format = &settings.index_format;
+        self.boxes = data_base.get_boxes();
+        let Some(id) = &self.selected else {
+            self.contents = vec![];
+            self.moves = vec![];
+            return;
+        };
+        let sheet = data_base.get_sheet();
+        self.contents = data_base
+            .box_contents(id, format)
+            .into_iter()
+            .filter_map(|index| {
+                let row = sheet.get_row_index_from_index(index, format)?;
+                sheet.get_record(row, settings.fields.len() + 1, format)
+            })
+            .collect();
+        self.moves = data_base
+            .get_moves(format)
+            .into_iter()
+            .filter(|other| other.from == *id || other.to == *id)
+            .collect();
+    }
+
+    fn put_into_box(&mut self, indices: Vec<Index>, settings: &Settings) {
+        let Some(id) = self.selected.clone() else {
+            return;
+        };
+        if self.refuse(settings) {
+            return;
+        }
+        {
+            let _lock = DataBase::lock();
+            let mut data_base = DataBase::from(&*settings.path_to_db);
+            let (exist, missing): (Vec<_>, Vec<_>) = indices.into_iter().partition(|&index| {
+                data_base
+                    .get_sheet()
+                    .get_row_index_from_index(index, &settings.index_format)
+                    .is_some()
+            });
+            let moves =
+                data_base.move_to_box(&exist, &id, &settings.index_format, &settings.recorder);
+            data_base.save(&settings.path_to_db);
+            let language = &settings.current_language;
+            let values = [
+                ("count", moves.len().to_string()),
+                ("box", id),
+                (
+                    "missing",
+                    missing
+                        .iter()
+                        .map(|&index| settings.index_format.format(index))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                ),
+            ];
+            self.status = Some(if missing.is_empty() {
+                fill(tr(language, "{count} finds put into {box}"), &values)
+            } else {
+                fill(tr(language, "{count} finds put into {box}, not found: {missing}"), &values)
+            });
+        }
+        self.reload(settings);
+    }
+
+    fn save_file(
+        &mut self,
+        extension: &'static str,
+        settings: &Settings,
+        content: impl FnOnce(&Self, &str) -> String,
+    ) {
+        let Some(id) = self.selected.clone() else {
+            return;
+        };
+        let path = FileDialog::new()
+            .set_filename(&format!("{}.{}", id, extension))
+            .add_filter(extension, &[extension])
+            .show_save_single_file();
+        if let Ok(Some(path)) = path {
+            self.status = Some(match fs::write(&path, content(self, &id)) {
+                Ok(_) => saved(&settings.current_language, &path),
+                Err(error) => error.to_string(),
+            });
+        }
+    }
+
+    pub fn update(&mut self, message: BoxesMessage, settings: &Settings) {
+        match message {
+            BoxesMessage::SetBoxData(str, id) => self.box_data[id] = str,
+            BoxesMessage::AddBox if self.refuse(settings) => {}
+            BoxesMessage::AddBox => {
+                if let Some(new_box) = StorageBox::from_row(&self.box_data) {
+                    let _lock = DataBase::lock();
+                    let mut data_base = DataBase::from(&*settings.path_to_db);
+                    self.boxes = data_base.get_boxes();
+                    if !self.boxes.iter().any(|other| other.id == new_box.id) {
+                        self.boxes.push(new_box);
+                        data_base.set_boxes(&self.boxes);
+                        data_base.save(&settings.path_to_db);
+                        self.box_data = vec!["".to_string(); 3];
+                    }
+                }
+            }
+            BoxesMessage::SelectBox(id) => {
+                self.selected = Some(id);
+                self.status = None;
+                self.reload(settings);
+            }
+            BoxesMessage::SetInput(input) => self.input = input,
+            BoxesMessage::PutIntoBox => {
+                let input = std::mem::take(&mut self.input);
+                let indices = input
+                    .split(|char: char| char.is_whitespace() || char == ',' || char == ';')
+                    .filter_map(|token| settings.index_format.parse(token))
+                    .collect();
+                self.put_into_box(indices, settings)
+            }
+            BoxesMessage::PutScanned(indices) => self.put_into_box(indices, settings),
+            BoxesMessage::SaveLabel => self.save_file("svg", settings, |data, id| {
+                let lines = data
+                    .boxes
+                    .iter()
+                    .find(|other| other.id == id)
+                    .map(StorageBox::label_lines)
+                    .unwrap_or_else(|| vec![id.to_string()]);
+                settings.print_settings.label_svg(&lines)
+            }),
+            BoxesMessage::Export if self.refuse(settings) => {}
+            BoxesMessage::Export => self.save_file("csv", settings, |_, id| {
+                to_csv(&DataBase::from(&*settings.path_to_db).box_contents_rows(
+                    id,
+                    &settings.fields,
+                    &settings.index_format,
+                ))
+            }),
+        }
+    }
+}
+
+impl Default for BoxesData {
+    fn default() -> Self {
+        Self {
+            boxes: vec![],
+            box_data: vec!["".to_string(); 3],
+            selected: None,
+            contents: vec![],
+            moves: vec![],
+            input: "".to_string(),
+            status: None,
+        }
+    }
+}
+
+impl ArchaeologicalAssistant {
+    fn view_box(&self) -> Column<Message, Theme, Renderer> {
+        let Some(id) = &self.boxes_data.selected else {
+            return column![text(self.tr("Select a box"))];
+        };
+        let contents = self
+            .boxes_data
+            .contents
+            .iter()
+            .map(|record| {
+                Element::from(text(format!(
+                    "{}  {}",
+                    self.settings.index_format.format(record.index),
+                    record.data.get(1).cloned().unwrap_or_default()
+                )))
+            })
+            .collect::<Vec<_>>();
+        let moves = self
+            .boxes_data
+            .moves
+            .iter()
+            .rev()
+            .map(|other| {
+                Element::from(text(format!(
+                    "{}  {}  {} -> {}  {}",
+                    other.time,
+                    self.settings.index_format.format(other.index),
+                    if other.from.is_empty() { "-" } else { &other.from },
+                    if other.to.is_empty() { "-" } else { &other.to },
+                    other.recorder
+                )))
+            })
+            .collect::<Vec<_>>();
+
+        column![
+            row![
+                text(id.clone()).size(24),
+                button(self.tr("label")).on_press(Message::Boxes(BoxesMessage::SaveLabel)),
+                button(self.tr("export contents")).on_press(Message::Boxes(BoxesMessage::Export))
+            ]
+            .spacing(12)
+            .align_y(alignment::Vertical::Center),
+            row![
+                text_input(self.tr("indices, e.g. 15, 16, 20.1"), &self.boxes_data.input)
+                    .on_input(|input| Message::Boxes(BoxesMessage::SetInput(input)))
+                    .on_submit(Message::Boxes(BoxesMessage::PutIntoBox)),
+                button(self.tr("put into box")).on_press(Message::Boxes(BoxesMessage::PutIntoBox)),
+                button(self.tr("put scanned finds")).on_press_maybe(
+                    (!self.scan_data.scanned.is_empty()).then(|| {
+                        Message::Boxes(BoxesMessage::PutScanned(
+                            self.scan_data.scanned.iter().map(|record| record.index).collect(),
+                        ))
+                    })
+                )
+            ]
+            .spacing(12),
+            text(self.boxes_data.status.clone().unwrap_or_default()),
+            text(format!("{}: {}", self.tr("Contents"), self.boxes_data.contents.len())),
+            scrollable(Column::with_children(contents).spacing(2)).height(Length::FillPortion(2)),
+            text(self.tr("Moves")),
+            scrollable(Column::with_children(moves).spacing(2)).height(Length::FillPortion(1)),
+        ]
+        .spacing(12)
+    }
+
+    pub fn view_boxes_menu(&self) -> impl Into<Element<Message, Theme, Renderer>> {
+        let boxes = self
+            .boxes_data
+            .boxes
+            .iter()
+            .map(|storage_box| {
+                let is_selected = self.boxes_data.selected.as_ref() == Some(&storage_box.id);
+                Element::from(
+                    button(text(storage_box.to_string()))
+                        .on_press(Message::Boxes(BoxesMessage::SelectBox(storage_box.id.clone())))
+                        .width(Length::Fill)
+                        .style(move |theme, status| {
+                            if is_selected {
+                                button::primary(theme, status)
+                            } else {
+                                button::text(theme, status)
+                            }
+                        }),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        row![
+            column![
+                text_input(self.tr("box id"), &self.boxes_data.box_data[0])
+                    .on_input(|str| Message::Boxes(BoxesMessage::SetBoxData(str, 0))),
+                text_input(self.tr("shelf"), &self.boxes_data.box_data[1])
+                    .on_input(|str| Message::Boxes(BoxesMessage::SetBoxData(str, 1))),
+                text_input(self.tr("description"), &self.boxes_data.box_data[2])
+                    .on_input(|str| Message::Boxes(BoxesMessage::SetBoxData(str, 2))),
+                button(self.tr("add box")).on_press(Message::Boxes(BoxesMessage::AddBox)),
+                scrollable(Column::with_children(boxes).spacing(2)).height(Length::Fill)
+            ]
+            .spacing(5)
+            .width(300),
+            self.view_box().width(Length::Fill).padding(10)
+        ]
+        .spacing(5)
+    }
+}
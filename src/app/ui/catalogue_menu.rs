@@ -0,0 +1,80 @@
+use crate::app::data_base::catalogue::CatalogueFormat;
+use crate::app::data_base::report::fill;
+use crate::app::{ArchaeologicalAssistant, Message};
+use iced::widget::{button, column, pick_list, row, scrollable, text, text_input, Column, Row};
+use iced::{alignment, Color, Element, Length, Renderer, Theme};
+
+impl ArchaeologicalAssistant {
+    pub fn view_catalogue_menu(&self) -> impl Into<Element<Message, Theme, Renderer>> {
+        let sort_options = ["Index".to_string(), "Date".to_string()]
+            .into_iter()
+            .chain(self.settings.fields.iter().map(|field| field.name.clone()))
+            .collect::<Vec<_>>();
+        let placeholders = ["{index}".to_string(), "{date}".to_string()]
+            .into_iter()
+            .chain(
+                self.settings
+                    .fields
+                    .iter()
+                    .map(|field| format!("{{{}}}", field.name)),
+            )
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let preview: Element<Message, Theme, Renderer> = match &self.catalogue {
+            Ok(entries) => column![
+                text(fill(
+                    self.tr("{count} entries"),
+                    &[("count", entries.len().to_string())]
+                )),
+                scrollable(
+                    Column::with_children(entries.iter().map(|entry| text(entry.clone()).into()))
+                        .spacing(5)
+                )
+                .height(Length::Fill)
+            ]
+            .spacing(12)
+            .into(),
+            Err(error) => text(error.clone()).color(Color::new(1., 0., 0., 1.)).into(),
+        };
+
+        column![
+            row![
+                text(self.tr("title")),
+                text_input("", &self.settings.catalogue.title).on_input(Message::SetCatalogueTitle)
+            ]
+            .spacing(12)
+            .align_y(alignment::Vertical::Center),
+            row![
+                text(self.tr("entry")),
+                text_input("", &self.settings.catalogue.entry).on_input(Message::SetCatalogueEntry)
+            ]
+            .spacing(12)
+            .align_y(alignment::Vertical::Center),
+            text(placeholders),
+            row![
+                text(self.tr("filter")),
+                text_input("Square=A1; Name~pot; Status!=lost", &self.settings.catalogue.filter)
+                    .on_input(Message::SetCatalogueFilter),
+                text(self.tr("sort by")),
+                pick_list(
+                    sort_options,
+                    Some(self.settings.catalogue.sort_by.clone()),
+                    Message::SetCatalogueSort
+                )
+            ]
+            .spacing(12)
+            .align_y(alignment::Vertical::Center),
+            Row::with_children(CatalogueFormat::ALL.into_iter().map(|format| {
+                button(text(format!("{} {}", self.tr("export"), format)))
+                    .on_press_maybe(self.catalogue.is_ok().then_some(Message::ExportCatalogue(format)))
+                    .into()
+            }))
+            .push(text(self.catalogue_message.clone().unwrap_or_default()))
+            .spacing(12)
+            .align_y(alignment::Vertical::Center),
+            preview
+        ]
+        .spacing(12)
+    }
+}
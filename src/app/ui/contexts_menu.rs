@@ -0,0 +1,272 @@
+use crate::app::data_base::contexts::{render_dot, Context, Relation, RelationKind};
+use crate::app::data_base::report::fill;
+use crate::app::data_base::DataBase;
+use crate::app::i18n::tr;
+use crate::app::settings::Settings;
+use crate::app::ui::saved;
+use crate::app::{ArchaeologicalAssistant, Message};
+use iced::widget::{button, column, pick_list, row, scrollable, text, text_input, Column, Row};
+use iced::{alignment, Element, Length, Renderer, Theme};
+use native_dialog::FileDialog;
+use std::fs;
+
+#[derive(Debug, Clone)]
+pub enum ContextsMessage {
+    SetInput(String, usize),
+    Save,
+    SelectRelationFrom(String),
+    SelectRelationKind(RelationKind),
+    SelectRelationTo(String),
+    AddRelation,
+    RemoveRelation(usize),
+    ExportHarris(&'static str),
+}
+
+pub struct ContextsData {
+    pub contexts: Vec<Context>,
+    pub relations: Vec<Relation>,
+    /// The ID of a context followed by its fields.
+    pub input: Vec<String>,
+    pub relation_from: Option<String>,
+    pub relation_kind: RelationKind,
+    pub relation_to: Option<String>,
+    pub message: Option<String>,
+}
+
+impl ContextsData {
+    pub fn reload(&mut self, settings: &Settings) {
+        self.input.resize(settings.contexts.fields.len() + 1, "".to_string());
+        if settings.api.is_client() {
+            self.contexts = vec![];
+            self.relations = vec![];
+            let language = &settings.current_language;
+            self.message =
+                Some(tr(language, "Contexts can only be managed on the server").to_string());
+            return;
+        }
+        let data_base = DataBase::from(&*settings.path_to_db);
+        self.contexts = data_base.get_contexts();
+        self.relations = data_base.get_relations();
+    }
+
+    /// Saves the context typed in, replacing the one with the same ID.
+    fn save(&mut self, settings: &Settings) {
+        let language = &settings.current_language;
+        let Some(context) = Context::from_row(
+            &self
+                .input
+                .iter()
+                .map(|value| value.trim().to_string())
+                .collect::<Vec<_>>(),
+        ) else {
+            self.message = Some(tr(language, "Enter the ID of the context").to_string());
+            return;
+        };
+        {
+            let _lock = DataBase::lock();
+            let mut data_base = DataBase::from(&*settings.path_to_db);
+            let mut contexts = data_base.get_contexts();
+            match contexts.iter_mut().find(|other| other.id == context.id) {
+                Some(other) => *other = context.clone(),
+                None => contexts.push(context.clone()),
+            }
+            data_base.set_contexts(&contexts);
+            data_base.save(&settings.path_to_db);
+        }
+        self.message = Some(fill(
+            tr(language, "Saved {context}"),
+            &[("context", context.to_string())],
+        ));
+        self.input = vec!["".to_string(); self.input.len()];
+        self.reload(settings);
+    }
+
+    fn add_relation(&mut self, settings: &Settings) {
+        let (Some(from), Some(to)) = (self.relation_from.clone(), self.relation_to.clone()) else {
+            return;
+        };
+        let relation = Relation {
+            from,
+            kind: self.relation_kind,
+            to,
+        };
+        let language = &settings.current_language;
+        let result = {
+            let _lock = DataBase::lock();
+            let mut data_base = DataBase::from(&*settings.path_to_db);
+            let result = data_base.add_relation(relation.clone(), language);
+            if result.is_ok() {
+                data_base.save(&settings.path_to_db);
+            }
+            result
+        };
+        self.message = Some(match result {
+            Ok(_) => fill(tr(language, "Added {relation}"), &[("relation", relation.to_string())]),
+            Err(error) => error,
+        });
+        self.reload(settings);
+    }
+
+    fn export_harris(&mut self, extension: &'static str, settings: &Settings) {
+        let path = FileDialog::new()
+            .set_filename(&format!("harris.{}", extension))
+            .add_filter(extension, &[extension])
+            .show_save_single_file();
+        if let Ok(Some(path)) = path {
+            let dot = DataBase::from(&*settings.path_to_db).harris_dot();
+            let result = if extension == "dot" {
+                fs::write(&path, dot).map_err(|error| error.to_string())
+            } else {
+                render_dot(&dot, extension, &path)
+            };
+            self.message = Some(match result {
+                Ok(_) => saved(&settings.current_language, &path),
+                Err(error) => error,
+            });
+        }
+    }
+
+    pub fn update(&mut self, message: ContextsMessage, settings: &Settings) {
+        match message {
+            ContextsMessage::SetInput(value, id) => self.input[id] = value,
+            ContextsMessage::Save
+            | ContextsMessage::AddRelation
+            | ContextsMessage::RemoveRelation(_)
+            | ContextsMessage::ExportHarris(_)
+                if settings.api.is_client() =>
+            {
+                let language = &settings.current_language;
+                self.message =
+                    Some(tr(language, "Contexts can only be managed on the server").to_string())
+            }
+            ContextsMessage::Save => self.save(settings),
+            ContextsMessage::SelectRelationFrom(from) => self.relation_from = Some(from),
+            ContextsMessage::SelectRelationKind(kind) => self.relation_kind = kind,
+            ContextsMessage::SelectRelationTo(to) => self.relation_to = Some(to),
+            ContextsMessage::AddRelation => self.add_relation(settings),
+            ContextsMessage::RemoveRelation(id) => {
+                {
+                    let _lock = DataBase::lock();
+                    let mut data_base = DataBase::from(&*settings.path_to_db);
+                    let mut relations = data_base.get_relations();
+                    if id < relations.len() {
+                        relations.remove(id);
+                        data_base.set_relations(&relations);
+                        data_base.save(&settings.path_to_db);
+                    }
+                }
+                self.reload(settings);
+            }
+            ContextsMessage::ExportHarris(extension) => self.export_harris(extension, settings),
+        }
+    }
+}
+
+impl Default for ContextsData {
+    fn default() -> Self {
+        Self {
+            contexts: vec![],
+            relations: vec![],
+            input: vec![],
+            relation_from: None,
+            relation_kind: RelationKind::Above,
+            relation_to: None,
+            message: None,
+        }
+    }
+}
+
+impl ArchaeologicalAssistant {
+    pub fn view_contexts_menu(&self) -> impl Into<Element<Message, Theme, Renderer>> {
+        let ids = self
+            .contexts_data
+            .contexts
+            .iter()
+            .map(|context| context.id.clone())
+            .collect::<Vec<_>>();
+        let names = ["ID".to_string()]
+            .into_iter()
+            .chain(self.settings.contexts.fields.iter().cloned())
+            .collect::<Vec<_>>();
+
+        let context_input = Row::with_children(
+            names
+                .into_iter()
+                .zip(&self.contexts_data.input)
+                .enumerate()
+                .map(|(i, (name, value))| {
+                    text_input(&name, value)
+                        .on_input(move |value| {
+                            Message::Contexts(ContextsMessage::SetInput(value, i))
+                        })
+                        .on_submit(Message::Contexts(ContextsMessage::Save))
+                        .into()
+                }),
+        )
+        .push(button(self.tr("save context")).on_press(Message::Contexts(ContextsMessage::Save)))
+        .spacing(12);
+
+        let contexts = self
+            .contexts_data
+            .contexts
+            .iter()
+            .map(|context| text(context.to_string()).into());
+        let relations = self
+            .contexts_data
+            .relations
+            .iter()
+            .enumerate()
+            .map(|(i, relation)| {
+                Element::from(
+                    row![
+                        text(relation.to_string()).width(Length::Fill),
+                        button(self.tr("remove"))
+                            .on_press(Message::Contexts(ContextsMessage::RemoveRelation(i)))
+                    ]
+                    .spacing(5)
+                    .align_y(alignment::Vertical::Center),
+                )
+            });
+
+        column![
+            context_input,
+            row![
+                pick_list(ids.clone(), self.contexts_data.relation_from.clone(), |from| {
+                    Message::Contexts(ContextsMessage::SelectRelationFrom(from))
+                }),
+                pick_list(RelationKind::ALL, Some(self.contexts_data.relation_kind), |kind| {
+                    Message::Contexts(ContextsMessage::SelectRelationKind(kind))
+                }),
+                pick_list(ids, self.contexts_data.relation_to.clone(), |to| {
+                    Message::Contexts(ContextsMessage::SelectRelationTo(to))
+                }),
+                button(self.tr("add relation"))
+                    .on_press(Message::Contexts(ContextsMessage::AddRelation))
+            ]
+            .spacing(12)
+            .align_y(alignment::Vertical::Center),
+            row![
+                text(self.tr("Harris matrix")),
+                button(self.tr("export DOT"))
+                    .on_press(Message::Contexts(ContextsMessage::ExportHarris("dot"))),
+                button(self.tr("export PNG"))
+                    .on_press(Message::Contexts(ContextsMessage::ExportHarris("png"))),
+                button(self.tr("export SVG"))
+                    .on_press(Message::Contexts(ContextsMessage::ExportHarris("svg"))),
+                text(self.contexts_data.message.clone().unwrap_or_default())
+            ]
+            .spacing(12)
+            .align_y(alignment::Vertical::Center),
+            row![
+                scrollable(Column::with_children(contexts).spacing(5))
+                    .width(Length::FillPortion(1))
+                    .height(Length::Fill),
+                scrollable(Column::with_children(relations).spacing(5))
+                    .width(Length::FillPortion(1))
+                    .height(Length::Fill)
+            ]
+            .spacing(12)
+        ]
+        .spacing(12)
+    }
+}
@@ -0,0 +1,46 @@
+use crate::app::data_base::report::fill;
+use crate::app::ui::records_menu::RecordsMessage;
+use crate::app::{ArchaeologicalAssistant, Message};
+use iced::widget::{button, column, row, scrollable, text, Column};
+use iced::{alignment, Element, Length, Renderer, Theme};
+
+impl ArchaeologicalAssistant {
+    pub fn view_integrity_menu(&self) -> impl Into<Element<Message, Theme, Renderer>> {
+        let problems = self
+            .problems
+            .iter()
+            .map(|problem| {
+                Element::from(
+                    row![
+                        text(problem.text(&self.settings.current_language)).width(Length::Fill),
+                        button(self.tr("go to")).on_press_maybe(
+                            problem
+                                .row()
+                                .map(|row| Message::Records(RecordsMessage::JumpTo(row)))
+                        )
+                    ]
+                    .spacing(5)
+                    .align_y(alignment::Vertical::Center),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        column![
+            row![
+                button(self.tr("Check")).on_press(Message::CheckIntegrity),
+                text(if self.problems.is_empty() {
+                    self.tr("No problems found").to_string()
+                } else {
+                    fill(
+                        self.tr("{count} problems found"),
+                        &[("count", self.problems.len().to_string())],
+                    )
+                })
+            ]
+            .spacing(12)
+            .align_y(alignment::Vertical::Center),
+            scrollable(Column::with_children(problems).spacing(5)).height(Length::Fill)
+        ]
+        .spacing(12)
+    }
+}
@@ -1,5 +1,7 @@
-use crate::app::data_base::table::Table;
-use crate::app::data_base::DataBase;
+use crate::app::data_base::references::parse_references;
+use crate::app::data_base::report::fill;
+use crate::app::data_base::reservation::{Skip, SkipOption};
+use crate::app::data_base::spatial::{coordinates_id, Point};
 use crate::app::settings::insert_methods::StartEnd;
 use crate::app::settings::{
     insert_methods::{InsertMethods, InsertMethodsMessage},
@@ -9,7 +11,7 @@ use crate::app::{ArchaeologicalAssistant, Message};
 use iced::widget::{
     button, checkbox, column, combo_box, container, radio, row, text, text_input, Column,
 };
-use iced::{alignment, Element, Renderer, Theme};
+use iced::{alignment, Color, Element, Renderer, Theme};
 use iced_aw::{TabLabel, Tabs};
 
 impl ArchaeologicalAssistant {
@@ -17,14 +19,14 @@ impl ArchaeologicalAssistant {
         container(
             row![
                 radio(
-                    "End",
+                    self.tr("End"),
                     StartEnd::End,
                     Some(self.settings.insert_methods_data.start_end),
                     |data| Message::SetInsertMethodsData(InsertMethodsMessage::StartEnd(data))
                 ),
                 if self.is_can_start_insert {
                     radio(
-                        "Start",
+                        self.tr("Start"),
                         StartEnd::Start,
                         Some(self.settings.insert_methods_data.start_end),
                         |data| Message::SetInsertMethodsData(InsertMethodsMessage::StartEnd(data)),
@@ -33,7 +35,9 @@ impl ArchaeologicalAssistant {
                 } else {
                     Element::from(row![
                         text("  |  "),
-                        text("If the first index is equal to 1, the Start option is not available"),
+                        text(self.tr(
+                            "If the first index is equal to 1, the Start option is not available"
+                        )),
                     ])
                 }
             ]
@@ -46,17 +50,34 @@ impl ArchaeologicalAssistant {
         container(
             column![
                 text_input(
-                    "Enter index",
+                    self.tr("Enter index"),
                     &self
                         .settings
                         .insert_methods_data
                         .input_number_for_insert_methods_data
                         .input,
                 )
-                .on_input(|data| Message::SetInsertMethodsData(InsertMethodsMessage::Input(data))),
+                .on_input(|data| Message::SetInsertMethodsData(InsertMethodsMessage::Input(data)))
+                .on_submit(Message::Submit),
                 if self.is_replace {
-                    checkbox("are you sure you want to replace the data", self.on_replace)
-                        .on_toggle(Message::OnReplace).into()
+                    checkbox(
+                        self.tr("are you sure you want to replace the data"),
+                        self.on_replace,
+                    )
+                    .on_toggle(Message::OnReplace)
+                    .into()
+                } else if let Some(reservation) = self.reservations.iter().find(|reservation| {
+                    reservation.contains(self.settings.insert_methods_data.input.number)
+                }) {
+                    checkbox(
+                        fill(
+                            self.tr("record into the range {reservation}"),
+                            &[("reservation", reservation.text(&self.settings.current_language))],
+                        ),
+                        self.on_replace,
+                    )
+                    .on_toggle(Message::OnReplace)
+                    .into()
                 } else {
                     Element::from(row![])
                 }
@@ -71,9 +92,17 @@ impl ArchaeologicalAssistant {
             row![
                 combo_box(
                     &self.state_auto_insert,
-                    "Pick index",
-                    Some(&self.settings.insert_methods_data.auto_insert),
-                    |data| Message::SetInsertMethodsData(InsertMethodsMessage::AutoInsert(data))
+                    self.tr("Pick index"),
+                    Some(&SkipOption::new(
+                        Skip::Free(self.settings.insert_methods_data.auto_insert),
+                        &self.settings.current_language,
+                    )),
+                    |option| match option.skip {
+                        Skip::Free(data) => {
+                            Message::SetInsertMethodsData(InsertMethodsMessage::AutoInsert(data))
+                        }
+                        Skip::Reserved(_) => Message::None,
+                    }
                 ),
                 button("R").on_press(Message::RebootAutoInsertState)
             ]
@@ -82,23 +111,124 @@ impl ArchaeologicalAssistant {
         .padding(10)
     }
 
+    fn view_fragments_insert(&self) -> impl Into<Element<Message, Theme, Renderer>> {
+        container(
+            column![
+                text_input(
+                    self.tr("Enter parent index"),
+                    &self
+                        .settings
+                        .insert_methods_data
+                        .input_number_for_insert_methods_data
+                        .parent,
+                )
+                .on_input(|data| Message::SetInsertMethodsData(InsertMethodsMessage::Parent(data)))
+                .on_submit(Message::Submit),
+                if self.is_parent_exist {
+                    text(self.tr("Fragments copy the fields of the parent record")).into()
+                } else {
+                    Element::from(
+                        text(self.tr("The parent record does not exist"))
+                            .color(Color::new(1., 1., 0., 1.)),
+                    )
+                }
+            ]
+            .spacing(5),
+        )
+        .padding(10)
+    }
+
+    /// Why the entered square does not match the coordinates of the find, if it does not.
+    fn square_disagreement(&self, square: &str) -> Option<String> {
+        let point = Point::parse(&self.data[coordinates_id(&self.settings.fields)?])?;
+        match self.settings.grid.square_bounds(square) {
+            _ if square.trim().is_empty() => None,
+            Some(bounds) if point.is_in(bounds) => None,
+            _ => Some(match self.settings.grid.square_of(point.x, point.y) {
+                Some(computed) => fill(
+                    self.tr("the coordinates are in {square}"),
+                    &[("square", computed)],
+                ),
+                None => self.tr("the coordinates are outside of the grid").to_string(),
+            }),
+        }
+    }
+
     pub fn view_input_field(&self) -> impl Into<Element<Message, Theme, Renderer>> {
+        let language = &self.settings.current_language;
         let input_fields = Column::with_children(
             (1..=self.settings.fields.len())
                 .zip(&self.settings.fields)
                 .map(|(i, field)| match field.field_type {
                     FieldType::Text => Element::from(
                         row![
-                            text(field.name.clone())
+                            text(field.display_name(language).to_string())
                                 .align_x(alignment::Horizontal::Right)
                                 .width(100),
                             text_input(
-                                &*("Enter ".to_string() + &field.name.to_lowercase()),
+                                &fill(
+                                    self.tr("Enter {field}"),
+                                    &[("field", field.display_name(language).to_lowercase())],
+                                ),
                                 &self.data[i]
                             )
                             .on_input(move |str| Message::SetData(str, i))
+                            .on_submit(Message::Submit)
                         ]
-                        .spacing(5),
+                        .push_maybe(
+                            (field.name == self.settings.grid.square_field)
+                                .then(|| self.square_disagreement(&self.data[i]))
+                                .flatten()
+                                .map(|note| text(note).color(Color::new(1., 1., 0., 1.))),
+                        )
+                        .spacing(5)
+                        .align_y(alignment::Vertical::Center),
+                    ),
+                    FieldType::Coordinates => {
+                        Element::from(
+                            row![
+                                text(field.display_name(language).to_string())
+                                    .align_x(alignment::Horizontal::Right)
+                                    .width(100),
+                                text_input("x; y; z", &self.data[i])
+                                    .on_input(move |str| Message::SetData(str, i))
+                                    .on_submit(Message::Submit),
+                                text(
+                                    if self.data[i].trim().is_empty()
+                                        || Point::parse(&self.data[i]).is_some()
+                                    {
+                                        ""
+                                    } else {
+                                        self.tr("x; y; z expected")
+                                    }
+                                )
+                                .color(Color::new(1., 1., 0., 1.))
+                            ]
+                            .spacing(5)
+                            .align_y(alignment::Vertical::Center),
+                        )
+                    }
+                    FieldType::Reference => Element::from(
+                        row![
+                            text(field.display_name(language).to_string())
+                                .align_x(alignment::Horizontal::Right)
+                                .width(100),
+                            text_input(self.tr("indices separated by ;"), &self.data[i])
+                                .on_input(move |str| Message::SetData(str, i))
+                                .on_submit(Message::Submit),
+                            text(
+                                if parse_references(&self.data[i], &self.settings.index_format)
+                                    .is_some()
+                                {
+                                    ""
+                                } else {
+                                    self.tr("indices separated by ; expected")
+                                }
+                            )
+                            .color(Color::new(1., 1., 0., 1.))
+                        ]
+                        .spacing(5)
+                        .align_y(alignment::Vertical::Center),
                     ),
                 })
                 .collect::<Vec<_>>(),
@@ -107,25 +237,30 @@ impl ArchaeologicalAssistant {
 
         column![
             row![
-                text("Insets method")
+                text(self.tr("Insets method"))
                     .align_x(alignment::Horizontal::Right)
                     .width(100),
                 Tabs::new(Message::SetInsertMethods)
                     .push(
                         InsertMethods::StartEnd,
-                        TabLabel::Text("Start/End".to_string()),
+                        TabLabel::Text(self.tr("Start/End").to_string()),
                         self.view_start_end_insert(),
                     )
                     .push(
                         InsertMethods::Input,
-                        TabLabel::Text("Input".to_string()),
+                        TabLabel::Text(self.tr("Input").to_string()),
                         self.view_input_insert(),
                     )
                     .push(
                         InsertMethods::AutoInsert,
-                        TabLabel::Text("Auto insert".to_string()),
+                        TabLabel::Text(self.tr("Auto insert").to_string()),
                         self.view_auto_insert(),
                     )
+                    .push(
+                        InsertMethods::Fragments,
+                        TabLabel::Text(self.tr("Fragments").to_string()),
+                        self.view_fragments_insert(),
+                    )
                     .set_active_tab(&self.settings.insert_methods_data.insert_methods),
             ]
             .spacing(5),
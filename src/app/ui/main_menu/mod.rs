@@ -13,10 +13,26 @@ impl ArchaeologicalAssistant {
                 self.view_date().into(),
                 column![self.view_input_field().into(),]
             ],
+            text(if self.settings.camera_ingest {
+                self.photo_status.clone().unwrap_or_default()
+            } else {
+                "".to_string()
+            }),
             container(
                 row![
-                    if self.is_replace {
-                        text("!!! This record already exist")
+                    if let Some(error) = self
+                        .create_error
+                        .as_ref()
+                        .or(self.network_error.as_ref())
+                        .or(self.api_error.as_ref())
+                    {
+                        text(error)
+                            .color(Color::new(1., 0., 0., 1.))
+                            .height(Length::Fill)
+                            .align_y(alignment::Vertical::Bottom)
+                            .into()
+                    } else if self.is_replace {
+                        text(self.tr("!!! This record already exist"))
                             .color(Color::new(1., 1., 0., 1.))
                             .height(Length::Fill)
                             .align_y(alignment::Vertical::Bottom)
@@ -29,31 +45,34 @@ impl ArchaeologicalAssistant {
                             {
                                 if self.settings.insert_methods_data.insert_methods
                                     == InsertMethods::StartEnd
+                                    || self.settings.insert_methods_data.insert_methods
+                                        == InsertMethods::Fragments
                                 {
                                     row![
-                                        text("quantity:"),
+                                        text(self.tr("quantity:")),
                                         text_input::<Message, Theme, Renderer>(
                                             "",
                                             &self.quantity.to_string()
                                         )
                                         .on_input(Message::SetQuantity)
+                                        .on_submit(Message::Submit)
                                     ]
                                     .spacing(5)
                                     .into()
                                 } else {
-                                    Element::from(text(
-                                        "The field is only available in start/end mode",
-                                    ))
+                                    Element::from(text(self.tr(
+                                        "The field is only available in start/end and fragments modes",
+                                    )))
                                 }
                             },
                             button(if self.is_replace {
                                 if self.on_replace {
-                                    "Replace"
+                                    self.tr("Replace")
                                 } else {
-                                    "Not replace"
+                                    self.tr("Not replace")
                                 }
                             } else {
-                                "create"
+                                self.tr("create")
                             })
                             .on_press(Message::Create)
                             .width(Length::Fill)
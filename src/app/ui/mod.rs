@@ -1,14 +1,50 @@
+pub mod boxes_menu;
+mod catalogue_menu;
+pub mod contexts_menu;
+mod integrity_menu;
 mod main_menu;
+mod plan_menu;
+pub mod records_menu;
+pub mod scan_menu;
 mod settings_menu;
+mod statistics_menu;
+pub mod status_menu;
+pub mod sync_menu;
 mod update_menu;
 
+use crate::app::data_base::report::fill;
+use crate::app::i18n;
+use crate::app::i18n::tr;
 use crate::app::settings::{Axis, MessageSettings};
 use crate::app::{ArchaeologicalAssistant, MenuStatus, Message};
 use iced::widget::{button, combo_box, container, row, text, text_input, Column};
 use iced::{Element, Renderer, Theme};
 use iced_aw::{TabLabel, Tabs};
+use std::path::Path;
+
+/// The message shown once a file is written.
+pub fn saved(language: &str, path: &Path) -> String {
+    fill(tr(language, "Saved {path}"), &[("path", path.to_string_lossy().to_string())])
+}
 
 impl ArchaeologicalAssistant {
+    /// The text in the language of the interface.
+    pub fn tr(&self, text: &'static str) -> &'static str {
+        i18n::tr(&self.settings.current_language, text)
+    }
+
+    /// The names of the date and of the fields in the language of the interface.
+    pub fn field_names(&self) -> Vec<String> {
+        [self.tr("Date").to_string()]
+            .into_iter()
+            .chain(self.settings.fields.iter().map(|field| {
+                field
+                    .display_name(&self.settings.current_language)
+                    .to_string()
+            }))
+            .collect()
+    }
+
     fn create_param<'elem>(
         placeholder: &'elem str,
         input: Element<'elem, Message, Theme, Renderer>,
@@ -51,6 +87,7 @@ impl ArchaeologicalAssistant {
     }
 
     fn create_path_param<'elem, TextInputMessage, ButtonMessage>(
+        &self,
         placeholder: &'elem str,
         value: impl ToString,
         text_input_message: TextInputMessage,
@@ -65,7 +102,7 @@ impl ArchaeologicalAssistant {
             placeholder,
             row![
                 Element::from(text_input("", &value.to_string()).on_input(text_input_message)),
-                Element::from(button("select file").on_press(button_message(file_types))),
+                Element::from(button(self.tr("select file")).on_press(button_message(file_types))),
             ]
             .spacing(12)
             .into(),
@@ -77,17 +114,67 @@ impl ArchaeologicalAssistant {
             Tabs::new(Message::SetMenu)
                 .push(
                     MenuStatus::Main,
-                    TabLabel::Text("Main".to_string()),
+                    TabLabel::Text(self.tr("Main").to_string()),
                     container(self.view_main_menu()).padding(10),
                 )
+                .push(
+                    MenuStatus::Records,
+                    TabLabel::Text(self.tr("Records").to_string()),
+                    container(self.view_records_menu()).padding(10),
+                )
+                .push(
+                    MenuStatus::Integrity,
+                    TabLabel::Text(self.tr("Check").to_string()),
+                    container(self.view_integrity_menu()).padding(10),
+                )
+                .push(
+                    MenuStatus::Scan,
+                    TabLabel::Text(self.tr("Scan").to_string()),
+                    container(self.view_scan_menu()).padding(10),
+                )
+                .push(
+                    MenuStatus::Boxes,
+                    TabLabel::Text(self.tr("Boxes").to_string()),
+                    container(self.view_boxes_menu()).padding(10),
+                )
+                .push(
+                    MenuStatus::Status,
+                    TabLabel::Text(self.tr("Status").to_string()),
+                    container(self.view_status_menu()).padding(10),
+                )
+                .push(
+                    MenuStatus::Statistics,
+                    TabLabel::Text(self.tr("Statistics").to_string()),
+                    container(self.view_statistics_menu()).padding(10),
+                )
+                .push(
+                    MenuStatus::Catalogue,
+                    TabLabel::Text(self.tr("Catalogue").to_string()),
+                    container(self.view_catalogue_menu()).padding(10),
+                )
+                .push(
+                    MenuStatus::Plan,
+                    TabLabel::Text(self.tr("Plan").to_string()),
+                    container(self.view_plan_menu()).padding(10),
+                )
+                .push(
+                    MenuStatus::Contexts,
+                    TabLabel::Text(self.tr("Contexts").to_string()),
+                    container(self.view_contexts_menu()).padding(10),
+                )
+                .push(
+                    MenuStatus::Sync,
+                    TabLabel::Text(self.tr("Sync").to_string()),
+                    container(self.view_sync_menu()).padding(10),
+                )
                 .push(
                     MenuStatus::Settings,
-                    TabLabel::Text("Settings".to_string()),
+                    TabLabel::Text(self.tr("Settings").to_string()),
                     container(self.view_settings_menu()).padding(10),
                 )
                 .push(
                     MenuStatus::Update,
-                    TabLabel::Text("Update".to_string()),
+                    TabLabel::Text(self.tr("Update").to_string()),
                     container(self.view_update_menu()).padding(10),
                 )
                 .set_active_tab(&self.menu_status),
@@ -0,0 +1,97 @@
+use crate::app::data_base::report::fill;
+use crate::app::data_base::spatial::PlanFormat;
+use crate::app::ui::records_menu::RecordsMessage;
+use crate::app::{ArchaeologicalAssistant, Message};
+use iced::widget::{button, checkbox, column, row, scrollable, text, Column, Row};
+use iced::{alignment, Element, Length, Renderer, Theme};
+
+impl ArchaeologicalAssistant {
+    pub fn view_plan_menu(&self) -> impl Into<Element<Message, Theme, Renderer>> {
+        let problems = self
+            .plan_problems
+            .iter()
+            .map(|problem| {
+                Element::from(
+                    row![
+                        text(problem.text(&self.settings.current_language)).width(Length::Fill),
+                        button(self.tr("go to")).on_press_maybe(
+                            problem
+                                .row()
+                                .map(|row| Message::Records(RecordsMessage::JumpTo(row)))
+                        )
+                    ]
+                    .spacing(5)
+                    .align_y(alignment::Vertical::Center),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let import_report = self.import_report.as_ref().map(|report| {
+            let index_format = &self.settings.index_format;
+            column![
+                text(format!(
+                    "{}: {}",
+                    self.tr("Overwritten coordinates"),
+                    report
+                        .overwritten
+                        .iter()
+                        .map(|index| index_format.format(*index))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )),
+                text(format!(
+                    "{}: {}",
+                    self.tr("Points without a find"),
+                    report.unmatched.join(", ")
+                )),
+                text(format!(
+                    "{}: {}",
+                    self.tr("Finds without a point"),
+                    report
+                        .without_point
+                        .iter()
+                        .map(|index| index_format.format(*index))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ))
+            ]
+            .spacing(5)
+        });
+
+        column![
+            row![
+                button(self.tr("import total station file")).on_press(Message::ImportStation),
+                checkbox(self.tr("create records for unknown points"), self.create_missing)
+                    .on_toggle(Message::SetCreateMissing)
+            ]
+            .spacing(12)
+            .align_y(alignment::Vertical::Center),
+            text(fill(
+                self.tr("{finds} finds with coordinates, {problems} problems, {grid}"),
+                &[
+                    ("finds", self.plan.len().to_string()),
+                    ("problems", self.plan_problems.len().to_string()),
+                    (
+                        "grid",
+                        if self.settings.grid.epsg == 0 {
+                            self.tr("local grid").to_string()
+                        } else {
+                            format!("EPSG:{}", self.settings.grid.epsg)
+                        }
+                    ),
+                ],
+            )),
+            Row::with_children(PlanFormat::ALL.into_iter().map(|format| {
+                button(text(format!("{} {}", self.tr("export"), format)))
+                    .on_press(Message::ExportPlan(format))
+                    .into()
+            }))
+            .push(text(self.plan_message.clone().unwrap_or_default()))
+            .spacing(12)
+            .align_y(alignment::Vertical::Center)
+        ]
+        .push_maybe(import_report)
+        .push(scrollable(Column::with_children(problems).spacing(5)).height(Length::Fill))
+        .spacing(12)
+    }
+}
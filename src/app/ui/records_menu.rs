@@ -0,0 +1,233 @@
+use crate::app::data_base::index::Index;
+use crate::app::data_base::record::Record;
+use crate::app::data_base::report::fill;
+use crate::app::data_base::sync::Operation;
+use crate::app::data_base::DataBase;
+use crate::app::settings::{FieldType, Settings};
+use crate::app::{ArchaeologicalAssistant, Message};
+use iced::widget::{button, column, container, image, row, scrollable, text, Column, Row};
+use iced::{alignment, Element, Length, Renderer, Theme};
+
+#[derive(Debug, Clone)]
+pub enum RecordsMessage {
+    Select(u32),
+    /// Opens the record of a row from another tab.
+    JumpTo(u32),
+}
+
+#[derive(Default)]
+pub struct RecordsData {
+    pub records: Vec<Record>,
+    /// The row of the selected record.
+    pub selected: Option<u32>,
+    /// The logged operations of the selected record.
+    pub history: Vec<Operation>,
+}
+
+impl RecordsData {
+    pub fn selected_index(&self) -> Option<Index> {
+        self.records
+            .iter()
+            .find(|record| Some(record.row) == self.selected)
+            .map(|record| record.index)
+    }
+
+    fn load_history(&mut self, settings: &Settings) {
+        self.history = match self.selected_index() {
+            // the operation log of a server is not shared with its clients
+            Some(index) if !settings.api.is_client() => {
+                DataBase::from(&*settings.path_to_db).history(index)
+            }
+            _ => vec![],
+        };
+    }
+
+    pub fn update(&mut self, message: RecordsMessage, settings: &Settings) {
+        match message {
+            RecordsMessage::Select(row) | RecordsMessage::JumpTo(row) => {
+                self.selected = Some(row);
+                self.load_history(settings);
+            }
+        }
+    }
+}
+
+impl ArchaeologicalAssistant {
+    fn view_record_button(&self, record: &Record) -> Element<Message, Theme, Renderer> {
+        let is_selected = self.records_data.selected == Some(record.row);
+        button(text(format!(
+            "{}{}  {}",
+            if record.is_sub_record() { "    " } else { "" },
+            self.settings.index_format.format(record.index),
+            record.data.get(1).cloned().unwrap_or_default()
+        )))
+        .on_press(Message::Records(RecordsMessage::Select(record.row)))
+        .width(Length::Fill)
+        .style(move |theme, status| {
+            if is_selected {
+                button::primary(theme, status)
+            } else {
+                button::text(theme, status)
+            }
+        })
+        .into()
+    }
+
+    /// The linked finds as links to their records.
+    fn view_references(&self, value: &str) -> Element<Message, Theme, Renderer> {
+        let index_format = &self.settings.index_format;
+        Row::with_children(
+            value
+                .split([';', ','])
+                .filter(|reference| !reference.trim().is_empty())
+                .map(|reference| {
+                    let row = index_format.parse(reference).and_then(|index| {
+                        self.records_data
+                            .records
+                            .iter()
+                            .find(|record| record.index == index)
+                            .map(|record| record.row)
+                    });
+                    button(text(reference.trim().to_string()))
+                        .on_press_maybe(
+                            row.map(|row| Message::Records(RecordsMessage::Select(row))),
+                        )
+                        .style(button::text)
+                        .into()
+                }),
+        )
+        .spacing(5)
+        .into()
+    }
+
+    fn view_record(&self) -> Column<Message, Theme, Renderer> {
+        let Some(row) = self.records_data.selected else {
+            return column![text(self.tr("Select a record"))];
+        };
+        let Some(record) = self.records_data.records.iter().find(|record| record.row == row) else {
+            return column![text(fill(
+                self.tr("Row {row} does not contain a valid index"),
+                &[("row", row.to_string())]
+            ))];
+        };
+
+        let names = self.field_names();
+        let fields = names.into_iter().zip(&record.data).enumerate().map(|(id, (name, value))| {
+            let is_reference = id > 0
+                && self.settings.fields.get(id - 1).map(|field| field.field_type)
+                    == Some(FieldType::Reference);
+            Element::from(
+                row![
+                    text(name).align_x(alignment::Horizontal::Right).width(100),
+                    if is_reference {
+                        self.view_references(value)
+                    } else {
+                        text(value.clone()).into()
+                    }
+                ]
+                .spacing(5),
+            )
+        });
+        let related = self
+            .records_data
+            .records
+            .iter()
+            .filter(|related| {
+                related.index.number == record.index.number && related.row != record.row
+            })
+            .map(|related| self.view_record_button(related))
+            .collect::<Vec<_>>();
+
+        let photos = record
+            .photos
+            .iter()
+            .map(|photo| {
+                Element::from(
+                    column![
+                        image(self.settings.path_to_media.join(photo))
+                            .width(120)
+                            .height(120),
+                        text(photo.clone()).size(12)
+                    ]
+                    .spacing(2),
+                )
+            })
+            .collect::<Vec<_>>();
+        let history = self
+            .records_data
+            .history
+            .iter()
+            .map(|operation| {
+                Element::from(text(format!(
+                    "{}  {}  {}",
+                    operation.time, operation.kind, operation.recorder
+                )))
+            })
+            .collect::<Vec<_>>();
+
+        column![
+            text(self.settings.index_format.format(record.index)).size(24),
+            Column::with_children(fields).spacing(12),
+        ]
+        .push_maybe((!record.audit.is_empty()).then(|| {
+            text(fill(
+                self.tr("recorded by {recorder}, created {created}, modified {modified}"),
+                &[
+                    ("recorder", record.audit.recorder.clone()),
+                    ("created", record.audit.created.clone()),
+                    ("modified", record.audit.modified.clone()),
+                ],
+            ))
+        }))
+        .push(
+            column![
+                row![
+                    text(self.tr("Photos")),
+                    button(self.tr("attach")).on_press(Message::AttachPhotos),
+                    text(self.photo_status.clone().unwrap_or_default())
+                ]
+                .spacing(12)
+                .align_y(alignment::Vertical::Center),
+                Row::with_children(photos).spacing(5).wrap()
+            ]
+            .spacing(5),
+        )
+        .push_maybe((!history.is_empty()).then(|| {
+            column![
+                text(self.tr("History")),
+                Column::with_children(history).spacing(2)
+            ]
+            .spacing(5)
+        }))
+        .push_maybe((!related.is_empty()).then(|| {
+            column![
+                text(if record.is_sub_record() {
+                    self.tr("Parent and other fragments")
+                } else {
+                    self.tr("Fragments")
+                }),
+                Column::with_children(related).spacing(2)
+            ]
+            .spacing(5)
+        }))
+        .spacing(12)
+    }
+
+    pub fn view_records_menu(&self) -> impl Into<Element<Message, Theme, Renderer>> {
+        row![
+            scrollable(
+                Column::with_children(
+                    self.records_data
+                        .records
+                        .iter()
+                        .map(|record| self.view_record_button(record))
+                )
+                .spacing(2)
+            )
+            .width(300)
+            .height(Length::Fill),
+            container(self.view_record()).padding(10).width(Length::Fill)
+        ]
+        .spacing(5)
+    }
+}
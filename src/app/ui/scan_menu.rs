@@ -0,0 +1,226 @@
+use crate::app::client;
+use crate::app::data_base::index::Index;
+use crate::app::data_base::record::Record;
+use crate::app::data_base::report::fill;
+use crate::app::data_base::table::Table;
+use crate::app::data_base::DataBase;
+use crate::app::i18n::tr;
+use crate::app::settings::Settings;
+use crate::app::{ArchaeologicalAssistant, Message};
+use iced::widget::{button, column, pick_list, row, scrollable, text, text_input, Column};
+use iced::{alignment, Element, Length, Renderer, Task, Theme};
+
+#[derive(Debug, Clone)]
+pub enum ScanMessage {
+    SetInput(String),
+    Scan,
+    /// The record of a scanned index, as the server answered.
+    Loaded(Index, Result<Option<Record>, String>),
+    Clear,
+    SetBulkField(String),
+    SetBulkValue(String),
+    ApplyBulk,
+}
+
+#[derive(Default)]
+pub struct ScanData {
+    pub input: String,
+    /// Records scanned since the list was cleared, the last one is shown.
+    pub scanned: Vec<Record>,
+    pub status: Option<String>,
+    pub bulk_field: Option<String>,
+    pub bulk_value: String,
+}
+
+impl ScanData {
+    fn scan(&mut self, settings: &Settings) -> Task<Message> {
+        let scan = std::mem::take(&mut self.input);
+        let Some(index) = settings.index_format.find(&scan) else {
+            let language = &settings.current_language;
+            self.status = Some(fill(
+                tr(language, "\"{scan}\" does not contain an index"),
+                &[("scan", scan.trim().to_string())],
+            ));
+            return Task::none();
+        };
+        if settings.api.is_client() {
+            let settings = settings.clone();
+            return Task::perform(
+                client::run(move || client::record(&settings, index)),
+                move |record| Message::Scan(ScanMessage::Loaded(index, record)),
+            );
+        }
+        let data_base = DataBase::from(&*settings.path_to_db);
+        let sheet = data_base.get_sheet();
+        let record = sheet
+            .get_row_index_from_index(index, &settings.index_format)
+            .and_then(|row| {
+                sheet.get_record(row, settings.fields.len() + 1, &settings.index_format)
+            });
+        self.add(index, record, settings);
+        Task::none()
+    }
+
+    fn add(&mut self, index: Index, record: Option<Record>, settings: &Settings) {
+        match record {
+            Some(record) => {
+                self.status = None;
+                self.scanned.retain(|scanned| scanned.index != record.index);
+                self.scanned.push(record);
+            }
+            None => {
+                let language = &settings.current_language;
+                self.status = Some(fill(
+                    tr(language, "The record {index} does not exist"),
+                    &[("index", settings.index_format.format(index))],
+                ))
+            }
+        }
+    }
+
+    fn apply_bulk(&mut self, settings: &Settings) {
+        let Some(field) = &self.bulk_field else {
+            return;
+        };
+        let Some(id) = settings.fields.iter().position(|other| other.name == *field) else {
+            return;
+        };
+        let language = &settings.current_language;
+        if settings.api.is_client() {
+            self.status =
+                Some(tr(language, "Bulk updates can only be made on the server").to_string());
+            return;
+        }
+        let indices = self.scanned.iter().map(|record| record.index).collect::<Vec<_>>();
+        let updated = {
+            let _lock = DataBase::lock();
+            DataBase::update_field(
+                &settings.path_to_db,
+                &indices,
+                id + 1,
+                &self.bulk_value,
+                &settings.fields,
+                &settings.index_format,
+                settings.audit_recorder(),
+            )
+        };
+        for record in self.scanned.iter_mut() {
+            if let Some(value) = record.data.get_mut(id + 1) {
+                if updated.contains(&record.index) {
+                    *value = self.bulk_value.clone();
+                }
+            }
+        }
+        self.status = Some(fill(
+            tr(language, "{field} = \"{value}\" set for {count} records"),
+            &[
+                ("field", field.clone()),
+                ("value", self.bulk_value.clone()),
+                ("count", updated.len().to_string()),
+            ],
+        ));
+    }
+
+    pub fn update(&mut self, message: ScanMessage, settings: &Settings) -> Task<Message> {
+        match message {
+            ScanMessage::SetInput(input) => self.input = input,
+            ScanMessage::Scan => return self.scan(settings),
+            ScanMessage::Loaded(index, record) => match record {
+                Ok(record) => self.add(index, record, settings),
+                Err(error) => self.status = Some(error),
+            },
+            ScanMessage::Clear => {
+                self.scanned.clear();
+                self.status = None;
+            }
+            ScanMessage::SetBulkField(field) => self.bulk_field = Some(field),
+            ScanMessage::SetBulkValue(value) => self.bulk_value = value,
+            ScanMessage::ApplyBulk => self.apply_bulk(settings),
+        }
+        Task::none()
+    }
+}
+
+impl ArchaeologicalAssistant {
+    fn view_scanned_record(&self) -> Column<Message, Theme, Renderer> {
+        let Some(record) = self.scan_data.scanned.last() else {
+            return column![text(self.tr("Scan a find tag"))];
+        };
+        let fields = self.field_names().into_iter().zip(&record.data).map(|(name, value)| {
+            Element::from(
+                row![
+                    text(name).align_x(alignment::Horizontal::Right).width(100),
+                    text(value.clone())
+                ]
+                .spacing(5),
+            )
+        });
+
+        column![
+            text(self.settings.index_format.format(record.index)).size(24),
+            Column::with_children(fields).spacing(12),
+        ]
+        .spacing(12)
+    }
+
+    pub fn view_scan_menu(&self) -> impl Into<Element<Message, Theme, Renderer>> {
+        let scanned = self
+            .scan_data
+            .scanned
+            .iter()
+            .rev()
+            .map(|record| {
+                Element::from(text(format!(
+                    "{}  {}",
+                    self.settings.index_format.format(record.index),
+                    record.data.get(1).cloned().unwrap_or_default()
+                )))
+            })
+            .collect::<Vec<_>>();
+
+        column![
+            text_input(self.tr("scan a find tag"), &self.scan_data.input)
+                .on_input(|input| Message::Scan(ScanMessage::SetInput(input)))
+                .on_submit(Message::Scan(ScanMessage::Scan)),
+            text(self.scan_data.status.clone().unwrap_or_default()),
+            row![
+                column![
+                    row![
+                        text(format!("{}: {}", self.tr("Scanned"), self.scan_data.scanned.len())),
+                        button(self.tr("clear")).on_press(Message::Scan(ScanMessage::Clear))
+                    ]
+                    .spacing(12)
+                    .align_y(alignment::Vertical::Center),
+                    scrollable(Column::with_children(scanned).spacing(2)).height(Length::Fill)
+                ]
+                .spacing(5)
+                .width(300),
+                self.view_scanned_record().width(Length::Fill)
+            ]
+            .spacing(12)
+            .height(Length::Fill),
+            row![
+                text(self.tr("set")),
+                pick_list(
+                    self.settings
+                        .fields
+                        .iter()
+                        .map(|field| field.name.clone())
+                        .collect::<Vec<_>>(),
+                    self.scan_data.bulk_field.clone(),
+                    |field| Message::Scan(ScanMessage::SetBulkField(field))
+                ),
+                text(self.tr("to")),
+                text_input("", &self.scan_data.bulk_value)
+                    .on_input(|value| Message::Scan(ScanMessage::SetBulkValue(value))),
+                button(self.tr("apply to scanned")).on_press_maybe(
+                    (self.scan_data.bulk_field.is_some() && !self.scan_data.scanned.is_empty())
+                        .then_some(Message::Scan(ScanMessage::ApplyBulk))
+                )
+            ]
+            .spacing(12)
+            .align_y(alignment::Vertical::Center)
+        ]
+        .spacing(12)
+    }
+}
@@ -1,14 +1,37 @@
-use iced::{Element, Renderer, Theme};
-use iced::widget::{combo_box, text, Column};
+use iced::{alignment, Color, Element, Renderer, Theme};
+use iced::widget::{
+    button, checkbox, combo_box, pick_list, radio, row, scrollable, text, text_input, Column, Row,
+};
 use crate::app::{ArchaeologicalAssistant, Message};
-use crate::app::settings::MessageSettings;
+use crate::app::data_base::index::Index;
+use crate::app::i18n::Language;
+use crate::app::settings::insert_methods::InsertMethodsMessage;
+use crate::app::settings::api_settings::NetworkMode;
+use crate::app::settings::shortcut_settings::{ShortcutAction, ShortcutSettings};
+use crate::app::settings::{FieldType, MessageSettings};
 
 impl ArchaeologicalAssistant {
     pub fn view_settings_menu(&self) -> impl Into<Element<Message, Theme, Renderer>> {
-        let menu_settings = vec![
-            text("Base").into(),
-            Self::create_path_param(
-                "path to db",
+        let language = &self.settings.current_language;
+        let reservations = self
+            .reservations
+            .iter()
+            .enumerate()
+            .map(|(i, reservation)| {
+                Element::from(
+                    row![
+                        text(reservation.text(&self.settings.current_language)),
+                        button(self.tr("remove")).on_press(Message::RemoveReservation(i))
+                    ]
+                    .spacing(12),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let mut menu_settings = vec![
+            text(self.tr("Base")).into(),
+            self.create_path_param(
+                self.tr("path to db"),
                 self.settings
                     .path_to_db
                     .to_str()
@@ -23,9 +46,238 @@ impl ArchaeologicalAssistant {
                     ("opendocument spreadsheets", &["ods"]),
                 ],
             ),
-            text("Print Settings").into(),
-            Self::create_path_param(
-                "path to font",
+            Self::create_1_param(
+                self.tr("photos folder"),
+                &self.settings.path_to_media.to_string_lossy(),
+                |path| Message::SetSettings(MessageSettings::SetPathToMedia(path)),
+            ),
+            Self::create_1_param(
+                self.tr("camera folder"),
+                &self.settings.camera_folder.to_string_lossy(),
+                |path| Message::SetSettings(MessageSettings::SetCameraFolder(path)),
+            ),
+            Self::create_param(
+                self.tr("camera ingest"),
+                checkbox(self.tr("attach new camera photos"), self.settings.camera_ingest)
+                    .on_toggle(|ingest| {
+                        Message::SetSettings(MessageSettings::SetCameraIngest(ingest))
+                    })
+                    .into(),
+            ),
+            Self::create_param(
+                self.tr("register order"),
+                row![
+                    checkbox(
+                        self.tr("insert at the sorted position"),
+                        self.settings.insert_methods_data.sorted_insert
+                    )
+                    .on_toggle(|sorted_insert| Message::SetInsertMethodsData(
+                        InsertMethodsMessage::SortedInsert(sorted_insert)
+                    )),
+                    button(self.tr("sort register by index")).on_press(Message::SortDataBase)
+                ]
+                .spacing(12)
+                .into(),
+            ),
+            Self::create_param(
+                self.tr("reserved ranges"),
+                Column::with_children(reservations)
+                    .push(
+                        row![
+                            text_input(self.tr("first index"), &self.reservation_data[0])
+                                .on_input(|str| Message::SetReservationData(str, 0)),
+                            text_input(self.tr("last index"), &self.reservation_data[1])
+                                .on_input(|str| Message::SetReservationData(str, 1)),
+                            text_input(self.tr("team"), &self.reservation_data[2])
+                                .on_input(|str| Message::SetReservationData(str, 2)),
+                            button(self.tr("reserve")).on_press(Message::AddReservation)
+                        ]
+                        .spacing(12),
+                    )
+                    .push(text(self.register_message.clone().unwrap_or_default()))
+                    .spacing(5)
+                    .into(),
+            ),
+            text(self.tr("Fields")).into(),
+            Self::create_param(
+                self.tr("required fields"),
+                Row::with_children(self.settings.fields.iter().enumerate().map(|(i, field)| {
+                    Element::from(
+                        checkbox(field.display_name(language).to_string(), field.required)
+                            .on_toggle(move |required| {
+                                Message::SetSettings(MessageSettings::SetFieldRequired(i, required))
+                            }),
+                    )
+                }))
+                .spacing(12)
+                .into(),
+            ),
+            Self::create_param(
+                self.tr("field names in this language"),
+                Row::with_children(self.settings.fields.iter().enumerate().map(|(i, field)| {
+                    text_input(&field.name, field.translation(language).unwrap_or_default())
+                        .on_input(move |name| {
+                            Message::SetSettings(MessageSettings::SetFieldTranslation(i, name))
+                        })
+                        .into()
+                }))
+                .spacing(12)
+                .into(),
+            ),
+            Self::create_param(
+                self.tr("new field"),
+                row![
+                    text_input("", &self.settings.input_new_field)
+                        .on_input(|name| Message::SetSettings(MessageSettings::SetNewField(name)))
+                        .on_submit(Message::SetSettings(MessageSettings::AddField(FieldType::Text))),
+                    button(self.tr("add")).on_press(Message::SetSettings(
+                        MessageSettings::AddField(FieldType::Text)
+                    )),
+                    button(self.tr("add coordinates")).on_press(Message::SetSettings(
+                        MessageSettings::AddField(FieldType::Coordinates)
+                    )),
+                    button(self.tr("add reference")).on_press(Message::SetSettings(
+                        MessageSettings::AddField(FieldType::Reference)
+                    ))
+                ]
+                .spacing(12)
+                .into(),
+            ),
+            text(self.tr("Find status")).into(),
+            Self::create_1_param(
+                self.tr("status field"),
+                &self.settings.status.field,
+                |field| Message::SetSettings(MessageSettings::SetStatusField(field)),
+            ),
+            Self::create_1_param(
+                self.tr("group by field"),
+                &self.settings.status.group_field,
+                |field| Message::SetSettings(MessageSettings::SetStatusGroupField(field)),
+            ),
+        ];
+        menu_settings.extend(self.settings.status.statuses.iter().enumerate().map(|(i, status)| {
+            Self::create_param(
+                &status.name,
+                row![
+                    text(self.tr("next:")),
+                    text_input("", &status.input_next).on_input(move |next| {
+                        Message::SetSettings(MessageSettings::SetStatusNext(i, next))
+                    }),
+                    button(self.tr("remove"))
+                        .on_press(Message::SetSettings(MessageSettings::RemoveStatus(i)))
+                ]
+                .spacing(12)
+                .align_y(alignment::Vertical::Center)
+                .into(),
+            )
+        }));
+        menu_settings.extend([
+            Self::create_param(
+                self.tr("new status"),
+                row![
+                    text_input("", &self.settings.status.input_new_status)
+                        .on_input(|name| Message::SetSettings(MessageSettings::SetNewStatus(name)))
+                        .on_submit(Message::SetSettings(MessageSettings::AddStatus)),
+                    button(self.tr("add"))
+                        .on_press(Message::SetSettings(MessageSettings::AddStatus))
+                ]
+                .spacing(12)
+                .into(),
+            ),
+            text(self.tr("Daily report")).into(),
+            Self::create_1_param(
+                self.tr("group by fields"),
+                &self.settings.report.input_group_fields,
+                |fields| Message::SetSettings(MessageSettings::SetReportGroupFields(fields)),
+            ),
+            self.create_path_param(
+                self.tr("template (empty for the built-in one)"),
+                &self.settings.report.template.to_string_lossy(),
+                |path| Message::SetSettings(MessageSettings::SetReportTemplate(path)),
+                |path| Message::SetSettings(MessageSettings::SelectReportTemplate(path)),
+                vec![("markdown", &["md", "txt"])],
+            ),
+            text(self.tr("Contexts")).into(),
+            Self::create_1_param(
+                self.tr("find context field"),
+                &self.settings.contexts.find_field,
+                |field| Message::SetSettings(MessageSettings::SetContextFindField(field)),
+            ),
+            Self::create_1_param(
+                self.tr("context fields"),
+                &self.settings.contexts.input_fields,
+                |fields| Message::SetSettings(MessageSettings::SetContextFields(fields)),
+            ),
+            text(self.tr("Plan grid")).into(),
+            Self::create_1_param(
+                self.tr("EPSG (0 for a local grid)"),
+                &self.settings.grid.input_epsg,
+                |epsg| Message::SetSettings(MessageSettings::SetGridEpsg(epsg)),
+            ),
+            Self::create_2_param(
+                self.tr("origin of A1 (x, y)"),
+                &self.settings.grid.input_origin,
+                |origin, axis| Message::SetSettings(MessageSettings::SetGridOrigin(origin, axis)),
+            ),
+            Self::create_1_param(
+                self.tr("square size"),
+                &self.settings.grid.input_square_size,
+                |size| Message::SetSettings(MessageSettings::SetGridSquareSize(size)),
+            ),
+            Self::create_1_param(
+                self.tr("square field"),
+                &self.settings.grid.square_field,
+                |field| Message::SetSettings(MessageSettings::SetGridSquareField(field)),
+            ),
+            Self::create_param(
+                self.tr("square names"),
+                checkbox(
+                    self.tr("letters along x, numbers along y"),
+                    self.settings.grid.letters_along_x,
+                )
+                    .on_toggle(|letters_along_x| {
+                        Message::SetSettings(MessageSettings::SetGridLettersAlongX(letters_along_x))
+                    })
+                    .into(),
+            ),
+            text(self.tr("Index")).into(),
+            Self::create_1_param(
+                self.tr("prefix ({site}, {year}, {trench})"),
+                &self.settings.index_format.prefix_template,
+                |prefix| Message::SetSettings(MessageSettings::SetIndexPrefix(prefix)),
+            ),
+            Self::create_1_param(
+                self.tr("site"),
+                &self.settings.index_format.site,
+                |site| Message::SetSettings(MessageSettings::SetIndexSite(site)),
+            ),
+            Self::create_1_param(
+                self.tr("year"),
+                &self.settings.index_format.year,
+                |year| Message::SetSettings(MessageSettings::SetIndexYear(year)),
+            ),
+            Self::create_1_param(
+                self.tr("trench"),
+                &self.settings.index_format.trench,
+                |trench| Message::SetSettings(MessageSettings::SetIndexTrench(trench)),
+            ),
+            Self::create_1_param(
+                self.tr("zero padding"),
+                &self.settings.index_format.input_padding,
+                |padding| Message::SetSettings(MessageSettings::SetIndexPadding(padding)),
+            ),
+            Self::create_1_param(
+                self.tr("sub-number separator"),
+                &self.settings.index_format.sub_separator,
+                |separator| Message::SetSettings(MessageSettings::SetIndexSubSeparator(separator)),
+            ),
+            Self::create_param(
+                self.tr("example"),
+                text(self.settings.index_format.format(Index::new(157, Some(2)))).into(),
+            ),
+            text(self.tr("Print Settings")).into(),
+            self.create_path_param(
+                self.tr("path to font"),
                 self.settings
                     .print_settings
                     .font
@@ -36,43 +288,156 @@ impl ArchaeologicalAssistant {
                 vec![("font files", &["ttf"])],
             ),
             Self::create_2_param(
-                "size",
+                self.tr("size"),
                 &self.settings.print_settings.input_number.size,
                 |size, axis| Message::SetSettings(MessageSettings::SetSize(size, axis)),
             ),
             Self::create_2_param(
-                "size image",
+                self.tr("size image"),
                 &self.settings.print_settings.input_number.size_image,
                 |size, axis| Message::SetSettings(MessageSettings::SetSizeImage(size, axis)),
             ),
             Self::create_2_param(
-                "position image",
+                self.tr("position image"),
                 &self.settings.print_settings.input_number.pos_image,
                 |position, axis| Message::SetSettings(MessageSettings::SetPositionImage(position, axis)),
             ),
             Self::create_1_param(
-                "text size",
+                self.tr("text size"),
                 &self.settings.print_settings.input_number.text_size,
                 |size| Message::SetSettings(MessageSettings::SetTextSize(size)),
             ),
             Self::create_1_param(
-                "Y position of text",
+                self.tr("Y position of text"),
                 &self.settings.print_settings.input_number.height_text,
                 |height| Message::SetSettings(MessageSettings::SetHeightText(height)),
             ),
-            text("Application").into(),
+            text(self.tr("API")).into(),
+            Self::create_param(
+                self.tr("local HTTP API"),
+                row![
+                    checkbox(self.tr("enabled"), self.settings.api.enabled).on_toggle(|enabled| {
+                        Message::SetSettings(MessageSettings::SetApiEnabled(enabled))
+                    }),
+                    text(self.api_error.clone().unwrap_or_default())
+                ]
+                .spacing(12)
+                .into(),
+            ),
+            Self::create_1_param(
+                self.tr("port"),
+                &self.settings.api.input_port,
+                |port| Message::SetSettings(MessageSettings::SetApiPort(port)),
+            ),
             Self::create_param(
-                "Theme",
+                self.tr("network"),
+                row![
+                    radio(
+                        self.tr("standalone"),
+                        NetworkMode::Standalone,
+                        Some(self.settings.api.mode),
+                        |mode| Message::SetSettings(MessageSettings::SetNetworkMode(mode)),
+                    ),
+                    radio(
+                        self.tr("server"),
+                        NetworkMode::Server,
+                        Some(self.settings.api.mode),
+                        |mode| Message::SetSettings(MessageSettings::SetNetworkMode(mode)),
+                    ),
+                    radio(
+                        self.tr("client"),
+                        NetworkMode::Client,
+                        Some(self.settings.api.mode),
+                        |mode| Message::SetSettings(MessageSettings::SetNetworkMode(mode)),
+                    ),
+                ]
+                .spacing(12)
+                .into(),
+            ),
+            Self::create_param(
+                self.tr("local network"),
+                checkbox(
+                    self.tr("accept connections, anyone on the network can create records"),
+                    self.settings.api.accept_network,
+                )
+                .on_toggle(|accept_network| {
+                    Message::SetSettings(MessageSettings::SetAcceptNetwork(accept_network))
+                })
+                .into(),
+            ),
+            Self::create_1_param(
+                self.tr("server address"),
+                &self.settings.api.server_address,
+                |address| Message::SetSettings(MessageSettings::SetServerAddress(address)),
+            ),
+            text(self.tr("Profile")).into(),
+            Self::create_1_param(
+                self.tr("recorder name"),
+                &self.settings.recorder,
+                |recorder| Message::SetSettings(MessageSettings::SetRecorder(recorder)),
+            ),
+            Self::create_param(
+                self.tr("audit columns"),
+                checkbox(self.tr("record who and when"), self.settings.audit)
+                    .on_toggle(|audit| Message::SetSettings(MessageSettings::SetAudit(audit)))
+                    .into(),
+            ),
+            text(self.tr("Application")).into(),
+            Self::create_param(
+                self.tr("Language"),
+                pick_list(
+                    Language::ALL,
+                    Language::from_code(language),
+                    |language| Message::SetSettings(MessageSettings::SetLanguage(language)),
+                )
+                .into(),
+            ),
+            Self::create_param(
+                self.tr("Theme"),
                 combo_box(
                     &self.state_themes,
-                    "Select theme",
+                    self.tr("Select theme"),
                     Some(&self.settings.theme),
                     |theme| Message::SetSettings(MessageSettings::SetTheme(theme)),
                 )
                     .into(),
             ),
-        ];
+        ]);
+        menu_settings.push(text(self.tr("Keyboard")).into());
+        menu_settings.extend(
+            [
+                (self.tr("create"), ShortcutAction::Create),
+                (self.tr("Start/End"), ShortcutAction::StartEnd),
+                (self.tr("Input"), ShortcutAction::Input),
+                (self.tr("Auto insert"), ShortcutAction::AutoInsert),
+                (self.tr("Fragments"), ShortcutAction::Fragments),
+                (self.tr("today's date"), ShortcutAction::Today),
+                (self.tr("quantity up"), ShortcutAction::QuantityUp),
+                (self.tr("quantity down"), ShortcutAction::QuantityDown),
+            ]
+            .into_iter()
+            .map(|(name, action)| {
+                let binding = self.settings.shortcuts.binding(action);
+                Self::create_param(
+                    name,
+                    row![
+                        text_input("", binding).on_input(move |binding| {
+                            Message::SetSettings(MessageSettings::SetShortcut(action, binding))
+                        }),
+                        text(if ShortcutSettings::is_valid(binding) {
+                            ""
+                        } else {
+                            self.tr("unknown key")
+                        })
+                        .color(Color::new(1., 1., 0., 1.))
+                    ]
+                    .spacing(12)
+                    .align_y(alignment::Vertical::Center)
+                    .into(),
+                )
+            }),
+        );
 
-        Column::with_children(menu_settings).spacing(12)
+        scrollable(Column::with_children(menu_settings).spacing(12))
     }
 }
\ No newline at end of file
@@ -0,0 +1,70 @@
+use crate::app::data_base::report::fill;
+use crate::app::{ArchaeologicalAssistant, Message};
+use iced::widget::{
+    button, column, pick_list, progress_bar, row, scrollable, text, text_input, Column,
+};
+use iced::{alignment, Element, Length, Renderer, Theme};
+
+impl ArchaeologicalAssistant {
+    pub fn view_statistics_menu(&self) -> impl Into<Element<Message, Theme, Renderer>> {
+        let fields = ["Date".to_string()]
+            .into_iter()
+            .chain(self.settings.fields.iter().map(|field| field.name.clone()))
+            .collect::<Vec<_>>();
+        let max = self.statistics.iter().map(|(_, count)| *count).max().unwrap_or(1);
+        let bars = self.statistics.iter().map(|(value, count)| {
+            Element::from(
+                row![
+                    text(if value.is_empty() { self.tr("(empty)") } else { value.as_str() })
+                        .align_x(alignment::Horizontal::Right)
+                        .width(150),
+                    progress_bar(0.0..=max as f32, *count as f32)
+                        .width(Length::Fill)
+                        .height(16),
+                    text(count.to_string()).width(50)
+                ]
+                .spacing(12)
+                .align_y(alignment::Vertical::Center),
+            )
+        });
+
+        column![
+            text(format!(
+                "{} {}",
+                self.tr("Index coverage"),
+                self.coverage.text(&self.settings.current_language)
+            )),
+            row![
+                text(self.tr("daily report for")),
+                text_input(self.tr("date"), &self.report_date)
+                    .on_input(Message::SetReportDate)
+                    .width(150),
+                button("Markdown").on_press(Message::ExportReport("md")),
+                button("PDF").on_press(Message::ExportReport("pdf"))
+            ]
+            .spacing(12)
+            .align_y(alignment::Vertical::Center),
+            row![
+                text(self.tr("count by")),
+                pick_list(
+                    fields,
+                    Some(self.statistics_field.clone()),
+                    Message::SetStatisticsField
+                ),
+                text(fill(
+                    self.tr("{count} records"),
+                    &[(
+                        "count",
+                        self.statistics.iter().map(|(_, count)| count).sum::<u32>().to_string()
+                    )]
+                )),
+                button(self.tr("export CSV")).on_press(Message::ExportStatistics),
+                text(self.statistics_message.clone().unwrap_or_default())
+            ]
+            .spacing(12)
+            .align_y(alignment::Vertical::Center),
+            scrollable(Column::with_children(bars).spacing(5)).height(Length::Fill)
+        ]
+        .spacing(12)
+    }
+}
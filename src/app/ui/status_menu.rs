@@ -0,0 +1,172 @@
+use crate::app::data_base::report::fill;
+use crate::app::data_base::status::{parse_ranges, StatusCounts, StatusTable};
+use crate::app::data_base::DataBase;
+use crate::app::i18n::tr;
+use crate::app::settings::Settings;
+use crate::app::{ArchaeologicalAssistant, Message};
+use iced::widget::{
+    button, column, container, pick_list, row, scrollable, text, text_input, Column, Row,
+};
+use iced::{alignment, Element, Length, Renderer, Theme};
+
+#[derive(Debug, Clone)]
+pub enum StatusMessage {
+    SetRanges(String),
+    SetTarget(String),
+    Change,
+}
+
+#[derive(Default)]
+pub struct StatusData {
+    pub ranges: String,
+    pub target: Option<String>,
+    pub counts: StatusCounts,
+    pub message: Option<String>,
+}
+
+impl StatusData {
+    pub fn reload(&mut self, settings: &Settings) {
+        self.counts = match settings.data_id(&settings.status.field) {
+            Some(status_id) => DataBase::from(&*settings.path_to_db)
+                .get_sheet()
+                .status_counts(
+                    status_id,
+                    settings.data_id(&settings.status.group_field),
+                    &settings.index_format,
+                ),
+            None => StatusCounts::new(),
+        };
+    }
+
+    fn change(&mut self, settings: &Settings) {
+        let Some(to) = self.target.clone() else {
+            return;
+        };
+        let language = &settings.current_language;
+        if settings.api.is_client() {
+            self.message =
+                Some(tr(language, "Statuses can only be changed on the server").to_string());
+            return;
+        }
+        let Some(status_id) = settings.data_id(&settings.status.field) else {
+            self.message = Some(fill(
+                tr(language, "There is no field \"{field}\", add it in the settings"),
+                &[("field", settings.status.field.clone())],
+            ));
+            return;
+        };
+        let ranges = match parse_ranges(&self.ranges, &settings.index_format) {
+            Ok(ranges) => ranges,
+            Err(error) => {
+                self.message = Some(error);
+                return;
+            }
+        };
+        let change = {
+            let _lock = DataBase::lock();
+            DataBase::change_status(
+                &settings.path_to_db,
+                &ranges,
+                status_id,
+                &to,
+                &settings.status,
+                &settings.index_format,
+                settings.audit_recorder(),
+            )
+        };
+        let values = [
+            ("count", change.changed.len().to_string()),
+            ("status", to),
+            (
+                "rejected",
+                change
+                    .rejected
+                    .iter()
+                    .map(|(index, from)| {
+                        format!(
+                            "{} ({})",
+                            settings.index_format.format(*index),
+                            if from.is_empty() { tr(language, "no status") } else { from.as_str() }
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ),
+        ];
+        self.message = Some(if change.rejected.is_empty() {
+            fill(tr(language, "{count} finds are {status} now"), &values)
+        } else {
+            fill(
+                tr(language, "{count} finds are {status} now, not allowed for: {rejected}"),
+                &values,
+            )
+        });
+        self.reload(settings);
+    }
+
+    pub fn update(&mut self, message: StatusMessage, settings: &Settings) {
+        match message {
+            StatusMessage::SetRanges(ranges) => self.ranges = ranges,
+            StatusMessage::SetTarget(target) => self.target = Some(target),
+            StatusMessage::Change => self.change(settings),
+        }
+    }
+}
+
+impl ArchaeologicalAssistant {
+    fn view_status_cell<'a>(value: impl ToString) -> Element<'a, Message, Theme, Renderer> {
+        container(text(value.to_string())).width(110).into()
+    }
+
+    pub fn view_status_menu(&self) -> impl Into<Element<Message, Theme, Renderer>> {
+        let statuses = self.settings.status.names();
+        let header = Row::with_children(
+            [Self::view_status_cell(&self.settings.status.group_field)]
+                .into_iter()
+                .chain(statuses.iter().map(Self::view_status_cell))
+                .chain([
+                    Self::view_status_cell(self.tr("no status")),
+                    Self::view_status_cell(self.tr("total")),
+                ]),
+        );
+        let rows = self.status_data.counts.iter().map(|(group, counts)| {
+            let count = |status: &str| counts.get(status).copied().unwrap_or(0);
+            Element::from(Row::with_children(
+                [Self::view_status_cell(group)]
+                    .into_iter()
+                    .chain(statuses.iter().map(|status| Self::view_status_cell(count(status))))
+                    .chain([
+                        Self::view_status_cell(count("")),
+                        Self::view_status_cell(counts.values().sum::<u32>()),
+                    ]),
+            ))
+        });
+
+        column![
+            row![
+                text(self.tr("finds")),
+                text_input(self.tr("e.g. 15-40, 52"), &self.status_data.ranges)
+                    .on_input(|ranges| Message::Status(StatusMessage::SetRanges(ranges)))
+                    .on_submit(Message::Status(StatusMessage::Change)),
+                text(self.tr("become")),
+                pick_list(statuses.clone(), self.status_data.target.clone(), |target| {
+                    Message::Status(StatusMessage::SetTarget(target))
+                }),
+                button(self.tr("change")).on_press_maybe(
+                    self.status_data
+                        .target
+                        .is_some()
+                        .then_some(Message::Status(StatusMessage::Change))
+                )
+            ]
+            .spacing(12)
+            .align_y(alignment::Vertical::Center),
+            text(self.status_data.message.clone().unwrap_or_default()),
+            scrollable(
+                Column::with_children([Element::from(header)].into_iter().chain(rows)).spacing(5)
+            )
+            .height(Length::Fill)
+        ]
+        .spacing(12)
+    }
+}
@@ -0,0 +1,132 @@
+use crate::app::data_base::sync::MergeReport;
+use crate::app::data_base::DataBase;
+use crate::app::i18n::tr;
+use crate::app::settings::{MessageSettings, Settings};
+use crate::app::{ArchaeologicalAssistant, Message};
+use iced::widget::{button, column, row, scrollable, text, Column};
+use iced::{alignment, Element, Length, Renderer, Theme};
+
+#[derive(Debug, Clone)]
+pub enum SyncMessage {
+    Merge,
+    /// Keeps this record or takes the other one for a conflict of the report.
+    ResolveConflict(usize, bool),
+}
+
+#[derive(Default)]
+pub struct SyncData {
+    pub merge_report: Option<MergeReport>,
+    pub message: Option<String>,
+}
+
+impl SyncData {
+    pub fn update(&mut self, message: SyncMessage, settings: &Settings) {
+        match message {
+            SyncMessage::Merge | SyncMessage::ResolveConflict(..) if settings.api.is_client() => {
+                let language = &settings.current_language;
+                self.merge_report = None;
+                self.message =
+                    Some(tr(language, "Registers can only be merged on the server").to_string())
+            }
+            SyncMessage::Merge => {
+                self.message = None;
+                if settings.path_to_sync.exists() {
+                    let _lock = DataBase::lock();
+                    let mut data_base = DataBase::from(&*settings.path_to_db);
+                    self.merge_report = Some(data_base.merge(
+                        &DataBase::from(&*settings.path_to_sync),
+                        &settings.fields,
+                        &settings.index_format,
+                        settings.audit_recorder(),
+                    ));
+                    data_base.save(&settings.path_to_db);
+                }
+            }
+            SyncMessage::ResolveConflict(id, take_other) => {
+                if let Some(report) = &mut self.merge_report {
+                    if id < report.conflicts.len() {
+                        let conflict = report.conflicts.remove(id);
+                        let _lock = DataBase::lock();
+                        let mut data_base = DataBase::from(&*settings.path_to_db);
+                        data_base.resolve(
+                            conflict,
+                            take_other,
+                            &settings.fields,
+                            &settings.index_format,
+                            settings.audit_recorder(),
+                        );
+                        data_base.save(&settings.path_to_db);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl ArchaeologicalAssistant {
+    fn view_conflict_side<'a>(&'a self, title: &'a str, data: &'a [String]) -> Column<'a, Message> {
+        Column::with_children(
+            [Element::from(text(title))]
+                .into_iter()
+                .chain(self.field_names().into_iter().enumerate().map(|(i, name)| {
+                    Element::from(text(format!(
+                        "{}: {}",
+                        name,
+                        data.get(i).map(|value| value.as_str()).unwrap_or_default()
+                    )))
+                })),
+        )
+        .spacing(5)
+        .width(Length::Fill)
+    }
+
+    pub fn view_sync_menu(&self) -> impl Into<Element<Message, Theme, Renderer>> {
+        let conflicts = self
+            .sync_data
+            .merge_report
+            .iter()
+            .flat_map(|report| report.conflicts.iter().enumerate())
+            .map(|(i, conflict)| {
+                Element::from(
+                    column![
+                        text(self.settings.index_format.format(conflict.index)),
+                        row![
+                            self.view_conflict_side(self.tr("this register"), &conflict.local),
+                            self.view_conflict_side(self.tr("other register"), &conflict.other),
+                        ]
+                        .spacing(12),
+                        row![
+                            button(self.tr("keep this"))
+                                .on_press(Message::Sync(SyncMessage::ResolveConflict(i, false))),
+                            button(self.tr("take other"))
+                                .on_press(Message::Sync(SyncMessage::ResolveConflict(i, true))),
+                        ]
+                        .spacing(12)
+                    ]
+                    .spacing(5),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        column![
+            self.create_path_param(
+                self.tr("register to merge"),
+                self.settings.path_to_sync.to_string_lossy(),
+                |path| Message::SetSettings(MessageSettings::SetPathToSync(path)),
+                |path| Message::SetSettings(MessageSettings::SelectSyncDb(path)),
+                vec![("excel files", &["xlsx", "xlsm"])],
+            ),
+            row![
+                button(self.tr("merge")).on_press(Message::Sync(SyncMessage::Merge)),
+                text(match (&self.sync_data.merge_report, &self.sync_data.message) {
+                    (Some(report), _) => report.text(&self.settings.current_language),
+                    (None, message) => message.clone().unwrap_or_default(),
+                })
+            ]
+            .spacing(12)
+            .align_y(alignment::Vertical::Center),
+            scrollable(Column::with_children(conflicts).spacing(20)).height(Length::Fill)
+        ]
+        .spacing(12)
+    }
+}
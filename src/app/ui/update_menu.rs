@@ -8,7 +8,7 @@ use iced_term::TerminalView;
 impl ArchaeologicalAssistant {
     pub fn view_update_menu(&self) -> impl Into<Element<Message, Theme, Renderer>> {
         column![
-            container(button("Update").on_press(Message::Update)).padding(10),
+            container(button(self.tr("Update")).on_press(Message::Update)).padding(10),
             container(TerminalView::show(&self.term).map(Message::Terminal))
                 .width(Length::Fill)
                 .height(Length::Fill)
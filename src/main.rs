@@ -4,6 +4,15 @@ use app::ArchaeologicalAssistant;
 use iced_aw::iced_fonts;
 
 pub fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    if !args.is_empty() {
+        if let Err(error) = app::cli::run(args) {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     iced::application(
         "Archaeological assistant",
         ArchaeologicalAssistant::update,